@@ -1,6 +1,9 @@
+use crate::units::Memory;
+use crate::validation::Severity;
 use color_eyre::eyre::{bail, eyre, Report as Error, Result, WrapErr};
 use log::debug;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use toml::{map::Map, Value};
 
@@ -17,6 +20,10 @@ pub struct GedentConfig {
     /// template name matches multiple `software/name` paths.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub software: Option<String>,
+    /// Path of each generated input with `{key}` placeholders, e.g.
+    /// `"{name}/{template}_{method}.{ext}"`. Defaults to `"{name}.{ext}"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename_pattern: Option<String>,
 }
 
 /// Chemistry parameters from the `[model]` config section.
@@ -49,6 +56,17 @@ pub struct ModelConfig {
     pub solvation_model: Option<String>,
 }
 
+/// Whether `[resources] mem` is per core or for the whole job.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemScope {
+    /// `mem` is memory per core (the default).
+    #[default]
+    PerCore,
+    /// `mem` is the total memory of the job.
+    Total,
+}
+
 /// Compute resource settings from the `[resources]` config section.
 ///
 /// Unknown fields in the config file are rejected with an error.
@@ -58,9 +76,109 @@ pub struct ResourcesConfig {
     /// Number of parallel processes / CPU cores.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub nprocs: Option<i64>,
-    /// Memory per core in MB.
+    /// Memory per core. Bare integers are MB; strings may carry a unit
+    /// (e.g. `"4GB"`, `"500MW"`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mem: Option<Memory>,
+    /// Whether `mem` is per core (default) or total. gedent converts it to
+    /// each program's convention (`mem_per_core`, `mem_total`, `mem_safe`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mem_scope: Option<MemScope>,
+}
+
+/// Target compute node from the `[node]` config section.
+///
+/// Limits left unset are not checked, unless `local = true` takes them from the
+/// machine gedent runs on. Unknown fields in the config file are rejected with
+/// an error.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NodeConfig {
+    /// Cores available to one job.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cores: Option<i64>,
+    /// Memory available to one job. Bare integers are MB; strings may carry a
+    /// unit (e.g. `"256GB"`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mem: Option<Memory>,
+    /// Check against the cores and memory of the machine gedent runs on,
+    /// for jobs run where they are generated.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub mem: Option<i64>,
+    pub local: Option<bool>,
+}
+
+/// Batch system a submit script is written for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, strum::Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum SchedulerKind {
+    /// SLURM (`sbatch`), scripts end in `.slurm`.
+    Slurm,
+    /// PBS Pro / Torque (`qsub`), scripts end in `.pbs`.
+    Pbs,
+    /// Sun/Univa Grid Engine (`qsub`), scripts end in `.sge`.
+    Sge,
+}
+
+/// Batch scheduler settings from the `[scheduler]` config section, used by
+/// `gedent gen --submit-script`.
+///
+/// Unknown fields in the config file are rejected with an error.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SchedulerConfig {
+    /// Which batch system to write scripts for.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<SchedulerKind>,
+    /// Partition (SLURM) or queue (PBS, SGE).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partition: Option<String>,
+    /// Account or project charged for the job.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    /// Wall-clock limit as `[D-]HH:MM[:SS]`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub walltime: Option<String>,
+    /// Environment modules loaded before the run (`module load ...`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modules: Option<Vec<String>>,
+    /// Program to run. Defaults to the usual executable of the template's
+    /// software (e.g. `orca`, `g16`); ORCA wants the full path for parallel runs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub program: Option<String>,
+}
+
+/// Validation policy from the `[validation]` config section.
+///
+/// Checks are named by code (`"G006"`) or name (`"close-contact"`); unknown
+/// ones are rejected when the policy is resolved. Unknown fields in the config
+/// file are rejected with an error.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidationConfig {
+    /// Checks whose findings are never reported.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disable: Option<Vec<String>>,
+    /// Severity overrides per check (`"error"` or `"warning"`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub severity: BTreeMap<String, Severity>,
+    /// Distance in Å below which two atoms are superposed (default 0.5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub superposed_distance: Option<f64>,
+    /// Fraction of the sum of covalent radii below which two atoms are
+    /// reported as a close contact (default 0.5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub close_contact_factor: Option<f64>,
+    /// Multiple of the sum of covalent radii below which two atoms count as
+    /// bonded when perceiving fragments (default 1.2).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bond_factor: Option<f64>,
+    /// Disconnected fragments allowed before warning (default 1).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fragments: Option<usize>,
+    /// Treat every warning as an error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
 }
 
 /// Fully resolved configuration, produced by merging the cascade chain.
@@ -77,6 +195,15 @@ pub struct Config {
     /// Compute resources (`[resources]` section).
     #[serde(default)]
     pub resources: ResourcesConfig,
+    /// Target compute node (`[node]` section).
+    #[serde(default)]
+    pub node: NodeConfig,
+    /// Batch scheduler for submit scripts (`[scheduler]` section).
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+    /// Validation policy (`[validation]` section).
+    #[serde(default)]
+    pub validation: ValidationConfig,
     /// Arbitrary Tera context variables (`[parameters]` section).
     #[serde(default)]
     pub parameters: Map<String, Value>,
@@ -90,6 +217,7 @@ pub struct Config {
 struct RawGedentConfig {
     default_extension: Option<String>,
     software: Option<String>,
+    filename_pattern: Option<String>,
 }
 
 /// All-optional config used to parse individual files in the cascade chain.
@@ -104,12 +232,19 @@ struct RawConfig {
     #[serde(default)]
     resources: ResourcesConfig,
     #[serde(default)]
+    node: NodeConfig,
+    #[serde(default)]
+    scheduler: SchedulerConfig,
+    #[serde(default)]
+    validation: ValidationConfig,
+    #[serde(default)]
     parameters: Map<String, Value>,
 }
 
 impl RawConfig {
     /// Merge `overlay` on top of `self`. All `Some` values in `overlay` win;
-    /// `None` values fall through from `self`.
+    /// `None` values fall through from `self`. `[parameters]` and
+    /// `[validation] severity` are merged key by key.
     fn merge(self, overlay: Self) -> Self {
         let model = ModelConfig {
             method: overlay.model.method.or(self.model.method),
@@ -123,6 +258,43 @@ impl RawConfig {
         let resources = ResourcesConfig {
             nprocs: overlay.resources.nprocs.or(self.resources.nprocs),
             mem: overlay.resources.mem.or(self.resources.mem),
+            mem_scope: overlay.resources.mem_scope.or(self.resources.mem_scope),
+        };
+        let node = NodeConfig {
+            cores: overlay.node.cores.or(self.node.cores),
+            mem: overlay.node.mem.or(self.node.mem),
+            local: overlay.node.local.or(self.node.local),
+        };
+        let scheduler = SchedulerConfig {
+            kind: overlay.scheduler.kind.or(self.scheduler.kind),
+            partition: overlay.scheduler.partition.or(self.scheduler.partition),
+            account: overlay.scheduler.account.or(self.scheduler.account),
+            walltime: overlay.scheduler.walltime.or(self.scheduler.walltime),
+            modules: overlay.scheduler.modules.or(self.scheduler.modules),
+            program: overlay.scheduler.program.or(self.scheduler.program),
+        };
+        let mut severity = self.validation.severity;
+        severity.extend(overlay.validation.severity);
+        let validation = ValidationConfig {
+            disable: overlay.validation.disable.or(self.validation.disable),
+            severity,
+            superposed_distance: overlay
+                .validation
+                .superposed_distance
+                .or(self.validation.superposed_distance),
+            close_contact_factor: overlay
+                .validation
+                .close_contact_factor
+                .or(self.validation.close_contact_factor),
+            bond_factor: overlay
+                .validation
+                .bond_factor
+                .or(self.validation.bond_factor),
+            max_fragments: overlay
+                .validation
+                .max_fragments
+                .or(self.validation.max_fragments),
+            strict: overlay.validation.strict.or(self.validation.strict),
         };
         let mut params = self.parameters;
         for (k, v) in overlay.parameters {
@@ -135,9 +307,16 @@ impl RawConfig {
                     .default_extension
                     .or(self.gedent.default_extension),
                 software: overlay.gedent.software.or(self.gedent.software),
+                filename_pattern: overlay
+                    .gedent
+                    .filename_pattern
+                    .or(self.gedent.filename_pattern),
             },
             model,
             resources,
+            node,
+            scheduler,
+            validation,
             parameters: params,
         }
     }
@@ -151,9 +330,13 @@ impl RawConfig {
                     .default_extension
                     .unwrap_or_else(|| "inp".to_string()),
                 software: self.gedent.software,
+                filename_pattern: self.gedent.filename_pattern,
             },
             model: self.model,
             resources: self.resources,
+            node: self.node,
+            scheduler: self.scheduler,
+            validation: self.validation,
             parameters: self.parameters,
         }
     }
@@ -173,6 +356,9 @@ fn raw_contributions(raw: &RawConfig) -> Vec<String> {
     if let Some(ref v) = raw.gedent.software {
         gedent_parts.push(format!("software = {v:?}"));
     }
+    if let Some(ref v) = raw.gedent.filename_pattern {
+        gedent_parts.push(format!("filename_pattern = {v:?}"));
+    }
     if !gedent_parts.is_empty() {
         lines.push(format!("[gedent]     {}", gedent_parts.join(", ")));
     }
@@ -191,6 +377,30 @@ fn raw_contributions(raw: &RawConfig) -> Vec<String> {
         }
     }
 
+    if let Ok(s) = toml::to_string(&raw.node) {
+        let parts: Vec<&str> = s.lines().filter(|l| !l.is_empty()).collect();
+        if !parts.is_empty() {
+            lines.push(format!("[node]       {}", parts.join(", ")));
+        }
+    }
+
+    if let Ok(s) = toml::to_string(&raw.scheduler) {
+        let parts: Vec<&str> = s.lines().filter(|l| !l.is_empty()).collect();
+        if !parts.is_empty() {
+            lines.push(format!("[scheduler]  {}", parts.join(", ")));
+        }
+    }
+
+    if let Ok(s) = toml::to_string(&raw.validation) {
+        let parts: Vec<&str> = s
+            .lines()
+            .filter(|l| !l.is_empty() && !l.starts_with('['))
+            .collect();
+        if !parts.is_empty() {
+            lines.push(format!("[validation] {}", parts.join(", ")));
+        }
+    }
+
     if !raw.parameters.is_empty() {
         let parts: Vec<String> = raw
             .parameters
@@ -297,33 +507,32 @@ impl Config {
             );
         }
 
-        let current_dir = std::env::current_dir()?;
-        let mut locals: Vec<PathBuf> = Vec::new();
-        let mut dir = current_dir.as_path();
-
-        loop {
-            if dir == home {
-                break;
-            }
-            let candidate = dir.join(CONFIG_NAME);
+        let mut chain = vec![global];
+        for candidate in Self::local_candidates()? {
             if candidate.try_exists()? {
-                locals.push(candidate);
-            }
-            match dir.parent() {
-                Some(parent) => dir = parent,
-                None => break,
+                chain.push(candidate);
             }
         }
-
-        locals.reverse(); // cwd-first → global-first order
-        let mut chain = vec![global];
-        chain.extend(locals);
         for path in &chain {
             debug!("Config chain: {}", path.display());
         }
         Ok(chain)
     }
 
+    /// Every place a local config could be, from the directory below the
+    /// gedent home (or the filesystem root) down to the current directory.
+    pub fn local_candidates() -> Result<Vec<PathBuf>, Error> {
+        let home = Self::gedent_home()?;
+        let current_dir = std::env::current_dir()?;
+        let mut candidates: Vec<PathBuf> = current_dir
+            .ancestors()
+            .take_while(|dir| *dir != home)
+            .map(|dir| dir.join(CONFIG_NAME))
+            .collect();
+        candidates.reverse(); // cwd-first → global-first order
+        Ok(candidates)
+    }
+
     /// Returns the gedent home directory, erroring if it does not exist.
     ///
     /// The location is resolved in order:
@@ -385,6 +594,7 @@ mod tests {
             gedent: RawGedentConfig {
                 default_extension: default_extension.map(str::to_string),
                 software: None,
+                filename_pattern: None,
             },
             model: ModelConfig {
                 method: method.map(str::to_string),
@@ -392,6 +602,9 @@ mod tests {
                 ..ModelConfig::default()
             },
             resources: ResourcesConfig::default(),
+            node: NodeConfig::default(),
+            scheduler: SchedulerConfig::default(),
+            validation: ValidationConfig::default(),
             parameters: params
                 .iter()
                 .map(|(k, v)| ((*k).to_string(), v.clone()))
@@ -463,6 +676,65 @@ mod tests {
         assert_eq!(config.parameters["key"], Value::Integer(42));
     }
 
+    #[test]
+    fn cascade_mem_scope_local_wins() {
+        let mut global = raw(Some("inp"), None, None, &[]);
+        global.resources.mem_scope = Some(MemScope::Total);
+        let mut local = raw(None, None, None, &[]);
+        local.resources.mem = Some(Memory::from_mb(2000));
+        let merged = global.merge(local);
+        assert_eq!(merged.resources.mem_scope, Some(MemScope::Total)); // falls through
+        assert_eq!(merged.resources.mem, Some(Memory::from_mb(2000)));
+    }
+
+    #[test]
+    fn mem_scope_parses_snake_case() {
+        let raw: RawConfig = toml::from_str("[resources]\nmem_scope = \"total\"").unwrap();
+        assert_eq!(raw.resources.mem_scope, Some(MemScope::Total));
+    }
+
+    #[test]
+    fn cascade_validation_severity_merges_per_check() {
+        let global: RawConfig = toml::from_str(
+            "[validation]\nstrict = true\ndisable = [\"G006\"]\n\
+             [validation.severity]\nK001 = \"error\"\nK003 = \"error\"",
+        )
+        .unwrap();
+        let local: RawConfig = toml::from_str(
+            "[validation]\nsuperposed_distance = 0.3\n\
+             [validation.severity]\nK003 = \"warning\"",
+        )
+        .unwrap();
+        let merged = global.merge(local).validation;
+        assert_eq!(merged.strict, Some(true)); // falls through
+        assert_eq!(merged.disable, Some(vec!["G006".to_string()]));
+        assert_eq!(merged.superposed_distance, Some(0.3));
+        assert_eq!(merged.severity["K001"], Severity::Error);
+        assert_eq!(merged.severity["K003"], Severity::Warning); // local wins
+    }
+
+    #[test]
+    fn cascade_node_merges_per_key() {
+        let global: RawConfig = toml::from_str("[node]\ncores = 64\nmem = \"256GB\"").unwrap();
+        let local: RawConfig = toml::from_str("[node]\ncores = 32").unwrap();
+        let merged = global.merge(local).node;
+        assert_eq!(merged.cores, Some(32)); // local wins
+        assert_eq!(merged.mem.map(Memory::as_mb), Some(256 * 1024)); // falls through
+    }
+
+    #[test]
+    fn cascade_scheduler_merges_per_key() {
+        let global: RawConfig =
+            toml::from_str("[scheduler]\ntype = \"slurm\"\npartition = \"normal\"").unwrap();
+        let local: RawConfig =
+            toml::from_str("[scheduler]\npartition = \"gpu\"\nmodules = [\"orca/6.0\"]").unwrap();
+        let merged = global.merge(local).scheduler;
+        assert_eq!(merged.kind, Some(SchedulerKind::Slurm)); // falls through
+        assert_eq!(merged.partition.as_deref(), Some("gpu")); // local wins
+        assert_eq!(merged.modules, Some(vec!["orca/6.0".to_string()]));
+        assert!(toml::from_str::<RawConfig>("[scheduler]\ntype = \"lsf\"").is_err());
+    }
+
     #[test]
     fn unknown_field_in_model_errors() {
         let toml = "[model]\nmethod = \"pbe0\"\ntypo_field = \"oops\"";
@@ -488,6 +760,7 @@ mod tests {
             gedent: RawGedentConfig {
                 default_extension: Some("inp".to_string()),
                 software: None,
+                filename_pattern: None,
             },
             model: ModelConfig {
                 method: Some("pbe0".to_string()),
@@ -496,6 +769,9 @@ mod tests {
                 ..ModelConfig::default()
             },
             resources: ResourcesConfig::default(),
+            node: NodeConfig::default(),
+            scheduler: SchedulerConfig::default(),
+            validation: ValidationConfig::default(),
             parameters: Map::new(),
         };
         // project: charge=1 (overrides global)
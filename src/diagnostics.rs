@@ -0,0 +1,176 @@
+use crate::validation::{Check, Diagnostic, Severity};
+use serde_json::{json, Value};
+use strum::IntoEnumIterator;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// How validation findings are reported.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DiagnosticsFormat {
+    /// One human-readable line per finding.
+    #[default]
+    Text,
+    /// A JSON array of findings.
+    Json,
+    /// A SARIF 2.1.0 log, for code-scanning tools.
+    Sarif,
+}
+
+/// Render `diags` in `format`.
+pub fn render(diags: &[Diagnostic], format: DiagnosticsFormat) -> String {
+    match format {
+        DiagnosticsFormat::Text => diags.iter().map(text_line).collect::<Vec<_>>().join("\n"),
+        DiagnosticsFormat::Json => pretty(&to_json(diags)),
+        DiagnosticsFormat::Sarif => pretty(&to_sarif(diags)),
+    }
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+/// `water.xyz: error[G001] charge-mult-parity: ... (atoms 1, 2)`
+fn text_line(d: &Diagnostic) -> String {
+    let path = d
+        .path
+        .as_ref()
+        .map(|p| format!("{}: ", p.display()))
+        .unwrap_or_default();
+    let atoms = if d.atoms.is_empty() {
+        String::new()
+    } else {
+        let atoms: Vec<String> = d.atoms.iter().map(ToString::to_string).collect();
+        format!(" (atoms {})", atoms.join(", "))
+    };
+    format!(
+        "{path}{}[{}] {}: {}{atoms}",
+        d.severity,
+        d.check.code(),
+        d.check.name(),
+        d.message
+    )
+}
+
+/// Findings as a JSON array of `{code, name, severity, message, path, atoms}`.
+pub fn to_json(diags: &[Diagnostic]) -> Value {
+    diags
+        .iter()
+        .map(|d| {
+            json!({
+                "code": d.check.code(),
+                "name": d.check.name(),
+                "severity": d.severity.to_string(),
+                "message": d.message,
+                "path": d.path.as_ref().map(|p| p.display().to_string()),
+                "atoms": d.atoms,
+            })
+        })
+        .collect()
+}
+
+/// Findings as a SARIF 2.1.0 log with one run and every check as a rule.
+pub fn to_sarif(diags: &[Diagnostic]) -> Value {
+    let rules: Vec<Value> = Check::iter()
+        .map(|c| {
+            json!({
+                "id": c.code(),
+                "name": c.name(),
+                "shortDescription": { "text": c.description() },
+            })
+        })
+        .collect();
+    let results: Vec<Value> = diags
+        .iter()
+        .map(|d| {
+            let mut result = json!({
+                "ruleId": d.check.code(),
+                "level": match d.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                },
+                "message": { "text": d.message },
+            });
+            if let Some(path) = &d.path {
+                result["locations"] = json!([{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": path.display().to_string() },
+                    },
+                }]);
+            }
+            if !d.atoms.is_empty() {
+                result["properties"] = json!({ "atoms": d.atoms });
+            }
+            result
+        })
+        .collect();
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "gedent",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn sample() -> Vec<Diagnostic> {
+        vec![
+            Diagnostic::error(Check::SuperposedAtoms, "atoms 1 and 2 are superposed")
+                .with_atoms(vec![1, 2])
+                .with_path(Path::new("water.xyz")),
+            Diagnostic::warning(Check::KeywordSpelling, "use `D3BJ`"),
+        ]
+    }
+
+    #[test]
+    fn text_includes_path_code_and_atoms() {
+        let out = render(&sample(), DiagnosticsFormat::Text);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines[0],
+            "water.xyz: error[G005] superposed-atoms: atoms 1 and 2 are superposed (atoms 1, 2)"
+        );
+        assert_eq!(lines[1], "warning[K001] keyword-spelling: use `D3BJ`");
+    }
+
+    #[test]
+    fn json_has_structured_fields() {
+        let json = to_json(&sample());
+        assert_eq!(json[0]["code"], "G005");
+        assert_eq!(json[0]["name"], "superposed-atoms");
+        assert_eq!(json[0]["severity"], "error");
+        assert_eq!(json[0]["path"], "water.xyz");
+        assert_eq!(json[0]["atoms"], json!([1, 2]));
+        assert_eq!(json[1]["path"], Value::Null);
+    }
+
+    #[test]
+    fn sarif_lists_rules_and_results() {
+        let sarif = to_sarif(&sample());
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(
+            run["tool"]["driver"]["rules"].as_array().unwrap().len(),
+            Check::iter().count()
+        );
+        assert_eq!(run["results"][0]["ruleId"], "G005");
+        assert_eq!(run["results"][0]["level"], "error");
+        assert_eq!(
+            run["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "water.xyz"
+        );
+        assert!(run["results"][1].get("locations").is_none());
+    }
+}
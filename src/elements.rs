@@ -427,6 +427,79 @@ impl Element {
             _ => None,
         }
     }
+
+    /// Whether the element is a metal. Metalloids (B, Si, Ge, As, Sb, Te)
+    /// count as non-metals.
+    #[must_use]
+    pub const fn is_metal(self) -> bool {
+        !matches!(
+            self,
+            Self::X
+                | Self::H
+                | Self::He
+                | Self::B
+                | Self::C
+                | Self::N
+                | Self::O
+                | Self::F
+                | Self::Ne
+                | Self::Si
+                | Self::P
+                | Self::S
+                | Self::Cl
+                | Self::Ar
+                | Self::Ge
+                | Self::As
+                | Self::Se
+                | Self::Br
+                | Self::Kr
+                | Self::Sb
+                | Self::Te
+                | Self::I
+                | Self::Xe
+                | Self::At
+                | Self::Rn
+                | Self::Ts
+                | Self::Og
+        )
+    }
+
+    /// Largest number of covalent bonds to non-metals the element usually
+    /// forms in organic molecules, or `None` where that is not meaningful.
+    #[must_use]
+    pub const fn max_valence(self) -> Option<usize> {
+        match self {
+            Self::H | Self::F | Self::Cl | Self::Br => Some(1),
+            Self::O => Some(2),
+            Self::B | Self::C | Self::N | Self::Si => Some(4),
+            Self::P => Some(5),
+            Self::S => Some(6),
+            _ => None,
+        }
+    }
+
+    /// Whether the element is a first-row transition metal that commonly has
+    /// open-shell ground states (Ti–Cu). Sc and Zn are left out: their usual
+    /// oxidation states are d0 and d10.
+    #[must_use]
+    pub const fn is_first_row_transition_metal(self) -> bool {
+        matches!(
+            self,
+            Self::Ti | Self::V | Self::Cr | Self::Mn | Self::Fe | Self::Co | Self::Ni | Self::Cu
+        )
+    }
+
+    /// Most unpaired electrons the element's valence shell can hold: 5 for a
+    /// half-filled d shell (groups 3–11), 7 for a half-filled f shell
+    /// (lanthanides and actinides) and 0 for everything else.
+    #[must_use]
+    pub const fn max_unpaired(self) -> usize {
+        match self as u8 {
+            57..=71 | 89..=103 => 7,
+            21..=29 | 39..=47 | 72..=79 | 104..=111 => 5,
+            _ => 0,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -492,4 +565,28 @@ mod tests {
         approx::assert_relative_eq!(Element::Co.get_radius().unwrap(), 1.288_742_5);
         approx::assert_relative_eq!(Element::Cm.get_radius().unwrap(), 1.69);
     }
+
+    #[test]
+    fn metals_and_valences() {
+        assert!(Element::Fe.is_metal());
+        assert!(Element::Na.is_metal());
+        assert!(!Element::C.is_metal());
+        assert!(!Element::Si.is_metal());
+        assert_eq!(Element::C.max_valence(), Some(4));
+        assert_eq!(Element::O.max_valence(), Some(2));
+        assert_eq!(Element::Fe.max_valence(), None);
+    }
+
+    #[test]
+    fn open_shell_data() {
+        assert!(Element::Fe.is_first_row_transition_metal());
+        assert!(!Element::Zn.is_first_row_transition_metal());
+        assert!(!Element::Ru.is_first_row_transition_metal());
+        assert_eq!(Element::Fe.max_unpaired(), 5);
+        assert_eq!(Element::Pt.max_unpaired(), 5);
+        assert_eq!(Element::Zn.max_unpaired(), 0);
+        assert_eq!(Element::Gd.max_unpaired(), 7);
+        assert_eq!(Element::U.max_unpaired(), 7);
+        assert_eq!(Element::C.max_unpaired(), 0);
+    }
 }
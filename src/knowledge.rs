@@ -0,0 +1,391 @@
+use crate::software::Software;
+use crate::solvents;
+
+/// Which `[model]` field a [`Keyword`] belongs to.
+///
+/// Parses from the field name, as used by the `for_software(kind=...)` filter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display, strum::EnumString)]
+pub enum KeywordKind {
+    /// `method`
+    #[strum(to_string = "method")]
+    Method,
+    /// `basis_set`
+    #[strum(to_string = "basis set", serialize = "basis_set")]
+    BasisSet,
+    /// `dispersion`
+    #[strum(to_string = "dispersion correction", serialize = "dispersion")]
+    Dispersion,
+    /// `solvation_model`
+    #[strum(to_string = "solvation model", serialize = "solvation_model")]
+    SolvationModel,
+    /// `solvent` — looked up in the solvent table rather than [`KEYWORDS`].
+    #[strum(to_string = "solvent")]
+    Solvent,
+}
+
+/// A model keyword and how each program spells it.
+#[derive(Debug)]
+pub struct Keyword {
+    /// Field this keyword is used in.
+    pub kind: KeywordKind,
+    /// Canonical lowercase identifier (e.g. `"d3bj"`, `"def2-svp"`).
+    pub canonical: &'static str,
+    /// Other program-independent spellings of the same keyword.
+    pub aliases: &'static [&'static str],
+    /// Accepted spellings per program, preferred spelling first. Programs
+    /// that are absent do not support the keyword.
+    pub spellings: &'static [(Software, &'static [&'static str])],
+}
+
+impl Keyword {
+    /// Spellings `software` accepts, or `None` if it does not support the keyword.
+    pub fn spellings_for(&self, software: Software) -> Option<&'static [&'static str]> {
+        self.spellings
+            .iter()
+            .find(|(sw, _)| *sw == software)
+            .map(|(_, s)| *s)
+    }
+
+    /// Whether `value` is one of the spellings `software` accepts.
+    pub fn accepted_by(&self, software: Software, value: &str) -> bool {
+        self.spellings_for(software)
+            .is_some_and(|s| s.iter().any(|s| s.eq_ignore_ascii_case(value)))
+    }
+
+    fn matches(&self, value: &str) -> bool {
+        self.canonical.eq_ignore_ascii_case(value)
+            || self.aliases.iter().any(|a| a.eq_ignore_ascii_case(value))
+            || self
+                .spellings
+                .iter()
+                .flat_map(|(_, s)| s.iter())
+                .any(|s| s.eq_ignore_ascii_case(value))
+    }
+}
+
+/// Find the keyword of `kind` that `value` spells, in any program's dialect.
+pub fn lookup(kind: KeywordKind, value: &str) -> Option<&'static Keyword> {
+    let value = value.trim();
+    KEYWORDS.iter().find(|k| k.kind == kind && k.matches(value))
+}
+
+/// Spell `value` the way `software` expects.
+///
+/// `value` may be a canonical identifier, an alias or any program's spelling;
+/// the keyword is searched among `kind` only, or among all kinds (solvents
+/// last) when `None`. Returns `None` when the keyword is unknown or `software`
+/// does not support it.
+pub fn translate(
+    value: &str,
+    software: Software,
+    kind: Option<KeywordKind>,
+) -> Option<&'static str> {
+    let value = value.trim();
+    let keyword = KEYWORDS
+        .iter()
+        .filter(|k| kind.map_or(true, |kind| k.kind == kind))
+        .find(|k| k.matches(value));
+    match keyword {
+        Some(k) => k.spellings_for(software).map(|s| s[0]),
+        None if kind.map_or(true, |k| k == KeywordKind::Solvent) => solvents::lookup(value)
+            .and_then(|s| s.names_for(software))
+            .map(|n| n[0]),
+        None => None,
+    }
+}
+
+/// Whether `method` is one of the xtb tight-binding Hamiltonians (or GFN-FF).
+pub fn is_xtb_method(method: &str) -> bool {
+    lookup(KeywordKind::Method, method)
+        .is_some_and(|k| matches!(k.canonical, "gfn0-xtb" | "gfn1-xtb" | "gfn2-xtb" | "gfn-ff"))
+}
+
+/// Whether `method` is a composite "3c" method that brings its own basis set
+/// and dispersion correction.
+pub fn is_composite_method(method: &str) -> bool {
+    lookup(KeywordKind::Method, method).is_some_and(|k| k.canonical.ends_with("-3c"))
+}
+
+/// Element coverage of a basis set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BasisCoverage {
+    /// Inclusive atomic-number ranges the basis set defines functions for.
+    pub ranges: &'static [(u8, u8)],
+    /// First atomic number that gets an effective core potential, if any.
+    pub ecp_from: Option<u8>,
+    /// Contracted for a scalar-relativistic Hamiltonian (ZORA, DKH, X2C)
+    /// rather than for use with ECPs.
+    pub relativistic: bool,
+}
+
+impl BasisCoverage {
+    /// Whether the basis set defines functions for atomic number `z`.
+    pub fn covers(&self, z: u8) -> bool {
+        self.ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(&z))
+    }
+
+    /// Whether atomic number `z` gets an ECP with this basis set.
+    pub fn has_ecp(&self, z: u8) -> bool {
+        self.ecp_from.is_some_and(|from| z >= from)
+    }
+}
+
+/// Element coverage of `basis`, or `None` for basis sets gedent has no data for.
+pub fn basis_coverage(basis: &str) -> Option<BasisCoverage> {
+    const fn cov(
+        ranges: &'static [(u8, u8)],
+        ecp_from: Option<u8>,
+        relativistic: bool,
+    ) -> BasisCoverage {
+        BasisCoverage {
+            ranges,
+            ecp_from,
+            relativistic,
+        }
+    }
+    let canonical = lookup(KeywordKind::BasisSet, basis)?.canonical;
+    Some(match canonical {
+        // def2 has no 4f elements (Ce–Lu); Rb onwards use the Stuttgart ECPs.
+        c if c.starts_with("def2-") || c.starts_with("ma-def2-") => {
+            cov(&[(1, 57), (72, 86)], Some(37), false)
+        }
+        c if c.starts_with("x2c-") => cov(&[(1, 86)], None, true),
+        c if c.starts_with("zora-def2-") => cov(&[(1, 36)], None, true),
+        "sarc-zora-tzvp" => cov(&[(1, 86)], None, true),
+        "sto-3g" => cov(&[(1, 54)], None, false),
+        "3-21g" => cov(&[(1, 55)], None, false),
+        c if c.starts_with("6-311") => cov(&[(1, 20), (31, 36)], None, false),
+        c if c.starts_with("6-31") => cov(&[(1, 36)], None, false),
+        // Dunning sets with the later 3d (Sc–Zn) and K/Ca additions; the
+        // diffuse ones have no K.
+        c if c.starts_with("aug-cc-") => cov(&[(1, 18), (20, 36)], None, false),
+        c if c.starts_with("cc-") => cov(&[(1, 36)], None, false),
+        c if c.starts_with("pcseg-") || c.starts_with("pcsseg-") => cov(&[(1, 36)], None, false),
+        "lanl2dz" => cov(&[(1, 1), (3, 57), (72, 83)], Some(11), false),
+        "sdd" => cov(&[(1, 86)], Some(19), false),
+        // ADF's Slater basis sets cover the whole periodic table.
+        "sz" | "dz" | "dzp" | "tzp" | "tz2p" | "qz4p" => cov(&[(1, 118)], None, false),
+        _ => return None,
+    })
+}
+
+macro_rules! kw {
+    ($kind:ident, $canonical:literal, [$($alias:literal),*], {$($sw:ident: [$($s:literal),+]),* $(,)?}) => {
+        Keyword {
+            kind: KeywordKind::$kind,
+            canonical: $canonical,
+            aliases: &[$($alias),*],
+            spellings: &[$((Software::$sw, &[$($s),+])),*],
+        }
+    };
+}
+
+/// The knowledge base. Lists are not exhaustive: keywords missing here are
+/// only reported where gedent can be reasonably sure (basis sets, dispersion
+/// and solvation models), never for methods.
+static KEYWORDS: &[Keyword] = &[
+    // ── Methods ──────────────────────────────────────────────────────────────
+    kw!(Method, "hf", [], {Orca: ["HF"], Gaussian: ["HF"], Adf: ["HartreeFock"], Nwchem: ["scf"]}),
+    kw!(Method, "b3lyp", [], {Orca: ["B3LYP"], Gaussian: ["B3LYP"], Adf: ["Hybrid B3LYP"], Nwchem: ["b3lyp"]}),
+    kw!(Method, "pbe0", ["pbeh"], {Orca: ["PBE0"], Gaussian: ["PBE1PBE"], Adf: ["Hybrid PBE0"], Nwchem: ["pbe0"]}),
+    kw!(Method, "pbe", [], {Orca: ["PBE"], Gaussian: ["PBEPBE"], Adf: ["GGA PBE"], Nwchem: ["xpbe96 cpbe96"]}),
+    kw!(Method, "bp86", [], {Orca: ["BP86"], Gaussian: ["BP86"], Adf: ["GGA BP86"], Nwchem: ["becke88 perdew86"]}),
+    kw!(Method, "blyp", [], {Orca: ["BLYP"], Gaussian: ["BLYP"], Adf: ["GGA BLYP"], Nwchem: ["becke88 lyp"]}),
+    kw!(Method, "tpss", [], {Orca: ["TPSS"], Gaussian: ["TPSSTPSS"], Adf: ["MetaGGA TPSS"]}),
+    kw!(Method, "tpssh", [], {Orca: ["TPSSh"], Gaussian: ["TPSSh"], Adf: ["MetaHybrid TPSSH"]}),
+    kw!(Method, "r2scan", [], {Orca: ["r2SCAN"], Adf: ["MetaGGA r2SCAN"]}),
+    kw!(Method, "m06", [], {Orca: ["M06"], Gaussian: ["M06"], Adf: ["MetaHybrid M06"]}),
+    kw!(Method, "m062x", ["m06-2x"], {Orca: ["M062X"], Gaussian: ["M062X"], Adf: ["MetaHybrid M06-2X"]}),
+    kw!(Method, "cam-b3lyp", [], {Orca: ["CAM-B3LYP"], Gaussian: ["CAM-B3LYP"], Adf: ["LibXC CAM-B3LYP"]}),
+    kw!(Method, "wb97x-d", ["ωb97x-d"], {Gaussian: ["wB97XD"]}),
+    kw!(Method, "wb97x-d3", ["ωb97x-d3"], {Orca: ["wB97X-D3"]}),
+    kw!(Method, "wb97x-v", ["ωb97x-v"], {Orca: ["wB97X-V"]}),
+    kw!(Method, "wb97m-v", ["ωb97m-v"], {Orca: ["wB97M-V"]}),
+    kw!(Method, "b2plyp", [], {Orca: ["B2PLYP"], Gaussian: ["B2PLYP"]}),
+    kw!(Method, "pbeh-3c", [], {Orca: ["PBEh-3c"]}),
+    kw!(Method, "r2scan-3c", [], {Orca: ["r2SCAN-3c"]}),
+    kw!(Method, "b97-3c", [], {Orca: ["B97-3c"]}),
+    kw!(Method, "hf-3c", [], {Orca: ["HF-3c"]}),
+    kw!(Method, "mp2", [], {Orca: ["MP2"], Gaussian: ["MP2"], Nwchem: ["mp2"]}),
+    kw!(Method, "ri-mp2", [], {Orca: ["RI-MP2"]}),
+    kw!(Method, "ccsd(t)", [], {Orca: ["CCSD(T)"], Gaussian: ["CCSD(T)"], Nwchem: ["ccsd(t)"]}),
+    kw!(Method, "dlpno-ccsd(t)", [], {Orca: ["DLPNO-CCSD(T)"]}),
+    kw!(Method, "gfn0-xtb", ["gfn0"], {Orca: ["XTB0"], Xtb: ["--gfn 0"], Crest: ["gfn0"]}),
+    kw!(Method, "gfn1-xtb", ["gfn1", "xtb1"], {Orca: ["XTB1"], Xtb: ["--gfn 1"], Crest: ["gfn1"]}),
+    kw!(Method, "gfn2-xtb", ["gfn2", "xtb2", "xtb"], {Orca: ["XTB2", "XTB"], Xtb: ["--gfn 2"], Crest: ["gfn2"]}),
+    kw!(Method, "gfn-ff", ["gfnff"], {Xtb: ["--gfnff"], Crest: ["gfnff"]}),
+    // ── Basis sets ───────────────────────────────────────────────────────────
+    kw!(BasisSet, "def2-svp", ["def2svp"], {Orca: ["def2-SVP"], Gaussian: ["Def2SVP"], Nwchem: ["def2-svp"]}),
+    kw!(BasisSet, "def2-svpd", ["def2svpd"], {Orca: ["def2-SVPD"], Gaussian: ["Def2SVPD"], Nwchem: ["def2-svpd"]}),
+    kw!(BasisSet, "def2-tzvp", ["def2tzvp"], {Orca: ["def2-TZVP"], Gaussian: ["Def2TZVP"], Nwchem: ["def2-tzvp"]}),
+    kw!(BasisSet, "def2-tzvpp", ["def2tzvpp"], {Orca: ["def2-TZVPP"], Gaussian: ["Def2TZVPP"], Nwchem: ["def2-tzvpp"]}),
+    kw!(BasisSet, "def2-tzvpd", ["def2tzvpd"], {Orca: ["def2-TZVPD"], Gaussian: ["Def2TZVPD"], Nwchem: ["def2-tzvpd"]}),
+    kw!(BasisSet, "def2-qzvp", ["def2qzvp"], {Orca: ["def2-QZVP"], Gaussian: ["Def2QZVP"], Nwchem: ["def2-qzvp"]}),
+    kw!(BasisSet, "def2-qzvpp", ["def2qzvpp"], {Orca: ["def2-QZVPP"], Gaussian: ["Def2QZVPP"], Nwchem: ["def2-qzvpp"]}),
+    kw!(BasisSet, "ma-def2-svp", [], {Orca: ["ma-def2-SVP"]}),
+    kw!(BasisSet, "ma-def2-tzvp", [], {Orca: ["ma-def2-TZVP"]}),
+    kw!(BasisSet, "x2c-svpall", [], {Orca: ["x2c-SVPall"]}),
+    kw!(BasisSet, "x2c-tzvpall", [], {Orca: ["x2c-TZVPall"]}),
+    kw!(BasisSet, "zora-def2-svp", [], {Orca: ["ZORA-def2-SVP"]}),
+    kw!(BasisSet, "zora-def2-tzvp", [], {Orca: ["ZORA-def2-TZVP"]}),
+    kw!(BasisSet, "sarc-zora-tzvp", [], {Orca: ["SARC-ZORA-TZVP"]}),
+    kw!(BasisSet, "sto-3g", [], {Orca: ["STO-3G"], Gaussian: ["STO-3G"], Nwchem: ["sto-3g"]}),
+    kw!(BasisSet, "3-21g", [], {Orca: ["3-21G"], Gaussian: ["3-21G"], Nwchem: ["3-21g"]}),
+    kw!(BasisSet, "6-31g", [], {Orca: ["6-31G"], Gaussian: ["6-31G"], Nwchem: ["6-31g"]}),
+    kw!(BasisSet, "6-31g(d)", ["6-31g*"], {Orca: ["6-31G(d)", "6-31G*"], Gaussian: ["6-31G(d)", "6-31G*"], Nwchem: ["6-31g*"]}),
+    kw!(BasisSet, "6-31g(d,p)", ["6-31g**"], {Orca: ["6-31G(d,p)", "6-31G**"], Gaussian: ["6-31G(d,p)", "6-31G**"], Nwchem: ["6-31g**"]}),
+    kw!(BasisSet, "6-31+g(d)", ["6-31+g*"], {Orca: ["6-31+G(d)", "6-31+G*"], Gaussian: ["6-31+G(d)", "6-31+G*"], Nwchem: ["6-31+g*"]}),
+    kw!(BasisSet, "6-31+g(d,p)", ["6-31+g**"], {Orca: ["6-31+G(d,p)", "6-31+G**"], Gaussian: ["6-31+G(d,p)", "6-31+G**"], Nwchem: ["6-31+g**"]}),
+    kw!(BasisSet, "6-311g(d,p)", ["6-311g**"], {Orca: ["6-311G(d,p)", "6-311G**"], Gaussian: ["6-311G(d,p)", "6-311G**"], Nwchem: ["6-311g**"]}),
+    kw!(BasisSet, "6-311+g(d,p)", ["6-311+g**"], {Orca: ["6-311+G(d,p)", "6-311+G**"], Gaussian: ["6-311+G(d,p)", "6-311+G**"], Nwchem: ["6-311+g**"]}),
+    kw!(BasisSet, "6-311++g(d,p)", ["6-311++g**"], {Orca: ["6-311++G(d,p)", "6-311++G**"], Gaussian: ["6-311++G(d,p)", "6-311++G**"], Nwchem: ["6-311++g**"]}),
+    kw!(BasisSet, "6-311+g(2d,p)", [], {Orca: ["6-311+G(2d,p)"], Gaussian: ["6-311+G(2d,p)"], Nwchem: ["6-311+g(2d,p)"]}),
+    kw!(BasisSet, "cc-pvdz", [], {Orca: ["cc-pVDZ"], Gaussian: ["cc-pVDZ"], Nwchem: ["cc-pvdz"]}),
+    kw!(BasisSet, "cc-pvtz", [], {Orca: ["cc-pVTZ"], Gaussian: ["cc-pVTZ"], Nwchem: ["cc-pvtz"]}),
+    kw!(BasisSet, "cc-pvqz", [], {Orca: ["cc-pVQZ"], Gaussian: ["cc-pVQZ"], Nwchem: ["cc-pvqz"]}),
+    kw!(BasisSet, "aug-cc-pvdz", [], {Orca: ["aug-cc-pVDZ"], Gaussian: ["aug-cc-pVDZ"], Nwchem: ["aug-cc-pvdz"]}),
+    kw!(BasisSet, "aug-cc-pvtz", [], {Orca: ["aug-cc-pVTZ"], Gaussian: ["aug-cc-pVTZ"], Nwchem: ["aug-cc-pvtz"]}),
+    kw!(BasisSet, "aug-cc-pvqz", [], {Orca: ["aug-cc-pVQZ"], Gaussian: ["aug-cc-pVQZ"], Nwchem: ["aug-cc-pvqz"]}),
+    kw!(BasisSet, "pcseg-1", [], {Orca: ["pcseg-1"]}),
+    kw!(BasisSet, "pcseg-2", [], {Orca: ["pcseg-2"]}),
+    kw!(BasisSet, "pcsseg-1", [], {Orca: ["pcSseg-1"]}),
+    kw!(BasisSet, "lanl2dz", [], {Orca: ["LANL2DZ"], Gaussian: ["LANL2DZ"], Nwchem: ["lanl2dz_ecp"]}),
+    kw!(BasisSet, "sdd", [], {Gaussian: ["SDD"]}),
+    kw!(BasisSet, "sz", [], {Adf: ["SZ"]}),
+    kw!(BasisSet, "dz", [], {Adf: ["DZ"]}),
+    kw!(BasisSet, "dzp", [], {Adf: ["DZP"]}),
+    kw!(BasisSet, "tzp", [], {Adf: ["TZP"]}),
+    kw!(BasisSet, "tz2p", [], {Adf: ["TZ2P"]}),
+    kw!(BasisSet, "qz4p", [], {Adf: ["QZ4P"]}),
+    // ── Dispersion ───────────────────────────────────────────────────────────
+    kw!(Dispersion, "d2", [], {Orca: ["D2"], Gaussian: ["EmpiricalDispersion=GD2"], Nwchem: ["disp vdw 2"]}),
+    kw!(Dispersion, "d3zero", ["d3(0)"], {Orca: ["D3ZERO"], Gaussian: ["EmpiricalDispersion=GD3"], Adf: ["Grimme3"], Nwchem: ["disp vdw 3"]}),
+    kw!(Dispersion, "d3bj", ["d3(bj)"], {Orca: ["D3BJ", "D3"], Gaussian: ["EmpiricalDispersion=GD3BJ"], Adf: ["Grimme3 BJDAMP"], Nwchem: ["disp vdw 4"]}),
+    kw!(Dispersion, "d4", [], {Orca: ["D4"], Adf: ["Grimme4"]}),
+    // ── Solvation models ─────────────────────────────────────────────────────
+    kw!(SolvationModel, "smd", [], {Orca: ["SMD"], Gaussian: ["SMD"]}),
+    kw!(SolvationModel, "cpcm", [], {Orca: ["CPCM"], Gaussian: ["CPCM"]}),
+    kw!(SolvationModel, "pcm", ["iefpcm"], {Gaussian: ["PCM", "IEFPCM"]}),
+    kw!(SolvationModel, "alpb", [], {Orca: ["ALPB"], Xtb: ["--alpb"], Crest: ["--alpb"]}),
+    kw!(SolvationModel, "gbsa", [], {Xtb: ["--gbsa"], Crest: ["--gbsa"]}),
+    kw!(SolvationModel, "cosmo", [], {Adf: ["COSMO"], Nwchem: ["cosmo"], Xtb: ["--cosmo"]}),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_matches_canonical_alias_and_program_spellings() {
+        let k = lookup(KeywordKind::Dispersion, "d3bj").unwrap();
+        assert_eq!(k.canonical, "d3bj");
+        assert_eq!(
+            lookup(KeywordKind::Dispersion, "EmpiricalDispersion=GD3BJ").map(|k| k.canonical),
+            Some("d3bj")
+        );
+        assert_eq!(
+            lookup(KeywordKind::BasisSet, "Def2SVP").map(|k| k.canonical),
+            Some("def2-svp")
+        );
+        assert_eq!(
+            lookup(KeywordKind::BasisSet, "6-31g*").map(|k| k.canonical),
+            Some("6-31g(d)")
+        );
+    }
+
+    #[test]
+    fn lookup_respects_kind() {
+        assert!(lookup(KeywordKind::Method, "def2-svp").is_none());
+        assert!(lookup(KeywordKind::BasisSet, "no-such-basis").is_none());
+    }
+
+    #[test]
+    fn accepted_by_is_per_program() {
+        let k = lookup(KeywordKind::Dispersion, "d3bj").unwrap();
+        assert!(k.accepted_by(Software::Orca, "d3bj"));
+        assert!(!k.accepted_by(Software::Gaussian, "d3bj"));
+        assert!(k.accepted_by(Software::Gaussian, "empiricaldispersion=gd3bj"));
+        assert!(k.spellings_for(Software::Xtb).is_none());
+    }
+
+    #[test]
+    fn translate_between_programs() {
+        assert_eq!(
+            translate("d3bj", Software::Gaussian, None),
+            Some("EmpiricalDispersion=GD3BJ")
+        );
+        assert_eq!(translate("Def2SVP", Software::Orca, None), Some("def2-SVP"));
+        assert_eq!(
+            translate("pbe0", Software::Adf, Some(KeywordKind::Method)),
+            Some("Hybrid PBE0")
+        );
+        assert_eq!(
+            translate("smd", Software::Gaussian, Some(KeywordKind::SolvationModel)),
+            Some("SMD")
+        );
+        assert_eq!(
+            translate("pbe0", Software::Orca, Some(KeywordKind::BasisSet)),
+            None
+        );
+        assert_eq!(translate("d4", Software::Gaussian, None), None);
+        assert_eq!(translate("my-functional", Software::Orca, None), None);
+        assert_eq!(
+            translate("thf", Software::Gaussian, None),
+            Some("TetraHydroFuran")
+        );
+        assert_eq!(
+            translate("dcm", Software::Xtb, Some(KeywordKind::Solvent)),
+            Some("ch2cl2")
+        );
+    }
+
+    #[test]
+    fn kind_parses_from_field_name() {
+        assert_eq!("basis_set".parse(), Ok(KeywordKind::BasisSet));
+        assert_eq!("solvation_model".parse(), Ok(KeywordKind::SolvationModel));
+        assert_eq!("solvent".parse(), Ok(KeywordKind::Solvent));
+        assert!("functional".parse::<KeywordKind>().is_err());
+    }
+
+    #[test]
+    fn method_traits() {
+        assert!(is_xtb_method("XTB2"));
+        assert!(is_xtb_method("gfn2-xtb"));
+        assert!(!is_xtb_method("pbe0"));
+        assert!(is_composite_method("PBEh-3c"));
+        assert!(!is_composite_method("b3lyp"));
+    }
+
+    #[test]
+    fn basis_coverage_by_family() {
+        let def2 = basis_coverage("def2-TZVP").unwrap();
+        assert!(def2.covers(53) && def2.has_ecp(53));
+        assert!(!def2.covers(64));
+        assert!(!def2.has_ecp(26));
+        let dunning = basis_coverage("cc-pVTZ").unwrap();
+        assert!(dunning.covers(19) && dunning.covers(26) && !dunning.covers(37));
+        assert!(!basis_coverage("aug-cc-pVTZ").unwrap().covers(19));
+        let pople = basis_coverage("6-311G**").unwrap();
+        assert!(pople.covers(6) && !pople.covers(26));
+        assert!(basis_coverage("x2c-TZVPall").unwrap().relativistic);
+        assert!(basis_coverage("my-basis").is_none());
+    }
+
+    #[test]
+    fn every_basis_set_has_coverage() {
+        for k in KEYWORDS.iter().filter(|k| k.kind == KeywordKind::BasisSet) {
+            assert!(
+                basis_coverage(k.canonical).is_some(),
+                "{} has no coverage",
+                k.canonical
+            );
+        }
+    }
+
+    #[test]
+    fn every_keyword_has_a_spelling() {
+        for k in KEYWORDS {
+            assert!(!k.spellings.is_empty(), "{} has no spellings", k.canonical);
+        }
+    }
+}
@@ -0,0 +1,483 @@
+use crate::template::{self, TemplateMeta};
+use crate::validation::{Check, Diagnostic};
+use std::error::Error as _;
+use tera::ast::{Expr, ExprVal, FunctionCall, Node};
+
+/// Variables gedent puts in the context itself: `[model]`, `[resources]`,
+/// values derived from them, the molecule being rendered, the files a
+/// workflow wires in from earlier steps and what `gedent restart` adds.
+fn context_keys() -> impl Iterator<Item = &'static str> {
+    crate::CONFIG_KEYS
+        .into_iter()
+        .chain(derived_from().map(|(derived, _)| derived))
+        .chain(crate::template::MOLECULE_KEYS)
+        .chain(crate::workflow::WIRED_KEYS)
+        .chain(crate::RESTART_KEYS)
+}
+
+/// Derived keys and the key they are computed from, so `requires = ["mem"]`
+/// counts as used by `{{ mem_safe }}`.
+fn derived_from() -> impl Iterator<Item = (&'static str, &'static str)> {
+    crate::resources::DERIVED_KEYS
+        .into_iter()
+        .chain(crate::solvents::PROPERTY_KEYS.map(|key| (key, "solvent")))
+        .chain([("solvation", "solvent")])
+}
+
+/// Tera's built-in functions.
+const TERA_FUNCTIONS: &[&str] = &["range", "now", "throw", "get_random", "get_env"];
+
+/// Tera's built-in filters, including `default` and `safe`, which the
+/// parser handles itself.
+const TERA_FILTERS: &[&str] = &[
+    "upper",
+    "lower",
+    "trim",
+    "trim_start",
+    "trim_end",
+    "trim_start_matches",
+    "trim_end_matches",
+    "truncate",
+    "wordcount",
+    "replace",
+    "capitalize",
+    "title",
+    "linebreaksbr",
+    "indent",
+    "striptags",
+    "spaceless",
+    "urlencode",
+    "urlencode_strict",
+    "escape",
+    "escape_xml",
+    "slugify",
+    "addslashes",
+    "split",
+    "int",
+    "float",
+    "first",
+    "last",
+    "nth",
+    "join",
+    "sort",
+    "unique",
+    "slice",
+    "group_by",
+    "filter",
+    "map",
+    "concat",
+    "abs",
+    "pluralize",
+    "round",
+    "filesizeformat",
+    "length",
+    "reverse",
+    "date",
+    "json_encode",
+    "as_str",
+    "get",
+    "default",
+    "safe",
+];
+
+/// Names a template refers to, collected from its AST.
+#[derive(Debug, Default)]
+struct References {
+    /// Root names of variables read, in order of first appearance.
+    variables: Vec<String>,
+    /// Names bound inside the template by `set`, `for` and macro arguments.
+    locals: Vec<String>,
+    /// Functions called.
+    functions: Vec<String>,
+    /// Filters applied.
+    filters: Vec<String>,
+    /// Root names of variables piped into `for_software`.
+    translated: Vec<String>,
+}
+
+fn push_unique(list: &mut Vec<String>, name: &str) {
+    if !list.iter().any(|n| n == name) {
+        list.push(name.to_string());
+    }
+}
+
+impl References {
+    fn nodes(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            self.node(node);
+        }
+    }
+
+    fn node(&mut self, node: &Node) {
+        match node {
+            Node::VariableBlock(_, expr) => self.expr(expr),
+            Node::Set(_, set) => {
+                push_unique(&mut self.locals, &set.key);
+                self.expr(&set.value);
+            }
+            Node::Forloop(_, forloop, _) => {
+                push_unique(&mut self.locals, "loop");
+                if let Some(key) = &forloop.key {
+                    push_unique(&mut self.locals, key);
+                }
+                push_unique(&mut self.locals, &forloop.value);
+                self.expr(&forloop.container);
+                self.nodes(&forloop.body);
+                if let Some(body) = &forloop.empty_body {
+                    self.nodes(body);
+                }
+            }
+            Node::If(cond, _) => {
+                for (_, expr, body) in &cond.conditions {
+                    self.expr(expr);
+                    self.nodes(body);
+                }
+                if let Some((_, body)) = &cond.otherwise {
+                    self.nodes(body);
+                }
+            }
+            Node::MacroDefinition(_, definition, _) => {
+                for (arg, default) in &definition.args {
+                    push_unique(&mut self.locals, arg);
+                    if let Some(default) = default {
+                        self.expr(default);
+                    }
+                }
+                self.nodes(&definition.body);
+            }
+            Node::FilterSection(_, section, _) => {
+                self.call(&section.filter, false);
+                self.nodes(&section.body);
+            }
+            Node::Block(_, block, _) => self.nodes(&block.body),
+            _ => {}
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        self.value(&expr.val);
+        if let ExprVal::Ident(ident) = &expr.val {
+            if expr.filters.iter().any(|f| f.name == "for_software") {
+                push_unique(&mut self.translated, root(ident));
+            }
+        }
+        for filter in &expr.filters {
+            self.call(filter, false);
+        }
+    }
+
+    fn call(&mut self, call: &FunctionCall, is_function: bool) {
+        let names = if is_function {
+            &mut self.functions
+        } else {
+            &mut self.filters
+        };
+        push_unique(names, &call.name);
+        for arg in call.args.values() {
+            self.expr(arg);
+        }
+    }
+
+    fn value(&mut self, value: &ExprVal) {
+        match value {
+            ExprVal::Ident(ident) => push_unique(&mut self.variables, root(ident)),
+            ExprVal::Math(math) => {
+                self.expr(&math.lhs);
+                self.expr(&math.rhs);
+            }
+            ExprVal::Logic(logic) => {
+                self.expr(&logic.lhs);
+                self.expr(&logic.rhs);
+            }
+            ExprVal::In(contains) => {
+                self.expr(&contains.lhs);
+                self.expr(&contains.rhs);
+            }
+            ExprVal::Test(test) => {
+                self.value(&ExprVal::Ident(test.ident.clone()));
+                for arg in &test.args {
+                    self.expr(arg);
+                }
+            }
+            ExprVal::MacroCall(call) => {
+                for arg in call.args.values() {
+                    self.expr(arg);
+                }
+            }
+            ExprVal::FunctionCall(call) => self.call(call, true),
+            ExprVal::Array(items) => {
+                for item in items {
+                    self.expr(item);
+                }
+            }
+            ExprVal::StringConcat(concat) => {
+                for value in &concat.values {
+                    self.value(value);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The variable `ident` reads from, e.g. `scheduler` for `scheduler.walltime`.
+fn root(ident: &str) -> &str {
+    ident.split(['.', '[']).next().unwrap_or(ident)
+}
+
+/// Names of the variables piped into `for_software`, directly or after other
+/// filters (`{{ solvent | default(value="water") | for_software }}`), in order
+/// of first appearance. A body that doesn't parse translates nothing; `lint`
+/// reports why.
+pub fn translated_vars(body: &str) -> Vec<String> {
+    references(body).translated
+}
+
+/// Root names of the variables `body` reads, in order of first appearance.
+/// A body that doesn't parse reads nothing; `lint` reports why.
+pub fn read_vars(body: &str) -> Vec<String> {
+    references(body).variables
+}
+
+fn references(body: &str) -> References {
+    let mut refs = References::default();
+    if let Ok(template) = tera::Template::new("references", None, body) {
+        refs.nodes(&template.ast);
+    }
+    refs
+}
+
+/// Closest of `candidates` to `name`, for "did you mean" hints.
+fn suggest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    candidates
+        .into_iter()
+        .filter(|c| *c != name)
+        .map(|c| (c, strsim::jaro_winkler(name, c)))
+        .filter(|(_, score)| *score > 0.8)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(c, _)| c)
+}
+
+fn did_you_mean(suggestion: Option<&str>) -> String {
+    suggestion.map_or_else(String::new, |s| format!(" (did you mean `{s}`?)"))
+}
+
+/// Lint a template `body` against its frontmatter `meta`: variables the body
+/// reads but nobody declares or provides, `requires`/`optional` entries the
+/// body never reads, and calls to functions or filters that don't exist.
+pub fn lint(body: &str, meta: &TemplateMeta) -> Vec<Diagnostic> {
+    let ast = match tera::Template::new("lint", None, body) {
+        Ok(template) => template.ast,
+        Err(err) => {
+            let mut message = err.to_string();
+            let mut source = err.source();
+            while let Some(cause) = source {
+                message = format!("{message}: {cause}");
+                source = cause.source();
+            }
+            return vec![Diagnostic::error(Check::TemplateSyntax, message)];
+        }
+    };
+    let mut refs = References::default();
+    refs.nodes(&ast);
+
+    let declared: Vec<&str> = meta
+        .requires
+        .iter()
+        .chain(&meta.optional)
+        .map(String::as_str)
+        .collect();
+    let context: Vec<&str> = context_keys().collect();
+    let mut diags = vec![];
+
+    for var in &refs.variables {
+        let var = var.as_str();
+        if refs.locals.iter().any(|l| l == var) || declared.contains(&var) || context.contains(&var)
+        {
+            continue;
+        }
+        let candidates = declared
+            .iter()
+            .copied()
+            .chain(context.iter().copied())
+            .chain(refs.locals.iter().map(String::as_str));
+        diags.push(Diagnostic::warning(
+            Check::UndeclaredVariable,
+            format!(
+                "`{var}` is used but not listed in `requires` or `optional`, \
+                 and gedent does not provide it{}",
+                did_you_mean(suggest(var, candidates))
+            ),
+        ));
+    }
+
+    for var in &declared {
+        let used = refs.variables.iter().any(|v| {
+            v == var || derived_from().any(|(derived, from)| from == *var && v == derived)
+        });
+        if !used {
+            diags.push(Diagnostic::warning(
+                Check::UnusedVariable,
+                format!("`{var}` is declared in the frontmatter but never used"),
+            ));
+        }
+    }
+
+    let functions: Vec<&str> = TERA_FUNCTIONS
+        .iter()
+        .copied()
+        .chain(template::function_names())
+        .collect();
+    for function in &refs.functions {
+        if !functions.contains(&function.as_str()) {
+            diags.push(Diagnostic::error(
+                Check::UnknownFunction,
+                format!(
+                    "unknown function `{function}`{}",
+                    did_you_mean(suggest(function, functions.iter().copied()))
+                ),
+            ));
+        }
+    }
+
+    let filters: Vec<&str> = TERA_FILTERS
+        .iter()
+        .copied()
+        .chain(template::filter_names())
+        .collect();
+    for filter in &refs.filters {
+        if !filters.contains(&filter.as_str()) {
+            diags.push(Diagnostic::error(
+                Check::UnknownFilter,
+                format!(
+                    "unknown filter `{filter}`{}",
+                    did_you_mean(suggest(filter, filters.iter().copied()))
+                ),
+            ));
+        }
+    }
+
+    diags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use include_dir::Dir;
+
+    #[test]
+    fn translated_vars_found_through_filter_chains() {
+        let body = "{{ method | for_software }} {{basis_set|for_software(kind='basis_set')}} \
+                    {{ method | for_software }} {{ x }} \
+                    {% if solvation %}{{ solvation_model | default(value='smd') | upper | for_software(kind='solvation_model') }}{% endif %}";
+        assert_eq!(
+            translated_vars(body),
+            vec!["method", "basis_set", "solvation_model"]
+        );
+        assert!(translated_vars("{{ method | upper }} {{ for_software }}").is_empty());
+    }
+
+    fn lint_body(body: &str) -> Vec<Diagnostic> {
+        let meta = TemplateMeta {
+            requires: vec!["method".to_string(), "charge".to_string()],
+            optional: vec!["maxiter".to_string()],
+            ..TemplateMeta::default()
+        };
+        lint(body, &meta)
+    }
+
+    fn checks(diags: &[Diagnostic]) -> Vec<Check> {
+        diags.iter().map(|d| d.check).collect()
+    }
+
+    #[test]
+    fn clean_template_has_no_findings() {
+        let body = "{% set x = 2 %}{{ method }} {{ charge + x }} {{ mem_safe }}\
+                    {% if maxiter is defined %}{{ maxiter }}{% endif %}\
+                    {% for atom in Molecule.atoms %}{{ loop.index }} {{ atom.element }}{% endfor %}\
+                    {{ print_coords(molecule=Molecule) | trim }} {{ 1.0 | to_bohr }}";
+        assert!(lint_body(body).is_empty(), "{:?}", lint_body(body));
+    }
+
+    #[test]
+    fn undeclared_variable_with_suggestion() {
+        let diags = lint_body("{{ method }} {{ charge }} {{ maxiterr }}");
+        assert_eq!(
+            checks(&diags),
+            vec![Check::UndeclaredVariable, Check::UnusedVariable]
+        );
+        assert!(diags[0].message.contains("`maxiterr`"));
+        assert!(diags[0].message.contains("did you mean `maxiter`"));
+        assert!(diags[1].message.contains("`maxiter` is declared"));
+    }
+
+    #[test]
+    fn unused_requirement() {
+        let diags = lint_body("{{ method }} {% if maxiter is defined %}{% endif %}");
+        assert_eq!(checks(&diags), vec![Check::UnusedVariable]);
+        assert!(diags[0].message.contains("`charge`"));
+    }
+
+    #[test]
+    fn derived_keys_count_as_using_their_source() {
+        let meta = TemplateMeta {
+            requires: vec!["mem".to_string(), "nprocs".to_string()],
+            ..TemplateMeta::default()
+        };
+        assert!(lint("{{ mem_safe }} {{ omp_threads }}", &meta).is_empty());
+    }
+
+    #[test]
+    fn unknown_functions_and_filters() {
+        let diags = lint_body(
+            "{{ method | for_sofware }} {{ charge }} {{ maxiter }} {{ print_coord(molecule=Molecule) }}",
+        );
+        assert_eq!(
+            checks(&diags),
+            vec![Check::UnknownFunction, Check::UnknownFilter]
+        );
+        assert!(diags[0].message.contains("did you mean `print_coords`"));
+        assert!(diags[1].message.contains("did you mean `for_software`"));
+    }
+
+    #[test]
+    fn syntax_error_is_reported() {
+        let diags = lint_body("{% if method %}unterminated");
+        assert_eq!(checks(&diags), vec![Check::TemplateSyntax]);
+    }
+
+    fn bundled(dir: &'static Dir<'static>) -> Vec<&'static include_dir::File<'static>> {
+        dir.files().chain(dir.dirs().flat_map(bundled)).collect()
+    }
+
+    #[test]
+    fn bundled_templates_lint_clean() {
+        for file in bundled(&crate::INCLUDE_TEMPLATES_DIR) {
+            let body = file.contents_utf8().unwrap();
+            let diags = lint(body, &template::parse_frontmatter(body).unwrap());
+            if file.path() == std::path::Path::new("adf/eda") {
+                // The EDA template needs a function gedent doesn't have yet.
+                assert_eq!(checks(&diags), vec![Check::UnknownFunction], "{diags:?}");
+                assert!(diags[0].message.contains("`split_molecule`"));
+            } else {
+                assert!(diags.is_empty(), "{}: {diags:?}", file.path().display());
+            }
+        }
+    }
+
+    #[test]
+    fn stale_preset_variables_are_reported() {
+        let body = crate::INCLUDE_PRESETS_DIR
+            .get_file("orca")
+            .and_then(include_dir::File::contents_utf8)
+            .unwrap();
+        let diags = lint(body, &template::parse_frontmatter(body).unwrap());
+        for var in ["dft_level", "memory", "molecule"] {
+            assert!(
+                diags.iter().any(|d| d.check == Check::UndeclaredVariable
+                    && d.message.starts_with(&format!("`{var}`"))),
+                "{var}: {diags:?}"
+            );
+        }
+    }
+}
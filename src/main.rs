@@ -1,31 +1,77 @@
 #![allow(clippy::multiple_crate_versions)]
 
 use crate::config::{Config, ModelConfig, ResourcesConfig};
+use crate::diagnostics::DiagnosticsFormat;
 use crate::molecule::Molecule;
+use crate::naming::Naming;
+use crate::software::Software;
 use crate::template::Template;
+use crate::units::Memory;
 use clap::{Command, CommandFactory, Parser, Subcommand};
 use clap_complete::{generate, Shell};
 use clap_verbosity_flag::{Verbosity, WarnLevel};
 use color_eyre::eyre::{bail, eyre, Report as Error, Result, WrapErr};
 use include_dir::{include_dir, Dir};
 use log::{debug, error, info, warn};
-use std::fs::{read_dir, write};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs::{create_dir_all, read, read_dir, read_to_string, rename, write};
+use std::path::{Path, PathBuf};
 
 mod config;
+mod diagnostics;
 mod elements;
+mod knowledge;
+mod lint;
+mod manifest;
 mod molecule;
+mod naming;
+mod output;
+mod params;
+mod resources;
+mod results;
+mod scheduler;
+mod software;
+mod solvents;
+mod status;
+mod sweep;
+mod syntax;
 mod template;
+mod units;
 mod validation;
+mod watch;
+mod workflow;
 
 const PRESETS_DIR: &str = "presets";
+const SCHEDULERS_DIR: &str = "schedulers";
 const TEMPLATES_DIR: &str = "templates";
+const WORKFLOWS_DIR: &str = "workflows";
+
+/// Context keys [`build_context`] fills from `[model]`, `[resources]` and
+/// their flags.
+const CONFIG_KEYS: [&str; 10] = [
+    "method",
+    "basis_set",
+    "charge",
+    "mult",
+    "dispersion",
+    "solvent",
+    "solvation",
+    "solvation_model",
+    "nprocs",
+    "mem",
+];
+
+/// Context keys `gedent restart` adds on top of the recorded ones.
+const RESTART_KEYS: [&str; 2] = ["restart", "restart_reason"];
 
 static INCLUDE_PRESETS_DIR: Dir = include_dir!("presets");
+static INCLUDE_SCHEDULERS_DIR: Dir = include_dir!("schedulers");
 static INCLUDE_TEMPLATES_DIR: Dir = include_dir!("templates");
 static GEDENT_CONFIG: &str = include_str!("../gedent.toml");
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
+#[allow(clippy::struct_excessive_bools)]
 struct GenOptions {
     software: Option<String>,
     ext: Option<String>,
@@ -38,25 +84,197 @@ struct GenOptions {
     charge: Option<i64>,
     mult: Option<i64>,
     nprocs: Option<i64>,
-    mem: Option<i64>,
+    mem: Option<Memory>,
+    /// Extra context set by a workflow step; inserted after `[parameters]`.
+    parameters: toml::map::Map<String, toml::Value>,
     /// Raw `KEY=VALUE` strings from `--var`; parsed and inserted into context
     /// after `[parameters]`, so they win over config file values.
     vars: Vec<String>,
+    /// Overrides `[gedent] filename_pattern`.
+    filename_pattern: Option<String>,
+    /// Directory the filename pattern is resolved in (default: current).
+    outdir: Option<PathBuf>,
+    /// Copy each source geometry next to its input.
+    copy_xyz: bool,
+    /// Render a `[scheduler]` submit script next to each input.
+    submit_script: bool,
+    /// Write a manifest recording how every input was made.
+    manifest: bool,
+    /// Only write files whose content changed, and summarize what changed.
+    incremental: bool,
+    /// Files an earlier run of this `--watch` session wrote, with their
+    /// hashes. They may be replaced as long as nobody edited them since.
+    written: HashMap<PathBuf, String>,
     dry_run: bool,
+    /// Inputs go to stdout, so existing files are never touched.
+    print: bool,
+    overwrite: Overwrite,
     show_context: bool,
+    diagnostics_format: DiagnosticsFormat,
+    /// File for json and sarif findings instead of stdout.
+    diagnostics_output: Option<PathBuf>,
+    /// Treat validation warnings as errors, on top of `[validation] strict`.
+    strict: bool,
+}
+
+/// What to do when an input would replace a file that already exists.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Overwrite {
+    /// Abort before writing anything.
+    #[default]
+    Refuse,
+    /// Replace the existing file.
+    Force,
+    /// Rename the existing file with a `.bak` suffix, then write.
+    Backup,
+}
+
+impl Overwrite {
+    /// Mode selected by `--force` and `--backup`.
+    const fn from_flags(force: bool, backup: bool) -> Self {
+        if force {
+            Self::Force
+        } else if backup {
+            Self::Backup
+        } else {
+            Self::Refuse
+        }
+    }
+}
+
+/// What a generated file is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputKind {
+    /// Input rendered from the template.
+    Input,
+    /// Submit script for an input.
+    Script,
+    /// Copy of a source geometry.
+    Geometry,
+    /// Record of how the other files were generated.
+    Manifest,
+    /// Hashes of the files an incremental run wrote.
+    Hashes,
 }
 
 #[derive(Debug)]
 struct Input {
     filename: PathBuf,
     content: String,
+    /// Geometry the input was rendered from, if any.
+    source: Option<PathBuf>,
+    kind: OutputKind,
+    /// How the input was rendered, with `--manifest`.
+    record: Option<manifest::Record>,
 }
 
 impl Input {
-    fn write(self) -> Result<(), Error> {
+    fn write(self, overwrite: Overwrite) -> Result<(), Error> {
+        if let Some(dir) = self.filename.parent().filter(|d| !d.as_os_str().is_empty()) {
+            create_dir_all(dir)
+                .wrap_err_with(|| format!("Failed to create directory {}", dir.display()))?;
+        }
+        if overwrite == Overwrite::Backup && self.filename.exists() {
+            let backup = backup_path(&self.filename);
+            info!(
+                "Backing up {} to {}",
+                self.filename.display(),
+                backup.display()
+            );
+            rename(&self.filename, &backup).wrap_err("Failed to back up existing input.")?;
+        }
         info!("Writing {}", self.filename.display());
         write(&self.filename, &self.content).wrap_err("Failed to save input.")
     }
+
+    /// What writing the input would do to the file on disk, comparing
+    /// content hashes.
+    fn change(&self) -> Change {
+        match read(&self.filename) {
+            Ok(existing) if manifest::sha256(&existing) == manifest::sha256(&self.content) => {
+                Change::Unchanged
+            }
+            Ok(_) => Change::Updated,
+            Err(_) => Change::Created,
+        }
+    }
+
+    /// Whether the file on disk is still what an earlier run of this watch
+    /// session wrote to it.
+    fn is_own(&self, written: &HashMap<PathBuf, String>) -> bool {
+        written.get(&self.filename).is_some_and(|hash| {
+            read(&self.filename).is_ok_and(|disk| manifest::sha256(disk) == *hash)
+        })
+    }
+
+    fn describe_source(&self) -> String {
+        self.source
+            .as_ref()
+            .map_or_else(|| "the template".to_string(), |p| p.display().to_string())
+    }
+}
+
+/// Effect of writing an input on the file already at its path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Change {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+/// First free name among `<file>.bak`, `<file>.bak.1`, `<file>.bak.2`, ...
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".bak");
+    let mut candidate = PathBuf::from(&name);
+    let mut n = 0;
+    while candidate.exists() {
+        n += 1;
+        let mut numbered = name.clone();
+        numbered.push(format!(".{n}"));
+        candidate = PathBuf::from(numbered);
+    }
+    candidate
+}
+
+/// Refuse to generate when two inputs would share a filename, or (unless
+/// `--force` or `--backup` was given) when an input already exists on disk.
+/// Runs before anything is written so a failed run leaves no partial output.
+fn check_outputs(inputs: &[Input], overwrite: Overwrite) -> Result<(), Error> {
+    let mut seen: HashMap<&Path, &Input> = HashMap::new();
+    let mut collisions = vec![];
+    for input in inputs {
+        if let Some(first) = seen.insert(&input.filename, input) {
+            collisions.push(format!(
+                "{} and {} would both write {}",
+                first.describe_source(),
+                input.describe_source(),
+                input.filename.display()
+            ));
+        }
+    }
+    if !collisions.is_empty() {
+        bail!(
+            "Output filenames collide:\n  {}\nRename the geometries or generate them separately.",
+            collisions.join("\n  ")
+        );
+    }
+
+    if overwrite == Overwrite::Refuse {
+        let existing: Vec<String> = inputs
+            .iter()
+            .filter(|i| i.filename.exists())
+            .map(|i| i.filename.display().to_string())
+            .collect();
+        if !existing.is_empty() {
+            bail!(
+                "Refusing to overwrite existing file(s): {}. Use --force to overwrite them \
+                 or --backup to keep the previous version.",
+                existing.join(", ")
+            );
+        }
+    }
+    Ok(())
 }
 
 #[derive(Parser, Debug)]
@@ -126,18 +344,138 @@ enum Mode {
         /// Set nprocs
         #[arg(long, default_value = None)]
         nprocs: Option<i64>,
-        /// Set mem
+        /// Set mem (MB, or with a unit such as 4GB)
         #[arg(long, default_value = None)]
-        mem: Option<i64>,
+        mem: Option<Memory>,
         /// Set an arbitrary template variable (KEY=VALUE, value parsed as TOML)
         #[arg(long = "var", value_name = "KEY=VALUE")]
         vars: Vec<String>,
+        /// Write inputs under this directory instead of the current one
+        #[arg(short, long, value_name = "DIR", default_value = None)]
+        outdir: Option<PathBuf>,
+        /// Copy each xyz file next to its generated input
+        #[arg(long, default_value_t = false)]
+        copy_xyz: bool,
+        /// Also write a batch script for the [scheduler] next to each input
+        #[arg(long, default_value_t = false)]
+        submit_script: bool,
+        /// Write a manifest recording how each input was made, for `gedent regen`
+        #[arg(long, default_value_t = false, conflicts_with = "print")]
+        manifest: bool,
+        /// Only write files whose content changed and summarize what changed
+        #[arg(long, default_value_t = false, conflicts_with = "print")]
+        incremental: bool,
+        /// Render again whenever the template, a config file or a geometry changes
+        #[arg(short, long, default_value_t = false)]
+        watch: bool,
+        /// Generate every combination of these values (KEY=VALUE,VALUE,...)
+        #[arg(long = "sweep", value_name = "KEY=VALUES")]
+        sweeps: Vec<String>,
+        /// TOML file of values to sweep (KEY = [VALUE, ...])
+        #[arg(long, value_name = "FILE", default_value = None)]
+        sweep_file: Option<PathBuf>,
         /// Validate and show what would be generated without writing any files
         #[arg(long, default_value_t = false)]
         dry_run: bool,
+        /// Overwrite inputs that already exist
+        #[arg(short, long, default_value_t = false, conflicts_with = "backup")]
+        force: bool,
+        /// Keep existing inputs as <file>.bak before overwriting them
+        #[arg(long, default_value_t = false)]
+        backup: bool,
         /// Print the full Tera context as JSON (useful for template debugging)
         #[arg(long, default_value_t = false)]
         show_context: bool,
+        /// Format of validation findings; json and sarif are written to stdout
+        #[arg(long, value_enum, default_value_t = DiagnosticsFormat::Text)]
+        diagnostics_format: DiagnosticsFormat,
+        /// Write json or sarif findings to this file instead of stdout
+        #[arg(long, value_name = "FILE", default_value = None)]
+        diagnostics_output: Option<PathBuf>,
+        /// Treat validation warnings as errors
+        #[arg(long, default_value_t = false)]
+        strict: bool,
+    },
+    /// Reproduce the inputs recorded in a manifest written by `gen --manifest`
+    Regen {
+        /// Manifest file (<name>.manifest.json)
+        manifest: PathBuf,
+        /// Show what would be regenerated without writing any files
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// Overwrite inputs that differ from the regenerated ones
+        #[arg(short, long, default_value_t = false, conflicts_with = "backup")]
+        force: bool,
+        /// Keep differing inputs as <file>.bak before overwriting them
+        #[arg(long, default_value_t = false)]
+        backup: bool,
+    },
+    /// Write a restart input for a failed or unconverged ORCA or Gaussian job
+    Restart {
+        /// Output file of the job
+        output: PathBuf,
+        /// Template to use instead of the one from a manifest or the job type
+        #[arg(short, long, default_value = None)]
+        template: Option<String>,
+        /// Set an arbitrary template variable (KEY=VALUE, value parsed as TOML)
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+        /// Print to screen and don't save file
+        #[arg(short, long, default_value_t = false)]
+        print: bool,
+        /// Validate and show what would be generated without writing any files
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// Overwrite files that already exist
+        #[arg(short, long, default_value_t = false, conflicts_with = "backup")]
+        force: bool,
+        /// Keep existing files as <file>.bak before overwriting them
+        #[arg(long, default_value_t = false)]
+        backup: bool,
+    },
+    /// Show which generated jobs are pending, running, finished or failed
+    Status {
+        /// Project directory to scan
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+        /// Minutes without output after which an unfinished job counts as failed
+        #[arg(long, value_name = "MINUTES", default_value_t = 60)]
+        stale_after: u64,
+    },
+    /// Tabulate energies, thermochemistry and timings of ORCA and Gaussian outputs
+    Results {
+        /// Output files, or directories to search for .out and .log files
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+        /// Energy that relative energies compare
+        #[arg(long, value_enum, default_value_t = results::EnergyKind::Electronic)]
+        energy: results::EnergyKind,
+        /// Name of the output relative energies are measured from (default: lowest)
+        #[arg(long, value_name = "NAME", default_value = None)]
+        reference: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = results::ResultsFormat::Table)]
+        format: results::ResultsFormat,
+    },
+    /// Validate inputs for a template without rendering anything
+    Check {
+        /// The template to look for in ~/.config/gedent/templates
+        template_name: String,
+        /// xyz files
+        #[arg(value_name = "XYZ files")]
+        xyz_files: Option<Vec<PathBuf>>,
+        /// Override software (used for template disambiguation)
+        #[arg(long, default_value = None)]
+        software: Option<String>,
+        /// Set an arbitrary template variable (KEY=VALUE, value parsed as TOML)
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+        /// Format of the report written to stdout
+        #[arg(long, value_enum, default_value_t = DiagnosticsFormat::Text)]
+        diagnostics_format: DiagnosticsFormat,
+        /// Treat validation warnings as errors
+        #[arg(long, default_value_t = false)]
+        strict: bool,
     },
     // Subcommand to deal with configurations
     /// Access gedent configuration
@@ -151,6 +489,11 @@ enum Mode {
         #[command(subcommand)]
         template_subcommand: TemplateSubcommand,
     },
+    /// Render multi-step workflows
+    Workflow {
+        #[command(subcommand)]
+        workflow_subcommand: WorkflowSubcommand,
+    },
     /// Shell completion endpoint — hidden from normal help output.
     /// `gedent _complete templates` prints one completable name per line.
     #[command(hide = true, name = "_complete")]
@@ -188,16 +531,21 @@ enum Mode {
         /// Set nprocs
         #[arg(long, default_value = None)]
         nprocs: Option<i64>,
-        /// Set mem
+        /// Set mem (MB, or with a unit such as 4GB)
         #[arg(long, default_value = None)]
-        mem: Option<i64>,
+        mem: Option<Memory>,
     },
 }
 
 #[derive(Debug, Subcommand)]
 enum TemplateSubcommand {
     /// Prints the unformatted template to stdout
-    Print { template: String },
+    Print {
+        template: String,
+        /// Print the frontmatter and parameter schema instead of the body
+        #[arg(long, default_value_t = false)]
+        info: bool,
+    },
     /// Create a new template from a preset located in ~/.config/gedent/presets
     New {
         template_name: String,
@@ -207,6 +555,69 @@ enum TemplateSubcommand {
     List {},
     /// Edit a given template
     Edit { template: String },
+    /// Check templates for undeclared, unused and misspelled variables and
+    /// unknown functions or filters
+    Lint {
+        /// Templates to check; all installed templates if omitted
+        templates: Vec<String>,
+        /// Format of the report written to stdout
+        #[arg(long, value_enum, default_value_t = DiagnosticsFormat::Text)]
+        diagnostics_format: DiagnosticsFormat,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum WorkflowSubcommand {
+    /// Render every step of a workflow into numbered directories per molecule
+    Gen {
+        /// Workflow file, or the name of one in ~/.config/gedent/workflows
+        workflow: String,
+        /// xyz files
+        #[arg(value_name = "XYZ files")]
+        xyz_files: Option<Vec<PathBuf>>,
+        /// Override software (used for template disambiguation)
+        #[arg(long, default_value = None)]
+        software: Option<String>,
+        /// Set nprocs
+        #[arg(long, default_value = None)]
+        nprocs: Option<i64>,
+        /// Set mem (MB, or with a unit such as 4GB)
+        #[arg(long, default_value = None)]
+        mem: Option<Memory>,
+        /// Set an arbitrary template variable (KEY=VALUE, value parsed as TOML)
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+        /// Write the molecule directories under this directory
+        #[arg(short, long, value_name = "DIR", default_value = None)]
+        outdir: Option<PathBuf>,
+        /// Also write a batch script for the [scheduler] next to each input
+        #[arg(long, default_value_t = false)]
+        submit_script: bool,
+        /// Write a manifest recording how each input was made, for `gedent regen`
+        #[arg(long, default_value_t = false)]
+        manifest: bool,
+        /// Only write files whose content changed and summarize what changed
+        #[arg(long, default_value_t = false)]
+        incremental: bool,
+        /// Validate and show what would be generated without writing any files
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// Overwrite inputs that already exist
+        #[arg(short, long, default_value_t = false, conflicts_with = "backup")]
+        force: bool,
+        /// Keep existing inputs as <file>.bak before overwriting them
+        #[arg(long, default_value_t = false)]
+        backup: bool,
+        /// Format of validation findings; json and sarif are written to stdout
+        #[arg(long, value_enum, default_value_t = DiagnosticsFormat::Text)]
+        diagnostics_format: DiagnosticsFormat,
+        /// Write json or sarif findings to this file instead of stdout
+        #[arg(long, value_name = "FILE", default_value = None)]
+        diagnostics_output: Option<PathBuf>,
+        /// Treat validation warnings as errors
+        #[arg(long, default_value_t = false)]
+        strict: bool,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -268,15 +679,32 @@ fn main() -> Result<()> {
                 nprocs,
                 mem,
                 vars,
+                outdir,
+                copy_xyz,
+                submit_script,
+                manifest,
+                incremental,
+                watch,
+                sweeps,
+                sweep_file,
                 dry_run,
+                force,
+                backup,
                 show_context,
+                diagnostics_format,
+                diagnostics_output,
+                strict,
             } => {
-                let mut molecules: Vec<(PathBuf, Molecule)> = vec![];
-                if let Some(files) = xyz_files {
-                    for file in files {
-                        molecules.push((file.clone(), Molecule::from_xyz(&file)?));
-                    }
+                if (print || show_context)
+                    && diagnostics_format != DiagnosticsFormat::Text
+                    && diagnostics_output.is_none()
+                {
+                    bail!(
+                        "--print and --show-context write to stdout; use --diagnostics-output \
+                         to write the findings to a file."
+                    );
                 }
+                let overwrite = Overwrite::from_flags(force, backup);
                 let opts = GenOptions {
                     software,
                     ext,
@@ -290,19 +718,150 @@ fn main() -> Result<()> {
                     nprocs,
                     mem,
                     vars,
+                    outdir,
+                    copy_xyz,
+                    submit_script,
+                    manifest,
+                    // Re-renders rewrite only what the change affected.
+                    incremental: incremental || (watch && !print),
                     dry_run,
+                    print,
+                    overwrite,
                     show_context,
+                    diagnostics_format,
+                    diagnostics_output,
+                    strict,
+                    ..GenOptions::default()
+                };
+                let gen = |written: &HashMap<PathBuf, String>| {
+                    let sweep = sweep::Sweep::parse(&sweeps, sweep_file.as_deref())?;
+                    let opts = GenOptions {
+                        written: written.clone(),
+                        ..opts.clone()
+                    };
+                    let mut findings = vec![];
+                    let result = run_gen(
+                        &template_name,
+                        xyz_files.clone(),
+                        &sweep,
+                        &opts,
+                        &mut findings,
+                    );
+                    write_findings(&findings, &opts)?;
+                    result
+                };
+                if watch {
+                    let mut written = HashMap::new();
+                    watch::watch(
+                        || {
+                            watched_files(
+                                &template_name,
+                                xyz_files.as_deref(),
+                                &opts,
+                                sweep_file.as_deref(),
+                            )
+                        },
+                        || match gen(&written) {
+                            Ok(files) => written.extend(files),
+                            Err(e) => error!("{e:#}"),
+                        },
+                    );
+                }
+                gen(&HashMap::new())?;
+            }
+
+            Mode::Regen {
+                manifest,
+                dry_run,
+                force,
+                backup,
+            } => {
+                let overwrite = Overwrite::from_flags(force, backup);
+                let opts = GenOptions {
+                    dry_run,
+                    overwrite,
+                    ..GenOptions::default()
+                };
+                for input in regenerate(&manifest, &opts)? {
+                    input.write(overwrite)?;
+                }
+            }
+
+            Mode::Restart {
+                output,
+                template,
+                vars,
+                print,
+                dry_run,
+                force,
+                backup,
+            } => {
+                let overwrite = Overwrite::from_flags(force, backup);
+                let opts = GenOptions {
+                    vars,
+                    dry_run,
+                    print,
+                    overwrite,
+                    ..GenOptions::default()
                 };
-                let results = generate_input(template_name, molecules, &opts)?;
-                for input in results {
+                for input in restart_job(&output, template, &opts)? {
                     if print {
                         println!("{}", input.content);
                     } else {
-                        input.write()?;
+                        input.write(overwrite)?;
                     }
                 }
             }
 
+            Mode::Status { dir, stale_after } => {
+                let mut extensions: Vec<String> =
+                    status::INPUT_EXTENSIONS.map(String::from).to_vec();
+                extensions.push(Config::get()?.gedent.default_extension);
+                let jobs = status::scan(
+                    &dir,
+                    &extensions,
+                    std::time::Duration::from_secs(stale_after * 60),
+                );
+                if !jobs.is_empty() {
+                    println!("{}", status::table(&dir, &jobs));
+                }
+                println!("{}", status::summary(&jobs));
+            }
+
+            Mode::Results {
+                paths,
+                energy,
+                reference,
+                format,
+            } => {
+                let mut found = results::collect(&paths)?;
+                if found.is_empty() {
+                    bail!("No ORCA or Gaussian outputs found");
+                }
+                let names = results::names(&found);
+                results::set_relative(&mut found, &names, energy, reference.as_deref())?;
+                println!("{}", results::render(&found, &names, format));
+            }
+
+            Mode::Check {
+                template_name,
+                xyz_files,
+                software,
+                vars,
+                diagnostics_format,
+                strict,
+            } => {
+                let molecules = read_molecules(xyz_files)?;
+                let opts = GenOptions {
+                    software,
+                    vars,
+                    diagnostics_format,
+                    strict,
+                    ..GenOptions::default()
+                };
+                check_inputs(template_name, &molecules, &opts)?;
+            }
+
             Mode::Config { config_subcommand } => match config_subcommand {
                 ConfigSubcommand::Print { location } => {
                     let config = Config::get()?;
@@ -311,12 +870,63 @@ fn main() -> Result<()> {
                 ConfigSubcommand::Edit { global } => Config::edit(global)?,
             },
 
+            Mode::Workflow {
+                workflow_subcommand:
+                    WorkflowSubcommand::Gen {
+                        workflow,
+                        xyz_files,
+                        software,
+                        nprocs,
+                        mem,
+                        vars,
+                        outdir,
+                        submit_script,
+                        manifest,
+                        incremental,
+                        dry_run,
+                        force,
+                        backup,
+                        diagnostics_format,
+                        diagnostics_output,
+                        strict,
+                    },
+            } => {
+                let molecules = read_molecules(xyz_files)?;
+                let overwrite = Overwrite::from_flags(force, backup);
+                let opts = GenOptions {
+                    software,
+                    nprocs,
+                    mem,
+                    vars,
+                    outdir,
+                    submit_script,
+                    manifest,
+                    incremental,
+                    dry_run,
+                    overwrite,
+                    diagnostics_format,
+                    diagnostics_output,
+                    strict,
+                    ..GenOptions::default()
+                };
+                let mut findings = vec![];
+                let result = generate_workflow(&workflow, &molecules, &opts, &mut findings);
+                write_findings(&findings, &opts)?;
+                for input in result? {
+                    input.write(overwrite)?;
+                }
+            }
+
             Mode::Template {
                 template_subcommand,
             } => match template_subcommand {
-                TemplateSubcommand::Print { template } => {
+                TemplateSubcommand::Print { template, info } => {
                     let software = Config::get().ok().and_then(|c| c.gedent.software);
-                    Template::print_template(&template, software.as_deref())?;
+                    if info {
+                        Template::print_info(&template, software.as_deref())?;
+                    } else {
+                        Template::print_template(&template, software.as_deref())?;
+                    }
                 }
                 TemplateSubcommand::New {
                     software,
@@ -329,6 +939,10 @@ fn main() -> Result<()> {
                     let software = Config::get().ok().and_then(|c| c.gedent.software);
                     Template::edit_template(&template, software.as_deref())?;
                 }
+                TemplateSubcommand::Lint {
+                    templates,
+                    diagnostics_format,
+                } => lint_templates(templates, diagnostics_format)?,
             },
 
             Mode::Init {
@@ -353,7 +967,11 @@ fn main() -> Result<()> {
                     solvent,
                     solvation_model,
                 },
-                ResourcesConfig { nprocs, mem },
+                ResourcesConfig {
+                    nprocs,
+                    mem,
+                    mem_scope: None,
+                },
             )?,
 
             Mode::Complete {
@@ -531,6 +1149,16 @@ fn setup_gedent() -> Result<(), Error> {
             INCLUDE_TEMPLATES_DIR
                 .extract(templates)
                 .wrap_err("Failed to extract templates.")?;
+
+            info!("Generating submit script templates.");
+            let schedulers: PathBuf = [config_dir.clone(), Into::into(SCHEDULERS_DIR)]
+                .iter()
+                .collect();
+            std::fs::create_dir(&schedulers)
+                .wrap_err("Failed to create submit script templates dir.")?;
+            INCLUDE_SCHEDULERS_DIR
+                .extract(schedulers)
+                .wrap_err("Failed to extract submit script templates.")?;
         }
         Err(err) => bail!("Failed to check if gedent home exists, caused by {:?}", err),
     }
@@ -616,7 +1244,10 @@ fn build_context(
     }
 
     // Layer 2: resources config (what machine it runs on)
-    for (k, v) in [("nprocs", resources.nprocs), ("mem", resources.mem)] {
+    for (k, v) in [
+        ("nprocs", resources.nprocs),
+        ("mem", resources.mem.map(Memory::as_mb)),
+    ] {
         if let Some(v) = v {
             context.insert(k, &v);
         }
@@ -633,7 +1264,7 @@ fn build_context(
         ("charge", opts.charge),
         ("mult", opts.mult),
         ("nprocs", opts.nprocs),
-        ("mem", opts.mem),
+        ("mem", opts.mem.map(Memory::as_mb)),
     ] {
         if let Some(v) = v {
             context.insert(k, &v);
@@ -664,32 +1295,39 @@ fn parse_var(s: &str) -> Result<(String, toml::Value), Error> {
     if key.is_empty() {
         bail!("--var key cannot be empty in {s:?}");
     }
+    Ok((key.to_string(), parse_value(val_str)))
+}
+
+/// Parse a value given on the command line as a TOML literal, falling back to
+/// a plain string.
+fn parse_value(s: &str) -> toml::Value {
     // Wrap in a dummy key to let the TOML parser infer the type, then extract.
     // Falls back to a plain string for values that aren't valid TOML literals.
-    let value = toml::from_str::<toml::Table>(&format!("v = {val_str}"))
+    toml::from_str::<toml::Table>(&format!("v = {s}"))
         .ok()
         .and_then(|mut t| t.remove("v"))
-        .unwrap_or_else(|| toml::Value::String(val_str.to_string()));
-    Ok((key.to_string(), value))
+        .unwrap_or_else(|| toml::Value::String(s.to_string()))
 }
 
 fn render_inputs(
     template: &Template,
     molecules: Vec<(PathBuf, Molecule)>,
     context: &tera::Context,
-    extension: &str,
+    naming: &Naming,
 ) -> Result<Vec<Input>, Error> {
     let mut results: Vec<Input> = vec![];
+    let template_name = Path::new(&template.name)
+        .file_name()
+        .ok_or_else(|| eyre!("Can't retrieve template name, exiting.."))?
+        .to_string_lossy();
 
     if molecules.is_empty() {
-        let filename = PathBuf::from(&template.name).with_extension(extension);
-        let filename = filename
-            .file_name()
-            .ok_or_else(|| eyre!("Can't retrieve template name, exiting.."))?;
-
         results.push(Input {
-            filename: PathBuf::from(filename),
+            filename: naming.path(context, &template_name, &template_name)?,
             content: template.render(context)?,
+            source: None,
+            kind: OutputKind::Input,
+            record: None,
         });
     }
 
@@ -699,20 +1337,44 @@ fn render_inputs(
             .ok_or_else(|| eyre!("Can't retrieve stem from path {}", path.display()))?
             .to_string_lossy();
         results.push(Input {
-            filename: PathBuf::from(stem.as_ref()).with_extension(extension),
+            filename: naming.path(context, &stem, &template_name)?,
             content: template.render_with_molecule(context, &molecule, &stem)?,
+            source: Some(path),
+            kind: OutputKind::Input,
+            record: None,
         });
     }
 
     Ok(results)
 }
 
-fn generate_input(
-    template_name: String,
-    molecules: Vec<(PathBuf, Molecule)>,
-    opts: &GenOptions,
-) -> Result<Vec<Input>, Error> {
+fn read_molecules(xyz_files: Option<Vec<PathBuf>>) -> Result<Vec<(PathBuf, Molecule)>, Error> {
+    let mut molecules: Vec<(PathBuf, Molecule)> = vec![];
+    for file in xyz_files.unwrap_or_default() {
+        let molecule = Molecule::from_xyz(&file)?;
+        molecules.push((file, molecule));
+    }
+    Ok(molecules)
+}
+
+/// A resolved template with everything needed to validate and render it.
+struct Prepared {
+    template: Template,
+    context: tera::Context,
+    naming: Naming,
+    policy: validation::Policy,
+    /// Submit script renderer, with `--submit-script`.
+    scheduler: Option<scheduler::Scheduler>,
+}
+
+/// Resolve the template and build its full context: config, `[parameters]`,
+/// `--var` overrides and derived values, plus the output naming, the
+/// validation policy and the submit script template.
+fn prepare(template_name: String, opts: &GenOptions) -> Result<Prepared, Error> {
     let config = Config::get()?;
+    let mut policy = validation::Policy::from_config(&config.validation)?;
+    policy.strict |= opts.strict;
+    policy.node = resources::Node::from_config(&config.node);
 
     let software = opts
         .software
@@ -722,75 +1384,864 @@ fn generate_input(
     let template = Template::get(template_name, software)?;
 
     let mut context = build_context(&config.model, &config.resources, opts);
-    for (key, value) in config.parameters {
-        context.insert(key, &value);
+    for (key, value) in config.parameters.iter().chain(&opts.parameters) {
+        context.insert(key, value);
     }
     for s in &opts.vars {
         let (key, value) = parse_var(s)?;
         context.insert(key, &value);
     }
+    params::insert_defaults(&template.meta.params, &mut context);
+    // The template's own software wins over the lookup hint for resource semantics.
+    let target = template
+        .meta
+        .software
+        .as_deref()
+        .or(software)
+        .and_then(Software::from_name);
+    resources::insert_resolved(
+        &mut context,
+        config.resources.mem_scope.unwrap_or_default(),
+        target,
+    );
+    solvents::insert_properties(&mut context);
+
+    let scheduler = if opts.submit_script {
+        let software = template.meta.software.as_deref().or(software);
+        Some(scheduler::Scheduler::from_config(
+            &config.scheduler,
+            software,
+        )?)
+    } else {
+        None
+    };
+
+    let extension = opts.ext.clone().unwrap_or(config.gedent.default_extension);
+    let naming = Naming::new(
+        opts.filename_pattern
+            .clone()
+            .or(config.gedent.filename_pattern),
+        opts.outdir.clone(),
+        extension,
+    );
+    Ok(Prepared {
+        template,
+        context,
+        naming,
+        policy,
+        scheduler,
+    })
+}
+
+/// Validate every input, attaching the molecule path to each finding.
+fn collect_diagnostics(
+    prepared: &Prepared,
+    molecules: &[(PathBuf, Molecule)],
+) -> Vec<validation::Diagnostic> {
+    let Prepared {
+        template,
+        context,
+        policy,
+        ..
+    } = prepared;
+    if molecules.is_empty() {
+        return validation::validate(None, context, &template.meta, policy);
+    }
+    molecules
+        .iter()
+        .flat_map(|(path, molecule)| {
+            validation::validate(Some(molecule), context, &template.meta, policy)
+                .into_iter()
+                .map(|d| d.with_path(path))
+        })
+        .collect()
+}
 
-    let extension = opts
-        .ext
-        .as_ref()
-        .unwrap_or(&config.gedent.default_extension);
+/// Check the rendered inputs against their program's input syntax, attaching
+/// the input filename to each finding.
+fn syntax_diagnostics(prepared: &Prepared, inputs: &[Input]) -> Vec<validation::Diagnostic> {
+    let Some(software) = prepared
+        .template
+        .meta
+        .software
+        .as_deref()
+        .and_then(Software::from_name)
+    else {
+        return vec![];
+    };
+    let diags = inputs
+        .iter()
+        .flat_map(|input| {
+            syntax::check(software, &input.content)
+                .into_iter()
+                .map(|d| d.with_path(&input.filename))
+        })
+        .collect();
+    prepared.policy.apply(diags)
+}
+
+fn has_errors(diags: &[validation::Diagnostic]) -> bool {
+    diags
+        .iter()
+        .any(|d| d.severity == validation::Severity::Error)
+}
+
+/// `gedent check`: validate, render in memory to check the syntax of the
+/// inputs, and report on stdout without writing anything.
+fn check_inputs(
+    template_name: String,
+    molecules: &[(PathBuf, Molecule)],
+    opts: &GenOptions,
+) -> Result<(), Error> {
+    let prepared = prepare(template_name, opts)?;
+    let mut diags = collect_diagnostics(&prepared, molecules);
+    if !has_errors(&diags) {
+        let inputs = render_inputs(
+            &prepared.template,
+            molecules.to_vec(),
+            &prepared.context,
+            &prepared.naming,
+        )?;
+        diags.extend(syntax_diagnostics(&prepared, &inputs));
+    }
+    if diags.is_empty() && opts.diagnostics_format == DiagnosticsFormat::Text {
+        info!("No problems found.");
+    } else {
+        println!("{}", diagnostics::render(&diags, opts.diagnostics_format));
+    }
+    if has_errors(&diags) {
+        bail!("Validation failed.");
+    }
+    Ok(())
+}
+
+/// `gedent template lint`: lint the named templates, or all of them.
+fn lint_templates(names: Vec<String>, format: DiagnosticsFormat) -> Result<(), Error> {
+    let templates = if names.is_empty() {
+        Template::all()?
+    } else {
+        let software = Config::get().ok().and_then(|c| c.gedent.software);
+        names
+            .into_iter()
+            .map(|name| Template::get(name, software.as_deref()))
+            .collect::<Result<_, _>>()?
+    };
+    let diags: Vec<validation::Diagnostic> = templates
+        .iter()
+        .flat_map(|t| {
+            lint::lint(t.body(), &t.meta)
+                .into_iter()
+                .map(|d| d.with_path(&t.path))
+        })
+        .collect();
+    if diags.is_empty() && format == DiagnosticsFormat::Text {
+        info!("No problems found in {} template(s).", templates.len());
+    } else {
+        println!("{}", diagnostics::render(&diags, format));
+    }
+    if has_errors(&diags) {
+        bail!("Lint failed.");
+    }
+    Ok(())
+}
+
+fn generate_input(
+    template_name: String,
+    molecules: Vec<(PathBuf, Molecule)>,
+    opts: &GenOptions,
+    findings: &mut Vec<validation::Diagnostic>,
+) -> Result<Vec<Input>, Error> {
+    let label = Path::new(&template_name).file_name().map_or_else(
+        || template_name.clone(),
+        |n| n.to_string_lossy().into_owned(),
+    );
+    let mut inputs = render_checked(template_name, molecules, opts, findings)?;
+    add_manifest(&mut inputs, opts, &label)?;
+    finish_outputs(inputs, opts)
+}
+
+/// Render `template_name` for the geometries in `xyz_files`, once or per
+/// combination of `sweep`, and print or write the results. Findings for
+/// `--diagnostics-format json|sarif` are added to `findings`.
+fn run_gen(
+    template_name: &str,
+    xyz_files: Option<Vec<PathBuf>>,
+    sweep: &sweep::Sweep,
+    opts: &GenOptions,
+    findings: &mut Vec<validation::Diagnostic>,
+) -> Result<Vec<(PathBuf, String)>, Error> {
+    let molecules = read_molecules(xyz_files)?;
+    let results = if sweep.is_empty() {
+        generate_input(template_name.to_string(), molecules, opts, findings)?
+    } else {
+        generate_sweep(template_name, &molecules, opts, sweep, findings)?
+    };
+    let mut written = vec![];
+    for input in results {
+        if opts.print {
+            println!("{}", input.content);
+        } else {
+            written.push((input.filename.clone(), manifest::sha256(&input.content)));
+            input.write(opts.overwrite)?;
+        }
+    }
+    Ok(written)
+}
+
+/// Files whose changes `gedent gen --watch` re-renders on: the resolved
+/// template, the config chain (including local configs that don't exist
+/// yet), the geometries and the sweep file.
+fn watched_files(
+    template_name: &str,
+    xyz_files: Option<&[PathBuf]>,
+    opts: &GenOptions,
+    sweep_file: Option<&Path>,
+) -> Vec<PathBuf> {
+    let mut files = Config::collect_chain().unwrap_or_default();
+    files.extend(Config::local_candidates().unwrap_or_default());
+    let software = opts
+        .software
+        .clone()
+        .or_else(|| Config::get().ok().and_then(|c| c.gedent.software));
+    if let Ok(template) = Template::get(template_name.to_string(), software.as_deref()) {
+        files.push(template.path);
+    }
+    files.extend(xyz_files.unwrap_or_default().iter().cloned());
+    files.extend(sweep_file.map(Path::to_path_buf));
+    files
+}
+
+/// With `--manifest`, add `<label>.manifest.json` to the output directory,
+/// recording how each input was rendered so `gedent regen` can reproduce it.
+fn add_manifest(inputs: &mut Vec<Input>, opts: &GenOptions, label: &str) -> Result<(), Error> {
+    if !opts.manifest || opts.print {
+        return Ok(());
+    }
+    let dir = opts.outdir.clone().unwrap_or_default();
+    let manifest = manifest::Manifest::new(
+        &dir,
+        inputs.iter().filter_map(|i| {
+            i.record
+                .as_ref()
+                .map(|r| (i.filename.as_path(), i.content.as_str(), r))
+        }),
+    )?;
+    inputs.push(Input {
+        filename: dir.join(format!("{label}.{}", manifest::SUFFIX)),
+        content: serde_json::to_string_pretty(&manifest)? + "\n",
+        source: None,
+        kind: OutputKind::Manifest,
+        record: None,
+    });
+    Ok(())
+}
+
+/// Validate, render and syntax-check the inputs for one template, together
+/// with their submit scripts and geometry copies. Reports every finding, or
+/// adds it to `findings` for `--diagnostics-format json|sarif`, and fails if
+/// any is an error.
+fn render_checked(
+    template_name: String,
+    molecules: Vec<(PathBuf, Molecule)>,
+    opts: &GenOptions,
+    findings: &mut Vec<validation::Diagnostic>,
+) -> Result<Vec<Input>, Error> {
+    let prepared = prepare(template_name, opts)?;
 
     if opts.show_context {
-        let json = context.clone().into_json();
+        let json = prepared.context.clone().into_json();
         println!("{}", serde_json::to_string_pretty(&json)?);
     }
 
     // Run validation on all inputs before rendering anything, so the user
     // sees every problem at once rather than one per run.
-    let mut has_errors = false;
-    if molecules.is_empty() {
-        for d in validation::validate(None, &context, &template.meta.requires) {
-            emit_diagnostic(&template.name, &d);
-            if d.severity == validation::Severity::Error {
-                has_errors = true;
+    let mut diags = collect_diagnostics(&prepared, &molecules);
+    let mut inputs = vec![];
+    if !has_errors(&diags) {
+        inputs = render_inputs(
+            &prepared.template,
+            molecules,
+            &prepared.context,
+            &prepared.naming,
+        )?;
+        diags.extend(syntax_diagnostics(&prepared, &inputs));
+        if opts.manifest && !opts.print {
+            for input in &mut inputs {
+                input.record = Some(manifest::Record::new(
+                    &prepared.template,
+                    &prepared.context,
+                    input.source.as_deref(),
+                )?);
             }
         }
+        if let Some(scheduler) = &prepared.scheduler {
+            let scripts = inputs
+                .iter()
+                .map(|input| {
+                    let (filename, content) =
+                        scheduler.render(&prepared.context, &input.filename)?;
+                    Ok(Input {
+                        filename,
+                        content,
+                        source: input.source.clone(),
+                        kind: OutputKind::Script,
+                        record: None,
+                    })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            inputs.extend(scripts);
+        }
+        if opts.copy_xyz && !opts.print {
+            let neb = prepared
+                .template
+                .meta
+                .jobtype
+                .as_deref()
+                .is_some_and(|j| j.starts_with("neb"));
+            inputs.extend(geometry_copies(&inputs, neb)?);
+        }
+    }
+    let failed = has_errors(&diags);
+    if opts.diagnostics_format == DiagnosticsFormat::Text {
+        for d in &diags {
+            let name = d.path.as_ref().map_or_else(
+                || prepared.template.name.clone(),
+                |p| {
+                    p.file_stem()
+                        .map_or_else(|| p.to_string_lossy(), |s| s.to_string_lossy())
+                        .into_owned()
+                },
+            );
+            emit_diagnostic(&name, d);
+        }
     } else {
-        for (path, molecule) in &molecules {
-            let name = path
-                .file_stem()
-                .map_or_else(|| path.to_string_lossy(), |s| s.to_string_lossy());
-            for d in validation::validate(Some(molecule), &context, &template.meta.requires) {
-                emit_diagnostic(&name, &d);
-                if d.severity == validation::Severity::Error {
-                    has_errors = true;
+        findings.extend(diags);
+    }
+    if failed {
+        bail!("Validation failed — fix the errors above before generating.");
+    }
+    Ok(inputs)
+}
+
+/// Write the findings collected for `--diagnostics-format json|sarif` as one
+/// report, to `--diagnostics-output` or stdout.
+fn write_findings(findings: &[validation::Diagnostic], opts: &GenOptions) -> Result<(), Error> {
+    if opts.diagnostics_format == DiagnosticsFormat::Text {
+        return Ok(());
+    }
+    let report = diagnostics::render(findings, opts.diagnostics_format);
+    match &opts.diagnostics_output {
+        Some(path) => write(path, report + "\n")
+            .wrap_err_with(|| format!("Failed to write {}", path.display()))?,
+        None => println!("{report}"),
+    }
+    Ok(())
+}
+
+/// Apply `--dry-run` and the overwrite checks to the inputs about to be
+/// written, returning those the caller should write.
+fn finish_outputs(mut inputs: Vec<Input>, opts: &GenOptions) -> Result<Vec<Input>, Error> {
+    // A geometry copied next to several inputs in one directory (as in a
+    // sweep) is written once.
+    let mut copied = HashSet::new();
+    inputs.retain(|i| i.kind != OutputKind::Geometry || copied.insert(i.filename.clone()));
+    let overwrite = opts.overwrite;
+    let mut summary = None;
+    let mut hashes = vec![];
+    // Files gedent may replace without --force: those this watch session
+    // wrote and, with --incremental, those an earlier run generated.
+    let is_own = |i: &Input| {
+        i.is_own(&opts.written) || (opts.incremental && manifest::is_generated(&i.filename))
+    };
+    if opts.incremental {
+        // Check collisions among everything rendered, then keep only the
+        // files that change; those that exist and were not generated by
+        // gedent are still refused below unless --force or --backup.
+        check_outputs(&inputs, Overwrite::Force)?;
+        hashes = hashes_files(&inputs);
+        let mut counts = HashMap::new();
+        inputs.retain(|input| {
+            let change = input.change();
+            if input.kind == OutputKind::Input {
+                *counts.entry(change).or_insert(0) += 1;
+            }
+            change != Change::Unchanged
+        });
+        let count = |change| counts.get(&change).copied().unwrap_or(0);
+        summary = Some(format!(
+            "{}{} created, {} updated, {} unchanged",
+            if opts.dry_run { "dry-run: " } else { "" },
+            count(Change::Created),
+            count(Change::Updated),
+            count(Change::Unchanged)
+        ));
+    }
+    if opts.dry_run {
+        check_outputs(&inputs, Overwrite::Force)?;
+        if let Some(summary) = summary {
+            println!("{summary}");
+        }
+        for input in &inputs {
+            let action = match (input.filename.exists(), overwrite) {
+                (false, _) => "write",
+                (true, _) if is_own(input) => "overwrite",
+                (true, Overwrite::Refuse) => "refuse to overwrite",
+                (true, Overwrite::Force) => "overwrite",
+                (true, Overwrite::Backup) => "back up and overwrite",
+            };
+            println!("dry-run: would {action} {}", input.filename.display());
+        }
+        return Ok(vec![]);
+    }
+    if !opts.print {
+        // A watch session replaces what it wrote itself; anything else
+        // that exists is treated as usual.
+        let (own, mut others): (Vec<_>, Vec<_>) = inputs.into_iter().partition(is_own);
+        check_outputs(&others, overwrite)?;
+        others.extend(own);
+        others.extend(hashes);
+        inputs = others;
+    }
+    if let Some(summary) = summary {
+        println!("{summary}");
+    }
+
+    Ok(inputs)
+}
+
+/// The [`manifest::HASHES`] file of every directory `inputs` go to, with the
+/// hashes of the inputs added to those recorded before. Files that would not
+/// change are left out.
+fn hashes_files(inputs: &[Input]) -> Vec<Input> {
+    let mut dirs: HashMap<&Path, Vec<&Input>> = HashMap::new();
+    for input in inputs {
+        let dir = input.filename.parent().unwrap_or_else(|| Path::new(""));
+        dirs.entry(dir).or_default().push(input);
+    }
+    dirs.into_iter()
+        .filter_map(|(dir, inputs)| {
+            let mut hashes = manifest::recorded_hashes(dir);
+            for input in inputs {
+                if let Some(name) = input.filename.file_name() {
+                    hashes.insert(
+                        name.to_string_lossy().into_owned(),
+                        manifest::sha256(&input.content),
+                    );
                 }
             }
+            let hashes = Input {
+                filename: dir.join(manifest::HASHES),
+                content: manifest::hashes_file(&hashes),
+                source: None,
+                kind: OutputKind::Hashes,
+                record: None,
+            };
+            (hashes.change() != Change::Unchanged).then_some(hashes)
+        })
+        .collect()
+}
+
+/// Copies of the source geometries next to their inputs, one per directory.
+/// With `neb`, the product geometry `<stem>.end.xyz` next to each source is
+/// copied too, where the NEB templates expect it. Files already in place with
+/// the same content are left alone.
+fn geometry_copies(inputs: &[Input], neb: bool) -> Result<Vec<Input>, Error> {
+    let mut seen = HashSet::new();
+    let mut copies = vec![];
+    for input in inputs {
+        let Some(source) = &input.source else {
+            continue;
+        };
+        let mut files = vec![source.clone()];
+        if neb {
+            let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+            let end = source.with_file_name(format!("{stem}.end.xyz"));
+            if end.is_file() {
+                files.push(end);
+            }
+        }
+        for file in files {
+            let Some(file_name) = file.file_name() else {
+                continue;
+            };
+            let target = input
+                .filename
+                .parent()
+                .map_or_else(|| PathBuf::from(file_name), |dir| dir.join(file_name));
+            if !seen.insert(target.clone()) {
+                continue;
+            }
+            let content = read_to_string(&file)
+                .wrap_err_with(|| format!("Failed to read {}", file.display()))?;
+            if read_to_string(&target).is_ok_and(|existing| existing == content) {
+                continue;
+            }
+            copies.push(Input {
+                filename: target,
+                content,
+                source: Some(source.clone()),
+                kind: OutputKind::Geometry,
+                record: None,
+            });
         }
     }
-    if has_errors {
-        bail!("Validation failed — fix the errors above before generating.");
+    Ok(copies)
+}
+
+/// Render `template_name` once per combination of `sweep`, each with a
+/// filename that includes the swept values, plus a `<template>.sweep.json`
+/// manifest mapping every input to its combination.
+fn generate_sweep(
+    template_name: &str,
+    molecules: &[(PathBuf, Molecule)],
+    opts: &GenOptions,
+    sweep: &sweep::Sweep,
+    findings: &mut Vec<validation::Diagnostic>,
+) -> Result<Vec<Input>, Error> {
+    let config = Config::get()?;
+    let pattern = opts
+        .filename_pattern
+        .clone()
+        .or(config.gedent.filename_pattern)
+        .unwrap_or_else(|| naming::DEFAULT_PATTERN.to_string());
+    let pattern = sweep::unique_pattern(&pattern, sweep.keys());
+
+    let combinations = sweep.combinations();
+    info!("Sweeping {} combinations", combinations.len());
+    let mut inputs = vec![];
+    let mut entries = vec![];
+    for combination in combinations {
+        let mut combo_opts = GenOptions {
+            filename_pattern: Some(pattern.clone()),
+            ..opts.clone()
+        };
+        for (key, value) in &combination {
+            apply_override(&mut combo_opts, key, value)?;
+        }
+        let rendered = render_checked(
+            template_name.to_string(),
+            molecules.to_vec(),
+            &combo_opts,
+            findings,
+        )?;
+        let parameters: serde_json::Map<_, _> = combination
+            .iter()
+            .map(|(k, v)| (k.clone(), serde_json::json!(v)))
+            .collect();
+        entries.extend(
+            rendered
+                .iter()
+                .filter(|i| i.kind == OutputKind::Input)
+                .map(|i| sweep::Entry {
+                    file: i.filename.clone(),
+                    molecule: i.source.clone(),
+                    parameters: parameters.clone(),
+                }),
+        );
+        inputs.extend(rendered);
     }
 
-    if opts.dry_run {
-        if molecules.is_empty() {
-            let filename = PathBuf::from(&template.name)
-                .with_extension(extension)
-                .file_name()
-                .map_or_else(|| PathBuf::from("output"), PathBuf::from);
-            println!("dry-run: would write {}", filename.display());
-        } else {
-            for (path, _) in &molecules {
-                let stem = path.file_stem().unwrap_or(path.as_os_str());
-                let filename = PathBuf::from(stem).with_extension(extension);
-                println!("dry-run: would write {}", filename.display());
+    let short_name = Path::new(template_name)
+        .file_name()
+        .map_or_else(|| template_name.into(), |n| n.to_string_lossy());
+    let manifest = sweep::Manifest {
+        template: template_name.to_string(),
+        sweep: sweep.to_json(),
+        inputs: entries,
+    };
+    inputs.push(Input {
+        filename: opts
+            .outdir
+            .clone()
+            .unwrap_or_default()
+            .join(format!("{short_name}.sweep.json")),
+        content: serde_json::to_string_pretty(&manifest)? + "\n",
+        source: None,
+        kind: OutputKind::Manifest,
+        record: None,
+    });
+    add_manifest(&mut inputs, opts, &short_name)?;
+    finish_outputs(inputs, opts)
+}
+
+/// Set `key` the way its command-line flag would: `[model]` and
+/// `[resources]` keys go through `build_context` (so `solvent` also turns on
+/// solvation), anything else is set like `--var`.
+fn apply_override(opts: &mut GenOptions, key: &str, value: &toml::Value) -> Result<(), Error> {
+    let string = || {
+        value
+            .as_str()
+            .map_or_else(|| value.to_string(), ToString::to_string)
+    };
+    let integer = || {
+        value
+            .as_integer()
+            .ok_or_else(|| eyre!("`{key}` must be an integer, got {value}"))
+    };
+    match key {
+        "method" => opts.method = Some(string()),
+        "basis_set" => opts.basis_set = Some(string()),
+        "dispersion" => opts.dispersion = Some(string()),
+        "solvation_model" => opts.solvation_model = Some(string()),
+        "solvent" => opts.solvent = Some(Some(string())),
+        "charge" => opts.charge = Some(integer()?),
+        "mult" => opts.mult = Some(integer()?),
+        "nprocs" => opts.nprocs = Some(integer()?),
+        "mem" => {
+            opts.mem = Some(
+                value
+                    .clone()
+                    .try_into()
+                    .map_err(|e| eyre!("Invalid `mem` {value}: {e}"))?,
+            );
+        }
+        _ => {
+            opts.parameters.insert(key.to_string(), value.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Render every step of `workflow` for every molecule into
+/// `<outdir>/<name>/<NN>_<step>/`, wiring each step to the files earlier
+/// steps produce. All steps are validated before anything is written.
+fn generate_workflow(
+    workflow: &str,
+    molecules: &[(PathBuf, Molecule)],
+    opts: &GenOptions,
+    findings: &mut Vec<validation::Diagnostic>,
+) -> Result<Vec<Input>, Error> {
+    let label = Path::new(workflow).file_stem().map_or_else(
+        || workflow.to_string(),
+        |n| n.to_string_lossy().into_owned(),
+    );
+    let workflow = workflow::Workflow::load(workflow)?;
+    if molecules.is_empty() {
+        bail!("A workflow needs at least one xyz file.");
+    }
+    if let Some(description) = &workflow.description {
+        info!("Workflow: {description}");
+    }
+    let config = Config::get()?;
+    let hint = opts.software.clone().or(config.gedent.software);
+    let templates = workflow
+        .steps
+        .iter()
+        .map(|step| Template::get(step.template.clone(), hint.as_deref()))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let mut inputs = vec![];
+    for (path, molecule) in molecules {
+        let stem = path
+            .file_stem()
+            .ok_or_else(|| eyre!("Can't retrieve stem from path {}", path.display()))?
+            .to_string_lossy();
+        let mut wiring = workflow::Wiring::default();
+        for (index, (step, template)) in workflow.steps.iter().zip(&templates).enumerate() {
+            let dir = step.dir_name(index);
+            let software = template
+                .meta
+                .software
+                .as_deref()
+                .or(hint.as_deref())
+                .and_then(Software::from_name);
+            let model = step.model(&workflow.model);
+            let jobtype = template.meta.jobtype.as_deref();
+            let mut parameters = step.parameters(&workflow.parameters);
+            wiring.insert_into(&mut parameters, software);
+            if index > 0 && !wiring.has_geometry() {
+                warn!(
+                    "{stem}: step {dir} gets no geometry from earlier steps and starts from {}",
+                    path.display()
+                );
             }
+
+            let step_opts = GenOptions {
+                method: model.method.clone().or_else(|| opts.method.clone()),
+                basis_set: model.basis_set.or_else(|| opts.basis_set.clone()),
+                dispersion: model.dispersion.or_else(|| opts.dispersion.clone()),
+                solvent: model.solvent.map(Some).or_else(|| opts.solvent.clone()),
+                solvation_model: model
+                    .solvation_model
+                    .or_else(|| opts.solvation_model.clone()),
+                charge: model.charge.or(opts.charge),
+                mult: model.mult.or(opts.mult),
+                parameters,
+                filename_pattern: Some(format!("{{name}}/{dir}/{{name}}.{{ext}}")),
+                // Programs that read the geometry from disk need it in the
+                // step directory unless an earlier step provides one; NEB
+                // also needs its product geometry.
+                copy_xyz: !wiring.has_geometry() || jobtype.is_some_and(|j| j.starts_with("neb")),
+                ..opts.clone()
+            };
+            inputs.extend(render_checked(
+                template.name.clone(),
+                vec![(path.clone(), molecule.clone())],
+                &step_opts,
+                findings,
+            )?);
+
+            let method = model
+                .method
+                .as_deref()
+                .or(opts.method.as_deref())
+                .or(config.model.method.as_deref());
+            let made = workflow::products(software, jobtype, method, &stem);
+            wiring.record(&dir, software, made);
         }
-        return Ok(vec![]);
     }
+    add_manifest(&mut inputs, opts, &label)?;
+    finish_outputs(inputs, opts)
+}
 
-    render_inputs(&template, molecules, &context, extension)
+/// Render the inputs recorded in `manifest` again. Inputs already on disk
+/// with the same content are left alone.
+fn regenerate(manifest: &Path, opts: &GenOptions) -> Result<Vec<Input>, Error> {
+    let dir = manifest.parent().unwrap_or_else(|| Path::new(""));
+    let rendered = manifest::Manifest::load(manifest)?.regenerate(dir)?;
+    let total = rendered.len();
+    let inputs: Vec<Input> = rendered
+        .into_iter()
+        .map(|r| Input {
+            filename: r.file,
+            content: r.content,
+            source: r.molecule,
+            kind: OutputKind::Input,
+            record: None,
+        })
+        .filter(|input| input.change() != Change::Unchanged)
+        .collect();
+    info!(
+        "{} of {total} inputs already match the manifest",
+        total - inputs.len()
+    );
+    finish_outputs(inputs, opts)
+}
+
+/// Make `opts` render like the manifest `entry`: its context wins over the
+/// current config and its extension is kept. Returns the template name.
+fn use_recorded(opts: &mut GenOptions, entry: &manifest::Entry) -> Result<String, Error> {
+    if let serde_json::Value::Object(context) = &entry.record.context {
+        for (key, value) in context {
+            match toml::Value::try_from(value) {
+                Ok(value) => {
+                    opts.parameters.insert(key.clone(), value);
+                }
+                Err(_) => debug!("Skipping `{key}` from the manifest"),
+            }
+        }
+    }
+    opts.ext = entry
+        .file
+        .extension()
+        .map(|e| e.to_string_lossy().into_owned());
+    entry
+        .record
+        .template
+        .name
+        .clone()
+        .ok_or_else(|| eyre!("The manifest doesn't name the template; pass --template"))
+}
+
+/// Render a restart input, `<job>_restart`, next to the output of a failed
+/// job: its last geometry, the orbitals it left as the guess, and the
+/// template and context it was generated with when a manifest records them,
+/// or else the template for its program and job type.
+fn restart_job(
+    output_path: &Path,
+    template: Option<String>,
+    opts: &GenOptions,
+) -> Result<Vec<Input>, Error> {
+    let output = output::JobOutput::read(output_path)?;
+    if output.termination == output::Termination::Normal {
+        bail!(
+            "{} finished normally; there is nothing to restart.",
+            output_path.display()
+        );
+    }
+    info!("{}: {}", output_path.display(), output.termination);
+    let mut molecule = output
+        .geometry
+        .clone()
+        .ok_or_else(|| eyre!("No geometry found in {}", output_path.display()))?;
+    let stem = output_path
+        .file_stem()
+        .ok_or_else(|| eyre!("Can't retrieve stem from path {}", output_path.display()))?
+        .to_string_lossy();
+    let dir = output_path.parent().unwrap_or_else(|| Path::new(""));
+    let name = output::restart_name(&stem);
+    molecule.description = Some(format!("Last geometry of {stem}"));
+
+    let mut opts = GenOptions {
+        software: Some(output.software.to_string()),
+        charge: output.charge,
+        mult: output.mult,
+        outdir: Some(dir.to_path_buf()),
+        filename_pattern: Some(naming::DEFAULT_PATTERN.to_string()),
+        ..opts.clone()
+    };
+    let template_name = match (template, manifest::find_entry(output_path)?) {
+        (Some(template), _) => template,
+        (None, Some(entry)) => {
+            info!("Restarting with the template and context from its manifest");
+            use_recorded(&mut opts, &entry)?
+        }
+        (None, None) => {
+            let jobtype = output.jobtype.as_deref().unwrap_or("sp");
+            info!(
+                "No manifest records how {stem} was generated; using {}/{jobtype} \
+                 and the current config",
+                output.software
+            );
+            format!("{}/{jobtype}", output.software)
+        }
+    };
+    // The geometry comes from the output, not from an earlier workflow step.
+    opts.parameters.remove("geometry_file");
+    let [restart, reason] = RESTART_KEYS;
+    opts.parameters
+        .insert(restart.to_string(), toml::Value::Boolean(true));
+    opts.parameters.insert(
+        reason.to_string(),
+        toml::Value::String(output.termination.reason().to_string()),
+    );
+    let guess = output::guess_file(output.software, &stem)?;
+    if dir.join(&guess).is_file() {
+        opts.parameters
+            .insert("guess_file".to_string(), toml::Value::String(guess));
+    } else {
+        opts.parameters.remove("guess_file");
+        warn!(
+            "No {} next to {}; the restart starts from a fresh guess",
+            guess,
+            output_path.display()
+        );
+    }
+
+    let geometry = dir.join(format!("{name}.xyz"));
+    let mut inputs = render_checked(
+        template_name,
+        vec![(geometry.clone(), molecule.clone())],
+        &opts,
+        &mut vec![],
+    )?;
+    if !opts.print {
+        inputs.push(Input {
+            filename: geometry,
+            content: molecule.to_xyz(),
+            source: None,
+            kind: OutputKind::Geometry,
+            record: None,
+        });
+    }
+    finish_outputs(inputs, &opts)
 }
 
 fn emit_diagnostic(name: &str, d: &validation::Diagnostic) {
+    let code = d.check.code();
     match d.severity {
-        validation::Severity::Error => error!("{name}: {}", d.message),
-        validation::Severity::Warning => warn!("{name}: {}", d.message),
+        validation::Severity::Error => error!("{name}: [{code}] {}", d.message),
+        validation::Severity::Warning => warn!("{name}: [{code}] {}", d.message),
     }
 }
 
@@ -824,6 +2275,35 @@ mod tests {
         assert_eq!(ctx["nprocs"], 8);
     }
 
+    #[test]
+    fn build_context_sets_exactly_the_config_keys() {
+        let model = ModelConfig {
+            method: Some("pbe0".into()),
+            basis_set: Some("def2-tzvp".into()),
+            charge: Some(0),
+            mult: Some(1),
+            dispersion: Some("d3bj".into()),
+            solvent: Some("water".into()),
+            solvation_model: Some("smd".into()),
+        };
+        let resources = ResourcesConfig {
+            nprocs: Some(8),
+            mem: Some("2GB".parse::<Memory>().unwrap()),
+            ..ResourcesConfig::default()
+        };
+        let ctx = build_context(&model, &resources, &GenOptions::default()).into_json();
+        let mut keys: Vec<&str> = ctx
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        let mut expected = CONFIG_KEYS.to_vec();
+        keys.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(keys, expected);
+    }
+
     #[test]
     fn build_context_cli_overrides_config() {
         let model = ModelConfig {
@@ -893,10 +2373,87 @@ mod tests {
         assert!(ctx.get("solvation").is_none());
     }
 
+    fn naming(extension: &str) -> Naming {
+        Naming::new(None, None, extension.to_string())
+    }
+
+    fn input(filename: &Path, source: &str) -> Input {
+        Input {
+            filename: filename.to_path_buf(),
+            content: String::new(),
+            source: Some(PathBuf::from(source)),
+            kind: OutputKind::Input,
+            record: None,
+        }
+    }
+
+    #[test]
+    fn change_compares_content_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut input = input(&dir.path().join("mol.inp"), "mol.xyz");
+        input.content = "! pbe0".to_string();
+        assert_eq!(input.change(), Change::Created);
+        write(&input.filename, "! pbe0").unwrap();
+        assert_eq!(input.change(), Change::Unchanged);
+        write(&input.filename, "! b3lyp").unwrap();
+        assert_eq!(input.change(), Change::Updated);
+    }
+
+    #[test]
+    fn apply_override_uses_flags_then_parameters() {
+        let mut opts = GenOptions::default();
+        apply_override(&mut opts, "solvent", &toml::Value::from("water")).unwrap();
+        apply_override(&mut opts, "mult", &toml::Value::Integer(3)).unwrap();
+        apply_override(&mut opts, "grid", &toml::Value::Integer(5)).unwrap();
+        assert_eq!(opts.solvent, Some(Some("water".to_string())));
+        assert_eq!(opts.mult, Some(3));
+        assert_eq!(opts.parameters["grid"].as_integer(), Some(5));
+        assert!(apply_override(&mut opts, "nprocs", &toml::Value::from("many")).is_err());
+    }
+
+    #[test]
+    fn check_outputs_rejects_colliding_filenames() {
+        let inputs = [
+            input(Path::new("mol.inp"), "a/mol.xyz"),
+            input(Path::new("mol.inp"), "b/mol.xyz"),
+        ];
+        let err = check_outputs(&inputs, Overwrite::Force).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("a/mol.xyz and b/mol.xyz would both write mol.inp"));
+    }
+
+    #[test]
+    fn check_outputs_refuses_existing_files_unless_allowed() {
+        let dir = tempfile::tempdir().unwrap();
+        let existing = dir.path().join("mol.inp");
+        write(&existing, "old").unwrap();
+        let inputs = [input(&existing, "mol.xyz")];
+        assert!(check_outputs(&inputs, Overwrite::Refuse).is_err());
+        assert!(check_outputs(&inputs, Overwrite::Force).is_ok());
+        assert!(check_outputs(&inputs, Overwrite::Backup).is_ok());
+    }
+
+    #[test]
+    fn backup_keeps_every_previous_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mol.inp");
+        for version in ["v1", "v2", "v3"] {
+            let mut next = input(&path, "mol.xyz");
+            next.content = version.to_string();
+            next.write(Overwrite::Backup).unwrap();
+        }
+        let read = |name: &str| std::fs::read_to_string(dir.path().join(name)).unwrap();
+        assert_eq!(read("mol.inp"), "v3");
+        assert_eq!(read("mol.inp.bak"), "v1");
+        assert_eq!(read("mol.inp.bak.1"), "v2");
+    }
+
     #[test]
     fn render_inputs_no_molecules_uses_template_stem() {
         let template = Template::with_body("orca/sp", "hello");
-        let inputs = render_inputs(&template, vec![], &tera::Context::new(), "inp").unwrap();
+        let inputs =
+            render_inputs(&template, vec![], &tera::Context::new(), &naming("inp")).unwrap();
         assert_eq!(inputs.len(), 1);
         assert_eq!(inputs[0].filename, PathBuf::from("sp.inp"));
         assert_eq!(inputs[0].content, "hello");
@@ -921,7 +2478,7 @@ mod tests {
             &template,
             vec![(PathBuf::from("water.xyz"), mol)],
             &tera::Context::new(),
-            "inp",
+            &naming("inp"),
         )
         .unwrap();
         assert_eq!(inputs.len(), 1);
@@ -951,7 +2508,7 @@ mod tests {
                 (PathBuf::from("mol2.xyz"), mol()),
             ],
             &tera::Context::new(),
-            "com",
+            &naming("com"),
         )
         .unwrap();
         assert_eq!(inputs.len(), 2);
@@ -0,0 +1,374 @@
+use crate::config::Config;
+use crate::molecule::Molecule;
+use crate::template::Template;
+use color_eyre::eyre::{bail, Report as Error, Result, WrapErr};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs::{read, read_to_string};
+use std::path::{Component, Path, PathBuf};
+
+/// Suffix of the manifest written by `gedent gen --manifest`.
+pub const SUFFIX: &str = "manifest.json";
+
+/// File in which `gedent gen --incremental` records the hashes of the files
+/// it wrote to a directory, in `sha256sum` format.
+pub const HASHES: &str = ".gedent.sha256";
+
+/// Record of one `gedent gen --manifest` run, enough for `gedent regen` to
+/// reproduce every input.
+///
+/// Paths of inputs and molecules are relative to the manifest's directory, so
+/// a project can be moved as a whole; templates and config files keep the
+/// absolute paths they were read from.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub gedent_version: String,
+    /// Command line of the run, overrides included.
+    pub command: Vec<String>,
+    /// Config files that were merged, most global first.
+    pub config_chain: Vec<PathBuf>,
+    pub inputs: Vec<Entry>,
+}
+
+/// One generated input.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Entry {
+    pub file: PathBuf,
+    pub sha256: String,
+    #[serde(flatten)]
+    pub record: Record,
+}
+
+/// How an input was rendered: template, molecule and resolved context.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Record {
+    pub template: FileRecord,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub molecule: Option<FileRecord>,
+    /// Everything the template saw apart from `name` and `Molecule`, which
+    /// come from the molecule.
+    pub context: serde_json::Value,
+}
+
+/// A file and the hash of its content.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileRecord {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub path: PathBuf,
+    pub sha256: String,
+}
+
+/// An input rendered again from its manifest entry.
+#[derive(Debug)]
+pub struct Rendered {
+    pub file: PathBuf,
+    pub content: String,
+    pub molecule: Option<PathBuf>,
+}
+
+/// Hex SHA-256 of `bytes`.
+pub fn sha256(bytes: impl AsRef<[u8]>) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+impl Record {
+    /// Record rendering `template` with `context` for the molecule read from
+    /// `molecule`.
+    pub fn new(
+        template: &Template,
+        context: &tera::Context,
+        molecule: Option<&Path>,
+    ) -> Result<Self, Error> {
+        let molecule = molecule
+            .map(|path| {
+                let bytes = read(path).wrap_err(format!("Failed to read {}", path.display()))?;
+                Ok::<_, Error>(FileRecord {
+                    name: None,
+                    path: path.to_path_buf(),
+                    sha256: sha256(bytes),
+                })
+            })
+            .transpose()?;
+        Ok(Self {
+            template: FileRecord {
+                name: Some(template.name.clone()),
+                path: template.path.clone(),
+                sha256: sha256(template.body()),
+            },
+            molecule,
+            context: context.clone().into_json(),
+        })
+    }
+}
+
+impl Manifest {
+    /// Manifest for `inputs` (file, content and record), to be written in
+    /// `dir`.
+    pub fn new<'a>(
+        dir: &Path,
+        inputs: impl Iterator<Item = (&'a Path, &'a str, &'a Record)>,
+    ) -> Result<Self, Error> {
+        let inputs = inputs
+            .map(|(file, content, record)| {
+                let mut record = record.clone();
+                if let Some(molecule) = &mut record.molecule {
+                    molecule.path = relative_to(&molecule.path, dir)?;
+                }
+                Ok(Entry {
+                    file: relative_to(file, dir)?,
+                    sha256: sha256(content),
+                    record,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            gedent_version: env!("CARGO_PKG_VERSION").to_string(),
+            command: std::env::args().collect(),
+            config_chain: Config::collect_chain()?,
+            inputs,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let body =
+            read_to_string(path).wrap_err(format!("Can't read manifest {}", path.display()))?;
+        serde_json::from_str(&body).wrap_err(format!("Can't parse manifest {}", path.display()))
+    }
+
+    /// Render every input again from its template, molecule and context, for
+    /// a manifest in `dir`. Fails if a template or molecule changed since the
+    /// manifest was written, since the result would no longer be the same.
+    pub fn regenerate(&self, dir: &Path) -> Result<Vec<Rendered>, Error> {
+        let version = env!("CARGO_PKG_VERSION");
+        if self.gedent_version != version {
+            warn!(
+                "Manifest was written by gedent {}, this is {version}; \
+                 inputs may differ if template functions changed",
+                self.gedent_version
+            );
+        }
+        self.inputs
+            .iter()
+            .map(|entry| {
+                let rendered = entry.regenerate(dir)?;
+                if sha256(&rendered.content) != entry.sha256 {
+                    warn!(
+                        "{} renders differently than when the manifest was written",
+                        rendered.file.display()
+                    );
+                }
+                Ok(rendered)
+            })
+            .collect()
+    }
+}
+
+impl Entry {
+    fn regenerate(&self, dir: &Path) -> Result<Rendered, Error> {
+        let recorded = &self.record.template;
+        let name = recorded
+            .name
+            .clone()
+            .unwrap_or_else(|| recorded.path.display().to_string());
+        let template = Template::from_file(name, recorded.path.clone())
+            .wrap_err(format!("Template of {} is gone", self.file.display()))?;
+        if sha256(template.body()) != recorded.sha256 {
+            bail!(
+                "Template {} changed since {} was generated; restore it to regenerate",
+                recorded.path.display(),
+                self.file.display()
+            );
+        }
+        let ctx = tera::Context::from_value(self.record.context.clone())?;
+        let (content, molecule) = match &self.record.molecule {
+            Some(recorded) => {
+                let path = dir.join(&recorded.path);
+                let bytes = read(&path).wrap_err(format!("Failed to read {}", path.display()))?;
+                if sha256(bytes) != recorded.sha256 {
+                    bail!(
+                        "{} changed since {} was generated",
+                        path.display(),
+                        self.file.display()
+                    );
+                }
+                let molecule = Molecule::from_xyz(&path)?;
+                let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                let rendered = template.render_with_molecule(&ctx, &molecule, &stem)?;
+                (rendered, Some(path))
+            }
+            None => (template.render(&ctx)?, None),
+        };
+        Ok(Rendered {
+            file: dir.join(&self.file),
+            content,
+            molecule,
+        })
+    }
+}
+
+/// Hashes recorded in the [`HASHES`] file of `dir`, by file name.
+pub fn recorded_hashes(dir: &Path) -> BTreeMap<String, String> {
+    read_to_string(dir.join(HASHES))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.split_once("  "))
+        .map(|(hash, name)| (name.to_string(), hash.to_string()))
+        .collect()
+}
+
+/// Content of a [`HASHES`] file recording `hashes`.
+pub fn hashes_file(hashes: &BTreeMap<String, String>) -> String {
+    hashes
+        .iter()
+        .fold(String::new(), |mut content, (name, hash)| {
+            let _ = writeln!(content, "{hash}  {name}");
+            content
+        })
+}
+
+/// Whether `file` still has the content gedent last generated there, as
+/// recorded in its directory's [`HASHES`] file or in a manifest.
+pub fn is_generated(file: &Path) -> bool {
+    let Ok(disk) = read(file) else {
+        return false;
+    };
+    let disk = sha256(disk);
+    let dir = file.parent().unwrap_or_else(|| Path::new(""));
+    let recorded = file
+        .file_name()
+        .and_then(|name| recorded_hashes(dir).remove(&*name.to_string_lossy()));
+    recorded.is_some_and(|hash| hash == disk)
+        || find_entry(file)
+            .ok()
+            .flatten()
+            .is_some_and(|entry| entry.sha256 == disk)
+}
+
+/// The entry of the input that produced `output` (same path but for the
+/// extension), from a manifest in the output's directory or one above it.
+pub fn find_entry(output: &Path) -> Result<Option<Entry>, Error> {
+    let output = absolute(output)?;
+    let job = output.with_extension("");
+    for dir in output.ancestors().skip(1) {
+        let Ok(files) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        for file in files.filter_map(std::result::Result::ok) {
+            let path = file.path();
+            if !path.to_string_lossy().ends_with(SUFFIX) {
+                continue;
+            }
+            let Ok(manifest) = Manifest::load(&path) else {
+                warn!("Skipping unreadable manifest {}", path.display());
+                continue;
+            };
+            if let Some(entry) = manifest
+                .inputs
+                .into_iter()
+                .find(|e| dir.join(&e.file).with_extension("") == job)
+            {
+                return Ok(Some(entry));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// `path` relative to `base`, going up with `..` where needed.
+fn relative_to(path: &Path, base: &Path) -> Result<PathBuf> {
+    let (path, base) = (absolute(path)?, absolute(base)?);
+    let common = path
+        .components()
+        .zip(base.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut relative: PathBuf = base
+        .components()
+        .skip(common)
+        .map(|_| Component::ParentDir)
+        .collect();
+    relative.extend(path.components().skip(common));
+    Ok(relative)
+}
+
+/// `path` joined to the current directory, with `.` and `..` resolved
+/// lexically so files that don't exist yet can be handled.
+fn absolute(path: &Path) -> Result<PathBuf> {
+    let mut absolute = PathBuf::new();
+    for component in std::env::current_dir()?.join(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                absolute.pop();
+            }
+            other => absolute.push(other),
+        }
+    }
+    Ok(absolute)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_round_trip_and_detect_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("water.inp");
+        std::fs::write(&file, "! pbe0").unwrap();
+        assert!(!is_generated(&file));
+
+        let hashes = BTreeMap::from([("water.inp".to_string(), sha256("! pbe0"))]);
+        std::fs::write(dir.path().join(HASHES), hashes_file(&hashes)).unwrap();
+        assert_eq!(recorded_hashes(dir.path()), hashes);
+        assert!(is_generated(&file));
+
+        std::fs::write(&file, "edited by hand").unwrap();
+        assert!(!is_generated(&file));
+    }
+
+    #[test]
+    fn paths_are_relative_to_the_manifest() {
+        let rel = |path: &str, base: &str| relative_to(Path::new(path), Path::new(base)).unwrap();
+        assert_eq!(
+            rel("jobs/water/sp.inp", "jobs"),
+            PathBuf::from("water/sp.inp")
+        );
+        assert_eq!(
+            rel("xyz/water.xyz", "jobs/run"),
+            PathBuf::from("../../xyz/water.xyz")
+        );
+        assert_eq!(rel("water.inp", "."), PathBuf::from("water.inp"));
+        assert_eq!(rel("./a/../water.xyz", ""), PathBuf::from("water.xyz"));
+    }
+
+    #[test]
+    fn entry_round_trips_and_regenerates() {
+        let dir = tempfile::tempdir().unwrap();
+        let template_path = dir.path().join("sp");
+        std::fs::write(&template_path, "! {{ method }}").unwrap();
+        let mut template = Template::with_body("sp", "! {{ method }}");
+        template.path = template_path.clone();
+        let mut context = tera::Context::new();
+        context.insert("method", "pbe0");
+
+        let entry = Entry {
+            file: PathBuf::from("sp.inp"),
+            sha256: sha256("! pbe0"),
+            record: Record::new(&template, &context, None).unwrap(),
+        };
+        let json = serde_json::to_string(&entry).unwrap();
+        let entry: Entry = serde_json::from_str(&json).unwrap();
+        let rendered = entry.regenerate(dir.path()).unwrap();
+        assert_eq!(rendered.file, dir.path().join("sp.inp"));
+        assert_eq!(rendered.content, "! pbe0");
+
+        std::fs::write(&template_path, "! {{ method }} tightscf").unwrap();
+        let err = entry.regenerate(dir.path()).unwrap_err();
+        assert!(err.to_string().contains("changed since"));
+    }
+}
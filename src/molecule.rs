@@ -124,6 +124,20 @@ impl Molecule {
         Ok(Self { description, atoms })
     }
 
+    /// The molecule as the content of an xyz file.
+    pub fn to_xyz(&self) -> String {
+        let mut xyz = format!(
+            "{}\n{}\n",
+            self.atoms.len(),
+            self.description.as_deref().unwrap_or_default()
+        );
+        for atom in &self.atoms {
+            xyz.push_str(&atom.to_string());
+            xyz.push('\n');
+        }
+        xyz
+    }
+
     /// Open an xyz file at `path` and parse it into a `Molecule`.
     pub fn from_xyz(path: &PathBuf) -> Result<Self, Error> {
         let file = std::fs::File::open(path)
@@ -131,6 +145,64 @@ impl Molecule {
         Self::from_reader(std::io::BufReader::new(file))
             .wrap_err(format!("Failed to parse xyz file {}", path.display()))
     }
+
+    /// Distance in Å between atoms `i` and `j` (0-based).
+    pub fn distance(&self, i: usize, j: usize) -> f64 {
+        let (a, b) = (&self.atoms[i], &self.atoms[j]);
+        let (dx, dy, dz) = (a.x - b.x, a.y - b.y, a.z - b.z);
+        dz.mul_add(dz, dx.mul_add(dx, dy * dy)).sqrt()
+    }
+
+    /// Bonded neighbours of each atom (0-based): atoms closer than `factor`
+    /// times the sum of their covalent radii. Atoms without a tabulated
+    /// radius (e.g. dummies) have no neighbours.
+    pub fn neighbours(&self, factor: f64) -> Vec<Vec<usize>> {
+        let radii: Vec<Option<f64>> = self
+            .atoms
+            .iter()
+            .map(|a| a.element.get_radius().map(f64::from))
+            .collect();
+        let mut neighbours = vec![vec![]; self.atoms.len()];
+        for i in 0..self.atoms.len() {
+            for j in (i + 1)..self.atoms.len() {
+                let (Some(ri), Some(rj)) = (radii[i], radii[j]) else {
+                    continue;
+                };
+                if self.distance(i, j) < factor * (ri + rj) {
+                    neighbours[i].push(j);
+                    neighbours[j].push(i);
+                }
+            }
+        }
+        neighbours
+    }
+
+    /// Connected fragments of the bond graph given by `neighbours`, each a
+    /// sorted list of 0-based atom indices, ordered by their first atom.
+    pub fn fragments(neighbours: &[Vec<usize>]) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; neighbours.len()];
+        let mut fragments = vec![];
+        for start in 0..neighbours.len() {
+            if seen[start] {
+                continue;
+            }
+            seen[start] = true;
+            let mut fragment = vec![];
+            let mut stack = vec![start];
+            while let Some(i) = stack.pop() {
+                fragment.push(i);
+                for &j in &neighbours[i] {
+                    if !seen[j] {
+                        seen[j] = true;
+                        stack.push(j);
+                    }
+                }
+            }
+            fragment.sort_unstable();
+            fragments.push(fragment);
+        }
+        fragments
+    }
 }
 
 #[cfg(test)]
@@ -187,6 +259,14 @@ mod tests {
         assert_eq!(mol.atoms, ch4_atoms());
     }
 
+    #[test]
+    fn to_xyz_round_trips() {
+        let water = "3\nwater\nO 0.0 0.0 0.119\nH 0.0 0.757 -0.477\nH 0.0 -0.757 -0.477\n";
+        let mol = Molecule::from_reader(Cursor::new(water)).unwrap();
+        let again = Molecule::from_reader(Cursor::new(mol.to_xyz())).unwrap();
+        assert_eq!(again, mol);
+    }
+
     #[test]
     fn xyz_parse_trailing_blank_lines() {
         let input = format!("{CH4_XYZ}\n\n\n");
@@ -235,6 +315,24 @@ mod tests {
         assert!(s.contains(" -2.00000000"));
     }
 
+    #[test]
+    fn neighbours_and_fragments() {
+        let mol = Molecule::from_reader(Cursor::new(CH4_XYZ)).unwrap();
+        let neighbours = mol.neighbours(1.2);
+        assert_eq!(neighbours[0], vec![1, 2, 3, 4]);
+        assert_eq!(neighbours[1], vec![0]);
+        assert_eq!(Molecule::fragments(&neighbours), vec![vec![0, 1, 2, 3, 4]]);
+
+        // A second methane 20 Å away is its own fragment.
+        let mut dimer = mol.clone();
+        dimer.atoms.extend(mol.atoms.iter().map(|a| Atom {
+            x: a.x + 20.0,
+            ..a.clone()
+        }));
+        let fragments = Molecule::fragments(&dimer.neighbours(1.2));
+        assert_eq!(fragments, vec![vec![0, 1, 2, 3, 4], vec![5, 6, 7, 8, 9]]);
+    }
+
     #[test]
     fn xyz_parse_case_insensitive_elements() {
         let input = "1\n\nfe  0.0  0.0  0.0";
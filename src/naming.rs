@@ -0,0 +1,158 @@
+use color_eyre::eyre::{bail, Report as Error, Result};
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// Pattern used when `[gedent] filename_pattern` is unset: `mol.xyz` becomes
+/// `mol.inp` in the output directory.
+pub const DEFAULT_PATTERN: &str = "{name}.{ext}";
+
+/// Where generated inputs are written.
+///
+/// The pattern is a path with `{key}` placeholders, e.g.
+/// `{name}/{template}_{method}.{ext}`. Keys are looked up in the template
+/// context, plus `name` (molecule stem, or the template name when there is no
+/// molecule), `template` (template name without software) and `ext`.
+#[derive(Clone, Debug)]
+pub struct Naming {
+    pattern: String,
+    outdir: Option<PathBuf>,
+    extension: String,
+}
+
+impl Naming {
+    pub fn new(pattern: Option<String>, outdir: Option<PathBuf>, extension: String) -> Self {
+        Self {
+            pattern: pattern.unwrap_or_else(|| DEFAULT_PATTERN.to_string()),
+            outdir,
+            extension,
+        }
+    }
+
+    /// Path of the input for `name` rendered from `template`.
+    pub fn path(&self, context: &tera::Context, name: &str, template: &str) -> Result<PathBuf> {
+        let json = context.clone().into_json();
+        let relative = render(&self.pattern, |key| match key {
+            "name" => Ok(Some(name.to_string())),
+            "template" => Ok(Some(template.to_string())),
+            "ext" => Ok(Some(self.extension.clone())),
+            _ => json.get(key).map(|v| scalar(key, v)).transpose(),
+        })?;
+        let relative = relative.strip_suffix('.').unwrap_or(&relative);
+        Ok(self
+            .outdir
+            .as_ref()
+            .map_or_else(|| PathBuf::from(relative), |dir| dir.join(relative)))
+    }
+}
+
+/// Fill the `{key}` placeholders of `pattern`. Path separators inside values
+/// become `_`, so a value can't add directories the pattern didn't ask for.
+fn render(pattern: &str, lookup: impl Fn(&str) -> Result<Option<String>>) -> Result<String, Error> {
+    let mut out = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            bail!("Unclosed `{{` in filename pattern {pattern:?}");
+        };
+        let key = rest[start + 1..start + len].trim();
+        let Some(value) = lookup(key)? else {
+            bail!(
+                "Filename pattern {pattern:?} uses `{{{key}}}`, which is not defined; \
+                 set it in the config or with --var"
+            );
+        };
+        out.push_str(&value.replace(['/', '\\'], "_"));
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// `value` as part of a filename. Arrays join their items with `-`, so
+/// `[1, 2]` becomes `1-2`, and tables their keys and values (`a-1-b-2`).
+fn scalar(key: &str, value: &Value) -> Result<String> {
+    Ok(match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| scalar(key, item))
+            .collect::<Result<Vec<_>>>()?
+            .join("-"),
+        Value::Object(table) => table
+            .iter()
+            .map(|(k, v)| Ok(format!("{k}-{}", scalar(key, v)?)))
+            .collect::<Result<Vec<_>>>()?
+            .join("-"),
+        Value::Null => bail!("`{{{key}}}` in the filename pattern has no value"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> tera::Context {
+        let mut ctx = tera::Context::new();
+        ctx.insert("method", "r2scan-3c");
+        ctx.insert("basis_set", "6-31G/d");
+        ctx.insert("charge", &-1);
+        ctx.insert("frozen", &[1, 2]);
+        ctx
+    }
+
+    #[test]
+    fn default_pattern_is_stem_and_extension() {
+        let naming = Naming::new(None, None, "inp".to_string());
+        let path = naming.path(&context(), "water", "sp").unwrap();
+        assert_eq!(path, PathBuf::from("water.inp"));
+    }
+
+    #[test]
+    fn pattern_uses_context_and_outdir() {
+        let naming = Naming::new(
+            Some("{name}/{template}_{method}_{charge}.{ext}".to_string()),
+            Some(PathBuf::from("jobs")),
+            "inp".to_string(),
+        );
+        let path = naming.path(&context(), "water", "opt").unwrap();
+        assert_eq!(path, PathBuf::from("jobs/water/opt_r2scan-3c_-1.inp"));
+    }
+
+    #[test]
+    fn values_cannot_add_directories() {
+        let naming = Naming::new(Some("{basis_set}.{ext}".to_string()), None, "com".into());
+        let path = naming.path(&context(), "water", "sp").unwrap();
+        assert_eq!(path, PathBuf::from("6-31G_d.com"));
+    }
+
+    #[test]
+    fn empty_extension_leaves_no_trailing_dot() {
+        let naming = Naming::new(None, None, String::new());
+        let path = naming.path(&context(), "water", "sp").unwrap();
+        assert_eq!(path, PathBuf::from("water"));
+    }
+
+    #[test]
+    fn arrays_and_tables_are_joined_with_dashes() {
+        let mut ctx = context();
+        ctx.insert("atoms", &serde_json::json!({"a": [1, 2], "b": 3}));
+        let naming = Naming::new(
+            Some("{name}_{frozen}_{atoms}.{ext}".into()),
+            None,
+            "inp".into(),
+        );
+        let path = naming.path(&ctx, "water", "sp").unwrap();
+        assert_eq!(path, PathBuf::from("water_1-2_a-1-2-b-3.inp"));
+    }
+
+    #[test]
+    fn unknown_keys_are_errors() {
+        for pattern in ["{solvent}.{ext}", "{name.{ext}"] {
+            let naming = Naming::new(Some(pattern.to_string()), None, "inp".into());
+            assert!(naming.path(&context(), "water", "sp").is_err(), "{pattern}");
+        }
+    }
+}
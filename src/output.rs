@@ -0,0 +1,477 @@
+use crate::elements::Element;
+use crate::molecule::{Atom, Molecule};
+use crate::software::Software;
+use color_eyre::eyre::{bail, eyre, Report as Error, Result, WrapErr};
+use std::fmt;
+use std::fs::read_to_string;
+use std::path::Path;
+
+/// How a job ended, as far as its output tells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Termination {
+    Normal,
+    /// The SCF hit its iteration limit.
+    ScfNotConverged,
+    /// The geometry optimization hit its cycle limit.
+    OptNotConverged,
+    /// The program stopped with an error, with its message.
+    Error(String),
+    /// The output just ends, e.g. at the walltime or when the job was killed.
+    Interrupted,
+}
+
+impl Termination {
+    /// Short name inserted into the restart context as `restart_reason`.
+    pub const fn reason(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::ScfNotConverged => "scf",
+            Self::OptNotConverged => "opt_cycles",
+            Self::Error(_) => "error",
+            Self::Interrupted => "interrupted",
+        }
+    }
+}
+
+impl fmt::Display for Termination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Normal => write!(f, "finished normally"),
+            Self::ScfNotConverged => write!(f, "SCF did not converge"),
+            Self::OptNotConverged => write!(f, "optimization ran out of cycles"),
+            Self::Error(message) => write!(f, "stopped with an error: {message}"),
+            Self::Interrupted => write!(f, "stopped before finishing (walltime or killed?)"),
+        }
+    }
+}
+
+/// What `gedent restart` needs from an ORCA or Gaussian output.
+#[derive(Debug)]
+pub struct JobOutput {
+    pub software: Software,
+    pub termination: Termination,
+    /// Last geometry printed, in Å.
+    pub geometry: Option<Molecule>,
+    pub charge: Option<i64>,
+    pub mult: Option<i64>,
+    /// Job type guessed from the keywords, named like the bundled templates.
+    pub jobtype: Option<String>,
+    /// Imaginary frequencies of the last frequency calculation; `None` if
+    /// there was none.
+    pub imaginary: Option<usize>,
+}
+
+impl JobOutput {
+    pub fn read(path: &Path) -> Result<Self, Error> {
+        let text =
+            read_to_string(path).wrap_err(format!("Can't read output {}", path.display()))?;
+        Self::parse(&text).wrap_err(format!("Can't parse output {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        if text.contains("O   R   C   A") {
+            Ok(parse_orca(text))
+        } else if text.contains("Entering Gaussian System") || text.contains("Gaussian, Inc.") {
+            Ok(parse_gaussian(text))
+        } else {
+            bail!("Not an ORCA or Gaussian output")
+        }
+    }
+
+    /// Imaginary frequencies a correct result has: one for a transition
+    /// state search, none otherwise.
+    pub fn expected_imaginary(&self) -> usize {
+        usize::from(self.jobtype.as_deref() == Some("optts"))
+    }
+}
+
+fn parse_orca(text: &str) -> JobOutput {
+    // ORCA still terminates "normally" after an optimization runs out of
+    // cycles, so the failure markers come first.
+    let termination = if text.contains("The optimization did not converge") {
+        Termination::OptNotConverged
+    } else if text.contains("SCF NOT CONVERGED") {
+        Termination::ScfNotConverged
+    } else if let Some(line) = text
+        .lines()
+        .find(|l| l.contains("ORCA finished by error termination"))
+    {
+        Termination::Error(line.trim().to_string())
+    } else if text.contains("****ORCA TERMINATED NORMALLY****") {
+        Termination::Normal
+    } else {
+        Termination::Interrupted
+    };
+
+    // The input is echoed as `|  1> ! PBE0 def2-SVP Opt`.
+    let keywords: Vec<String> = text
+        .lines()
+        .filter(|l| l.trim_start().starts_with('|'))
+        .filter_map(|l| l.split_once("> ").map(|(_, input)| input.trim()))
+        .filter(|input| input.starts_with('!'))
+        .flat_map(|input| input[1..].split_whitespace())
+        .map(str::to_lowercase)
+        .collect();
+    let has = |names: &[&str]| keywords.iter().any(|k| names.contains(&k.as_str()));
+    let opt = has(&[
+        "opt",
+        "looseopt",
+        "tightopt",
+        "verytightopt",
+        "copt",
+        "zopt",
+    ]);
+    let freq = has(&["freq", "numfreq"]);
+    let jobtype = if has(&["optts"]) {
+        "optts"
+    } else if opt && freq {
+        "optfreq"
+    } else if opt {
+        "opt"
+    } else if freq {
+        "freq"
+    } else {
+        "sp"
+    };
+
+    let geometry = last_block(text, "CARTESIAN COORDINATES (ANGSTROEM)").map(|lines| {
+        lines
+            .iter()
+            .skip(1) // dashes
+            .take_while(|l| !l.trim().is_empty())
+            .filter_map(|l| {
+                let fields: Vec<&str> = l.split_whitespace().collect();
+                atom(fields.first()?.parse().ok()?, &fields[1..])
+            })
+            .collect()
+    });
+
+    // Imaginary modes are printed as `6:  -512.34 cm**-1 ***imaginary mode***`.
+    let imaginary = last_block(text, "VIBRATIONAL FREQUENCIES").map(|lines| {
+        lines
+            .iter()
+            .take_while(|l| !l.contains("NORMAL MODES"))
+            .filter(|l| l.contains("***imaginary mode***"))
+            .count()
+    });
+
+    JobOutput {
+        software: Software::Orca,
+        termination,
+        geometry: geometry.map(molecule),
+        charge: value_after(text, "Total Charge", "...."),
+        mult: value_after(text, "Multiplicity", "...."),
+        jobtype: Some(jobtype.to_string()),
+        imaginary,
+    }
+}
+
+fn parse_gaussian(text: &str) -> JobOutput {
+    let last_line = text.lines().rev().find(|l| !l.trim().is_empty());
+    let termination = if last_line.is_some_and(|l| l.contains("Normal termination")) {
+        Termination::Normal
+    } else if text.contains("Number of steps exceeded") {
+        Termination::OptNotConverged
+    } else if text.contains("Convergence failure -- run terminated.") {
+        Termination::ScfNotConverged
+    } else if let Some(line) = text.lines().rev().find(|l| l.contains("Error termination")) {
+        Termination::Error(line.trim().to_string())
+    } else {
+        Termination::Interrupted
+    };
+
+    // The route section is the first block starting with `#`, ending at
+    // a line of dashes.
+    let route: String = text
+        .lines()
+        .skip_while(|l| !l.trim_start().starts_with('#'))
+        .take_while(|l| !l.trim_start().starts_with("---"))
+        .map(|l| l.trim().to_lowercase())
+        .collect();
+    // Options follow as `opt=ts`, `opt=(ts,calcfc)` or `opt(ts)`.
+    let options = |name: &str| {
+        route.split_whitespace().find_map(|k| {
+            let rest = k.strip_prefix(name)?;
+            (rest.is_empty() || rest.starts_with(['=', '('])).then(|| {
+                rest.trim_start_matches('=')
+                    .trim_matches(['(', ')'])
+                    .split(',')
+                    .collect::<Vec<_>>()
+            })
+        })
+    };
+    let opt = options("opt");
+    let freq = options("freq").is_some();
+    let jobtype = match opt {
+        Some(options) if options.contains(&"ts") => "optts",
+        Some(_) if freq => "optfreq",
+        Some(_) => "opt",
+        None if freq => "freq",
+        None => "sp",
+    };
+
+    // Standard orientation is missing with `nosymm`; take whichever came last.
+    let standard = last_block_at(text, "Standard orientation:");
+    let input = last_block_at(text, "Input orientation:");
+    let block = match (standard, input) {
+        (Some(s), Some(i)) => Some(if s.0 > i.0 { s.1 } else { i.1 }),
+        (s, i) => s.or(i).map(|b| b.1),
+    };
+    let geometry = block.map(|lines| {
+        lines
+            .iter()
+            .skip(4) // dashes, two header lines, dashes
+            .take_while(|l| !l.trim_start().starts_with("---"))
+            .filter_map(|l| {
+                let fields: Vec<&str> = l.split_whitespace().collect();
+                let element = Element::from_atomic_number(fields.get(1)?.parse().ok()?)?;
+                atom(element, fields.get(3..)?)
+            })
+            .collect()
+    });
+
+    let (charge, mult) = text
+        .lines()
+        .find(|l| l.trim_start().starts_with("Charge =") && l.contains("Multiplicity ="))
+        .map_or((None, None), |l| {
+            let fields: Vec<&str> = l.split_whitespace().collect();
+            (
+                fields.get(2).and_then(|v| v.parse().ok()),
+                fields.get(5).and_then(|v| v.parse().ok()),
+            )
+        });
+
+    // Frequencies come three to a line as ` Frequencies --  -512.3  101.2  ...`.
+    let imaginary = last_block(text, "Harmonic frequencies (cm**-1)").map(|lines| {
+        lines
+            .iter()
+            .filter_map(|l| l.trim_start().strip_prefix("Frequencies --"))
+            .flat_map(str::split_whitespace)
+            .filter(|v| v.parse::<f64>().is_ok_and(|v| v < 0.0))
+            .count()
+    });
+
+    JobOutput {
+        software: Software::Gaussian,
+        termination,
+        geometry: geometry.map(molecule),
+        charge,
+        mult,
+        jobtype: Some(jobtype.to_string()),
+        imaginary,
+    }
+}
+
+fn atom(element: Element, coords: &[&str]) -> Option<Atom> {
+    Some(Atom {
+        element,
+        x: coords.first()?.parse().ok()?,
+        y: coords.get(1)?.parse().ok()?,
+        z: coords.get(2)?.parse().ok()?,
+    })
+}
+
+const fn molecule(atoms: Vec<Atom>) -> Molecule {
+    Molecule {
+        description: None,
+        atoms,
+    }
+}
+
+/// Lines after the last line containing `header`.
+fn last_block<'a>(text: &'a str, header: &str) -> Option<Vec<&'a str>> {
+    last_block_at(text, header).map(|(_, lines)| lines)
+}
+
+/// Like [`last_block`], with the index of the header line.
+fn last_block_at<'a>(text: &'a str, header: &str) -> Option<(usize, Vec<&'a str>)> {
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.iter().rposition(|l| l.contains(header))?;
+    Some((start, lines[start + 1..].to_vec()))
+}
+
+/// Integer after `separator` on the first line starting with `label`, as in
+/// ORCA's `Total Charge  Charge  ....  0`.
+fn value_after(text: &str, label: &str, separator: &str) -> Option<i64> {
+    text.lines()
+        .find(|l| l.trim_start().starts_with(label) && l.contains(separator))
+        .and_then(|l| l.rsplit(separator).next())
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// Name for the restart of a job named `stem`: `mol` becomes `mol_restart`,
+/// `mol_restart` becomes `mol_restart2` and so on.
+pub fn restart_name(stem: &str) -> String {
+    match stem.rsplit_once("_restart") {
+        Some((base, "")) => format!("{base}_restart2"),
+        Some((base, n)) if n.chars().all(|c| c.is_ascii_digit()) => {
+            let n: u32 = n.parse().unwrap_or(1);
+            format!("{base}_restart{}", n + 1)
+        }
+        _ => format!("{stem}_restart"),
+    }
+}
+
+/// File the program keeps its orbitals in, to read as the restart guess.
+pub fn guess_file(software: Software, stem: &str) -> Result<String> {
+    match software {
+        Software::Orca => Ok(format!("{stem}.gbw")),
+        Software::Gaussian => Ok(format!("{stem}.chk")),
+        other => Err(eyre!("Can't restart {other} jobs")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORCA_OPT: &str = "\
+                                 * O   R   C   A *
+|  1> ! PBE0 def2-SVP Opt
+|  2> *xyz 0 1
+             *** FINAL ENERGY EVALUATION AT THE STATIONARY POINT ***
+---------------------------------
+CARTESIAN COORDINATES (ANGSTROEM)
+---------------------------------
+  O      0.000000    0.000000    0.119000
+  H      0.000000    0.757000   -0.477000
+  H      0.000000   -0.757000   -0.477000
+
+ Total Charge           Charge          ....    0
+ Multiplicity           Mult            ....    1
+---------------------------------
+CARTESIAN COORDINATES (ANGSTROEM)
+---------------------------------
+  O      0.000000    0.000000    0.120000
+  H      0.000000    0.760000   -0.480000
+  H      0.000000   -0.760000   -0.480000
+
+The optimization did not converge but reached the maximum number of
+optimization cycles.
+";
+
+    const GAUSSIAN_SCF: &str = "\
+ Entering Gaussian System, Link 0=g16
+ ----------------------------------
+ #p b3lyp/def2svp opt freq=noraman
+ ----------------------------------
+ Charge = -1 Multiplicity = 2
+                          Input orientation:
+ ---------------------------------------------------------------------
+ Center     Atomic      Atomic             Coordinates (Angstroms)
+ Number     Number       Type             X           Y           Z
+ ---------------------------------------------------------------------
+      1          8           0        0.000000    0.000000    0.119000
+      2          1           0        0.000000    0.757000   -0.477000
+ ---------------------------------------------------------------------
+ Convergence failure -- run terminated.
+ Error termination via Lnk1e in /opt/g16/l502.exe
+";
+
+    #[test]
+    fn orca_output_gives_last_geometry_and_reason() {
+        let output = JobOutput::parse(ORCA_OPT).unwrap();
+        assert_eq!(output.software, Software::Orca);
+        assert_eq!(output.termination, Termination::OptNotConverged);
+        assert_eq!(output.jobtype.as_deref(), Some("opt"));
+        assert_eq!((output.charge, output.mult), (Some(0), Some(1)));
+        let geometry = output.geometry.unwrap();
+        assert_eq!(geometry.atoms.len(), 3);
+        assert_eq!(geometry.atoms[1].element, Element::H);
+        assert!((geometry.atoms[1].y - 0.76).abs() < 1e-9);
+    }
+
+    #[test]
+    fn orca_output_unconverged_despite_normal_termination() {
+        let orca =
+            format!("{ORCA_OPT}\n                             ****ORCA TERMINATED NORMALLY****\n");
+        let output = JobOutput::parse(&orca).unwrap();
+        assert_eq!(output.termination, Termination::OptNotConverged);
+    }
+
+    #[test]
+    fn orca_output_that_just_ends_was_interrupted() {
+        let cut = ORCA_OPT.split("The optimization").next().unwrap();
+        let output = JobOutput::parse(cut).unwrap();
+        assert_eq!(output.termination, Termination::Interrupted);
+    }
+
+    #[test]
+    fn gaussian_output_gives_geometry_charge_and_reason() {
+        let output = JobOutput::parse(GAUSSIAN_SCF).unwrap();
+        assert_eq!(output.software, Software::Gaussian);
+        assert_eq!(output.termination, Termination::ScfNotConverged);
+        assert_eq!(output.jobtype.as_deref(), Some("optfreq"));
+        assert_eq!((output.charge, output.mult), (Some(-1), Some(2)));
+        let geometry = output.geometry.unwrap();
+        assert_eq!(geometry.atoms.len(), 2);
+        assert_eq!(geometry.atoms[0].element, Element::O);
+    }
+
+    #[test]
+    fn gaussian_transition_state_searches_are_optts() {
+        for route in ["opt=ts", "opt=(calcfc,ts,noeigentest) freq", "opt(ts)"] {
+            let text = GAUSSIAN_SCF.replace("opt freq=noraman", route);
+            let output = JobOutput::parse(&text).unwrap();
+            assert_eq!(output.jobtype.as_deref(), Some("optts"), "{route}");
+        }
+    }
+
+    #[test]
+    fn gaussian_jobtype_follows_opt_and_freq() {
+        for (route, jobtype) in [
+            ("opt=tight freq", "optfreq"),
+            ("opt", "opt"),
+            ("freq=noraman", "freq"),
+            ("sp", "sp"),
+            ("optics", "sp"),
+        ] {
+            let text = GAUSSIAN_SCF.replace("opt freq=noraman", route);
+            let output = JobOutput::parse(&text).unwrap();
+            assert_eq!(output.jobtype.as_deref(), Some(jobtype), "{route}");
+        }
+    }
+
+    #[test]
+    fn imaginary_frequencies_are_counted() {
+        let orca = format!(
+            "{ORCA_OPT}\
+-----------------------
+VIBRATIONAL FREQUENCIES
+-----------------------
+   5:         0.00 cm**-1
+   6:      -312.45 cm**-1 ***imaginary mode***
+   7:      1602.11 cm**-1
+------------
+NORMAL MODES
+------------
+"
+        );
+        let output = JobOutput::parse(&orca).unwrap();
+        assert_eq!(output.imaginary, Some(1));
+        assert_eq!(output.expected_imaginary(), 0);
+        assert_eq!(JobOutput::parse(ORCA_OPT).unwrap().imaginary, None);
+
+        let gaussian = format!(
+            "{GAUSSIAN_SCF}\
+ Harmonic frequencies (cm**-1), IR intensities (KM/Mole), Raman scattering
+ Frequencies --  -1520.1021              -45.3300              1650.2201
+ Frequencies --   3800.5410             3901.0012
+"
+        );
+        assert_eq!(JobOutput::parse(&gaussian).unwrap().imaginary, Some(2));
+    }
+
+    #[test]
+    fn unknown_output_is_an_error() {
+        assert!(JobOutput::parse("xtb version 6.6").is_err());
+    }
+
+    #[test]
+    fn restart_names_count_up() {
+        assert_eq!(restart_name("water"), "water_restart");
+        assert_eq!(restart_name("water_restart"), "water_restart2");
+        assert_eq!(restart_name("water_restart2"), "water_restart3");
+        assert_eq!(restart_name("water_restarted"), "water_restarted_restart");
+    }
+}
@@ -0,0 +1,429 @@
+use crate::validation::{Check, Diagnostic};
+use color_eyre::eyre::{bail, Result, WrapErr};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Value type of a template parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamType {
+    Int,
+    Float,
+    Bool,
+    String,
+    Array,
+}
+
+impl fmt::Display for ParamType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::Bool => "bool",
+            Self::String => "string",
+            Self::Array => "array",
+        })
+    }
+}
+
+impl ParamType {
+    const fn is_numeric(self) -> bool {
+        matches!(self, Self::Int | Self::Float)
+    }
+
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            Self::Int => value.is_i64() || value.is_u64(),
+            Self::Float => value.is_number(),
+            Self::Bool => value.is_boolean(),
+            Self::String => value.is_string(),
+            Self::Array => value.is_array(),
+        }
+    }
+
+    const fn article(self) -> &'static str {
+        match self {
+            Self::Int => "an integer",
+            Self::Float => "a number",
+            Self::Bool => "a boolean",
+            Self::String => "a string",
+            Self::Array => "an array",
+        }
+    }
+}
+
+/// Schema of one parameter, declared in the frontmatter as a
+/// `[params.<name>]` table:
+/// ```text
+/// [params.scan_atoms]
+/// type = "array"
+/// items = "int"
+/// length = [2, 4]
+/// range = [1, inf]
+/// description = "1-based atoms of the scanned coordinate"
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParamSpec {
+    /// Value type.
+    #[serde(rename = "type")]
+    pub kind: ParamType,
+    /// Type of each item when `kind` is `array`; any item type if unset.
+    pub items: Option<ParamType>,
+    /// Value injected when the parameter is not set anywhere.
+    pub default: Option<toml::Value>,
+    /// Inclusive bounds of a number, or of each item of a numeric array.
+    pub range: Option<[f64; 2]>,
+    /// Inclusive bounds on the number of items of an array.
+    pub length: Option<[usize; 2]>,
+    /// Allowed values.
+    #[serde(rename = "enum")]
+    pub choices: Option<Vec<toml::Value>>,
+    /// Shown by `gedent template print --info`.
+    pub description: Option<String>,
+    /// Whether the parameter must be set. Defaults to true for parameters
+    /// without a default and false otherwise.
+    pub required: Option<bool>,
+}
+
+impl ParamSpec {
+    /// Whether rendering needs a value for this parameter.
+    pub fn is_required(&self) -> bool {
+        self.required.unwrap_or_else(|| self.default.is_none())
+    }
+
+    /// Type as shown to users, e.g. `array of int`.
+    pub fn type_label(&self) -> String {
+        match (self.kind, self.items) {
+            (ParamType::Array, Some(items)) => format!("array of {items}"),
+            (kind, _) => kind.to_string(),
+        }
+    }
+
+    /// Range, length and enum constraints as shown to users.
+    pub fn constraint_label(&self) -> String {
+        let mut parts = vec![];
+        if let Some([lo, hi]) = self.length {
+            parts.push(format!("{} items", count_span(lo, hi)));
+        }
+        if let Some([lo, hi]) = self.range {
+            parts.push(span(lo, hi));
+        }
+        if let Some(choices) = &self.choices {
+            let choices: Vec<String> = choices.iter().map(ToString::to_string).collect();
+            parts.push(format!("one of {}", choices.join(", ")));
+        }
+        parts.join("; ")
+    }
+
+    /// Describe why `value` does not satisfy the schema, or `None` if it does.
+    fn violation(&self, value: &Value) -> Option<String> {
+        if !self.kind.matches(value) {
+            return Some(format!("must be {}, got {value}", self.kind.article()));
+        }
+        if let Some(items) = value.as_array() {
+            if let Some([lo, hi]) = self.length {
+                if items.len() < lo || items.len() > hi {
+                    return Some(format!(
+                        "must have {} items, got {}",
+                        count_span(lo, hi),
+                        items.len()
+                    ));
+                }
+            }
+            for item in items {
+                if let Some(kind) = self.items.filter(|kind| !kind.matches(item)) {
+                    return Some(format!("items must be {}, got {item}", kind.article()));
+                }
+                if let Some(msg) = self.out_of_range(item) {
+                    return Some(format!("items {msg}"));
+                }
+            }
+        } else if let Some(msg) = self.out_of_range(value) {
+            return Some(msg);
+        }
+        if let Some(choices) = &self.choices {
+            if !choices.iter().any(|choice| same_value(choice, value)) {
+                let choices: Vec<String> = choices.iter().map(ToString::to_string).collect();
+                return Some(format!(
+                    "must be one of {}, got {value}",
+                    choices.join(", ")
+                ));
+            }
+        }
+        None
+    }
+
+    fn out_of_range(&self, value: &Value) -> Option<String> {
+        let [lo, hi] = self.range?;
+        let x = value.as_f64()?;
+        (x < lo || x > hi).then(|| format!("must be {}, got {value}", span(lo, hi)))
+    }
+
+    /// Reject schemas that contradict themselves, including defaults and
+    /// enum values that the schema would refuse.
+    fn check_consistency(&self) -> Result<()> {
+        let is_array = self.kind == ParamType::Array;
+        if self.items.is_some() && !is_array {
+            bail!("`items` only applies to arrays");
+        }
+        if self.items == Some(ParamType::Array) {
+            bail!("nested arrays are not supported");
+        }
+        if self.length.is_some() && !is_array {
+            bail!("`length` only applies to arrays");
+        }
+        if let Some([lo, hi]) = self.length {
+            if lo > hi {
+                bail!("`length` lower bound {lo} exceeds upper bound {hi}");
+            }
+        }
+        if let Some([lo, hi]) = self.range {
+            let numeric = if is_array {
+                self.items.is_some_and(ParamType::is_numeric)
+            } else {
+                self.kind.is_numeric()
+            };
+            if !numeric {
+                bail!("`range` only applies to numbers and arrays of numbers");
+            }
+            if lo > hi {
+                bail!("`range` lower bound {lo} exceeds upper bound {hi}");
+            }
+        }
+        for choice in self.choices.iter().flatten() {
+            if let Some(msg) = self.violation(&to_json(choice)) {
+                bail!("enum value {choice} {msg}");
+            }
+        }
+        if let Some(default) = &self.default {
+            if let Some(msg) = self.violation(&to_json(default)) {
+                bail!("default {msg}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse the `[params]` table of a frontmatter block.
+pub fn parse(table: &toml::Table) -> Result<BTreeMap<String, ParamSpec>> {
+    let Some(params) = table.get("params") else {
+        return Ok(BTreeMap::new());
+    };
+    let params: BTreeMap<String, ParamSpec> = params
+        .clone()
+        .try_into()
+        .wrap_err("Invalid [params] table in template frontmatter")?;
+    for (name, spec) in &params {
+        spec.check_consistency()
+            .wrap_err(format!("Invalid schema for parameter `{name}`"))?;
+    }
+    Ok(params)
+}
+
+/// Insert the default of every parameter the context does not set.
+pub fn insert_defaults(params: &BTreeMap<String, ParamSpec>, context: &mut tera::Context) {
+    for (name, spec) in params {
+        if let Some(default) = spec
+            .default
+            .as_ref()
+            .filter(|_| !context.contains_key(name))
+        {
+            context.insert(name, default);
+        }
+    }
+}
+
+/// Check every parameter set in the context against its schema. Missing
+/// required parameters are left to the `requires` check.
+pub fn check(params: &BTreeMap<String, ParamSpec>, context: &tera::Context) -> Vec<Diagnostic> {
+    params
+        .iter()
+        .filter_map(|(name, spec)| {
+            let msg = spec.violation(context.get(name)?)?;
+            Some(Diagnostic::error(
+                Check::InvalidParameter,
+                format!("parameter `{name}` {msg}"),
+            ))
+        })
+        .collect()
+}
+
+fn to_json(value: &toml::Value) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// Compare an enum value with a context value, treating `1` and `1.0` alike.
+fn same_value(choice: &toml::Value, value: &Value) -> bool {
+    let choice = to_json(choice);
+    match (choice.as_f64(), value.as_f64()) {
+        (Some(a), Some(b)) => (a - b).abs() < f64::EPSILON,
+        _ => choice == *value,
+    }
+}
+
+fn count_span(lo: usize, hi: usize) -> String {
+    if lo == hi {
+        format!("exactly {lo}")
+    } else {
+        format!("between {lo} and {hi}")
+    }
+}
+
+fn span(lo: f64, hi: f64) -> String {
+    match (lo.is_finite(), hi.is_finite()) {
+        (true, true) if (lo - hi).abs() < f64::EPSILON => format!("exactly {lo}"),
+        (true, true) => format!("between {lo} and {hi}"),
+        (true, false) => format!("at least {lo}"),
+        (false, true) => format!("at most {hi}"),
+        (false, false) => "any number of".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(toml: &str) -> Result<BTreeMap<String, ParamSpec>> {
+        parse(&toml::from_str(toml).unwrap())
+    }
+
+    fn messages(params: &BTreeMap<String, ParamSpec>, context: &tera::Context) -> Vec<String> {
+        check(params, context)
+            .into_iter()
+            .map(|d| d.message)
+            .collect()
+    }
+
+    const SCAN: &str = r#"
+        [params.scan_atoms]
+        type = "array"
+        items = "int"
+        length = [2, 4]
+        range = [1, inf]
+
+        [params.scan_steps]
+        type = "int"
+        range = [1, inf]
+
+        [params.nimages]
+        type = "int"
+        default = 12
+        description = "Number of images"
+
+        [params.guess]
+        type = "string"
+        enum = ["hcore", "pmodel"]
+        required = false
+    "#;
+
+    #[test]
+    fn required_follows_default_unless_set() {
+        let params = schema(SCAN).unwrap();
+        assert!(params["scan_atoms"].is_required());
+        assert!(!params["nimages"].is_required());
+        assert!(!params["guess"].is_required());
+    }
+
+    #[test]
+    fn defaults_do_not_override_context() {
+        let params = schema(SCAN).unwrap();
+        let mut context = tera::Context::new();
+        insert_defaults(&params, &mut context);
+        assert_eq!(context.get("nimages"), Some(&Value::from(12)));
+
+        let mut context = tera::Context::new();
+        context.insert("nimages", &8);
+        insert_defaults(&params, &mut context);
+        assert_eq!(context.get("nimages"), Some(&Value::from(8)));
+    }
+
+    #[test]
+    fn valid_values_pass() {
+        let params = schema(SCAN).unwrap();
+        let mut context = tera::Context::new();
+        context.insert("scan_atoms", &[1, 2, 3]);
+        context.insert("scan_steps", &10);
+        context.insert("guess", "pmodel");
+        assert!(check(&params, &context).is_empty());
+    }
+
+    #[test]
+    fn reports_type_length_range_and_enum_violations() {
+        let params = schema(SCAN).unwrap();
+        let mut context = tera::Context::new();
+        context.insert("scan_atoms", &[1, 2, 3, 4, 5]);
+        context.insert("scan_steps", &0);
+        context.insert("nimages", "twelve");
+        context.insert("guess", "huckel");
+        let diags = check(&params, &context);
+        assert!(diags.iter().all(|d| d.check == Check::InvalidParameter));
+        assert_eq!(
+            messages(&params, &context),
+            [
+                "parameter `guess` must be one of \"hcore\", \"pmodel\", got \"huckel\"",
+                "parameter `nimages` must be an integer, got \"twelve\"",
+                "parameter `scan_atoms` must have between 2 and 4 items, got 5",
+                "parameter `scan_steps` must be at least 1, got 0",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_array_items() {
+        let params = schema(SCAN).unwrap();
+        let mut context = tera::Context::new();
+        context.insert("scan_atoms", &[1.5, 2.0]);
+        assert_eq!(
+            messages(&params, &context),
+            ["parameter `scan_atoms` items must be an integer, got 1.5"]
+        );
+        context.insert("scan_atoms", &[0, 2]);
+        assert_eq!(
+            messages(&params, &context),
+            ["parameter `scan_atoms` items must be at least 1, got 0"]
+        );
+    }
+
+    #[test]
+    fn float_accepts_integers() {
+        let params = schema("[params.scan_end]\ntype = \"float\"\nrange = [0, 10]").unwrap();
+        let mut context = tera::Context::new();
+        context.insert("scan_end", &3);
+        assert!(check(&params, &context).is_empty());
+    }
+
+    #[test]
+    fn rejects_inconsistent_schemas() {
+        for bad in [
+            "[params.x]\ntype = \"int\"\ndefault = \"twelve\"",
+            "[params.x]\ntype = \"int\"\nrange = [1, 10]\ndefault = 0",
+            "[params.x]\ntype = \"string\"\nrange = [1, 10]",
+            "[params.x]\ntype = \"int\"\nlength = [1, 2]",
+            "[params.x]\ntype = \"int\"\nitems = \"int\"",
+            "[params.x]\ntype = \"int\"\nrange = [10, 1]",
+            "[params.x]\ntype = \"string\"\nenum = [\"a\", 1]",
+            "[params.x]\ntype = \"integer\"",
+            "[params.x]\ntype = \"int\"\nunits = \"bohr\"",
+        ] {
+            assert!(schema(bad).is_err(), "accepted {bad:?}");
+        }
+    }
+
+    #[test]
+    fn labels() {
+        let params = schema(SCAN).unwrap();
+        assert_eq!(params["scan_atoms"].type_label(), "array of int");
+        assert_eq!(
+            params["scan_atoms"].constraint_label(),
+            "between 2 and 4 items; at least 1"
+        );
+        assert_eq!(
+            params["guess"].constraint_label(),
+            "one of \"hcore\", \"pmodel\""
+        );
+        assert_eq!(params["nimages"].constraint_label(), "");
+    }
+}
@@ -0,0 +1,293 @@
+use crate::config::{MemScope, NodeConfig};
+use crate::software::{MemoryKeyword, Software};
+use crate::units::Memory;
+use serde::Deserialize;
+
+/// Context keys [`ResolvedResources::insert_into`] adds, each with the key it
+/// is derived from.
+pub const DERIVED_KEYS: [(&str, &str); 6] = [
+    ("mem_per_core", "mem"),
+    ("mem_total", "mem"),
+    ("mem_total_gb", "mem"),
+    ("mem_safe", "mem"),
+    ("mpi_procs", "nprocs"),
+    ("omp_threads", "nprocs"),
+];
+
+/// `[resources]` translated into the quantities each program actually wants.
+///
+/// Injected into the Tera context by [`ResolvedResources::insert_into`] so the
+/// same config renders correctly for every bundled software.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedResources {
+    /// Memory per core in MB.
+    pub mem_per_core: i64,
+    /// Memory for the whole job in MB.
+    pub mem_total: i64,
+    /// Value for the program's own memory keyword in MB — per core or total
+    /// depending on the software, with its headroom applied.
+    pub mem_safe: i64,
+    /// Number of MPI processes to launch.
+    pub mpi_procs: i64,
+    /// Number of shared-memory threads per process (`OMP_NUM_THREADS`).
+    pub omp_threads: i64,
+}
+
+impl ResolvedResources {
+    /// Resolve `nprocs` cores and `mem` MB (interpreted according to `scope`)
+    /// for `software`. Unknown software gets the requested value unchanged,
+    /// using the historical per-core meaning.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn resolve(nprocs: i64, mem: i64, scope: MemScope, software: Option<Software>) -> Self {
+        let nprocs = nprocs.max(1);
+        let (mem_per_core, mem_total) = match scope {
+            MemScope::PerCore => (mem, mem * nprocs),
+            MemScope::Total => (mem / nprocs, mem),
+        };
+        let (mem_safe, mpi) = software.map_or((mem_per_core, true), |sw| {
+            let profile = sw.resource_profile();
+            let base = match profile.memory {
+                MemoryKeyword::PerCore => mem_per_core,
+                MemoryKeyword::Total => mem_total,
+            };
+            ((base as f64 * profile.headroom).floor() as i64, profile.mpi)
+        });
+        let (mpi_procs, omp_threads) = if mpi { (nprocs, 1) } else { (1, nprocs) };
+        Self {
+            mem_per_core,
+            mem_total,
+            mem_safe,
+            mpi_procs,
+            omp_threads,
+        }
+    }
+
+    /// Insert the resolved values into `context`.
+    pub fn insert_into(&self, context: &mut tera::Context) {
+        let values = [
+            self.mem_per_core,
+            self.mem_total,
+            // Rounded up, so a job never asks for less than its memory.
+            (self.mem_total + 1023) / 1024,
+            self.mem_safe,
+            self.mpi_procs,
+            self.omp_threads,
+        ];
+        for ((key, _), value) in DERIVED_KEYS.into_iter().zip(values) {
+            context.insert(key, &value);
+        }
+    }
+}
+
+/// Resolve resources from the `nprocs` and `mem` already in `context` (so that
+/// `--var` overrides are honoured) and insert the derived keys.
+///
+/// A `mem` such as `"4GB"` from `--var` or `[parameters]` is read like
+/// `[resources] mem` and replaced by its value in MB. Does nothing when `mem`
+/// is not set or not a valid amount, which validation reports; a missing
+/// `nprocs` counts as one core.
+pub fn insert_resolved(context: &mut tera::Context, scope: MemScope, software: Option<Software>) {
+    let Some(mem) = context
+        .get("mem")
+        .and_then(|mem| Memory::deserialize(mem).ok())
+        .map(Memory::as_mb)
+    else {
+        return;
+    };
+    context.insert("mem", &mem);
+    let nprocs = context
+        .get("nprocs")
+        .and_then(serde_json::Value::as_i64)
+        .unwrap_or(1);
+    ResolvedResources::resolve(nprocs, mem, scope, software).insert_into(context);
+}
+
+/// Where the limits of a [`Node`] come from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NodeSource {
+    /// The machine gedent runs on.
+    #[default]
+    Local,
+    /// The `[node]` config section.
+    Declared,
+}
+
+/// Cores and memory of the node a job will run on, checked against
+/// `[resources]` to catch oversubscription. Unknown limits are not checked.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Node {
+    /// Cores available to one job.
+    pub cores: Option<i64>,
+    /// Memory available to one job in MB.
+    pub mem: Option<i64>,
+    /// Where the limits come from.
+    pub source: NodeSource,
+}
+
+impl Node {
+    /// The `[node]` profile if it sets any limit, otherwise the local machine
+    /// with `local = true` and no limits at all without.
+    pub fn from_config(config: &NodeConfig) -> Self {
+        if config.cores.is_none() && config.mem.is_none() {
+            return if config.local == Some(true) {
+                Self::local()
+            } else {
+                Self::default()
+            };
+        }
+        Self {
+            cores: config.cores,
+            mem: config.mem.map(Memory::as_mb),
+            source: NodeSource::Declared,
+        }
+    }
+
+    /// Logical CPUs and total memory (from `/proc/meminfo`, so Linux only) of
+    /// the machine gedent runs on.
+    pub fn local() -> Self {
+        let cores = std::thread::available_parallelism()
+            .ok()
+            .and_then(|n| i64::try_from(n.get()).ok());
+        let mem = std::fs::read_to_string("/proc/meminfo")
+            .ok()
+            .and_then(|meminfo| parse_meminfo(&meminfo));
+        Self {
+            cores,
+            mem,
+            source: NodeSource::Local,
+        }
+    }
+
+    /// How diagnostics refer to the node.
+    pub const fn describe(&self) -> &'static str {
+        match self.source {
+            NodeSource::Local => "this machine",
+            NodeSource::Declared => "the [node] profile",
+        }
+    }
+}
+
+/// `MemTotal` from the contents of `/proc/meminfo`, in MB.
+fn parse_meminfo(meminfo: &str) -> Option<i64> {
+    let kb: i64 = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kb / 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orca_applies_75_percent_per_core() {
+        let r = ResolvedResources::resolve(4, 4000, MemScope::PerCore, Some(Software::Orca));
+        assert_eq!(r.mem_per_core, 4000);
+        assert_eq!(r.mem_total, 16000);
+        assert_eq!(r.mem_safe, 3000);
+        assert_eq!((r.mpi_procs, r.omp_threads), (4, 1));
+    }
+
+    #[test]
+    fn gaussian_uses_total_memory_and_threads() {
+        let r = ResolvedResources::resolve(8, 1000, MemScope::PerCore, Some(Software::Gaussian));
+        assert_eq!(r.mem_total, 8000);
+        assert_eq!(r.mem_safe, 7200);
+        assert_eq!((r.mpi_procs, r.omp_threads), (1, 8));
+    }
+
+    #[test]
+    fn total_scope_divides_across_cores() {
+        let r = ResolvedResources::resolve(4, 16000, MemScope::Total, Some(Software::Orca));
+        assert_eq!(r.mem_per_core, 4000);
+        assert_eq!(r.mem_total, 16000);
+        assert_eq!(r.mem_safe, 3000);
+    }
+
+    #[test]
+    fn xtb_maps_nprocs_to_threads() {
+        let r = ResolvedResources::resolve(6, 500, MemScope::PerCore, Some(Software::Xtb));
+        assert_eq!((r.mpi_procs, r.omp_threads), (1, 6));
+        assert_eq!(r.mem_safe, 3000);
+    }
+
+    #[test]
+    fn unknown_software_keeps_requested_value() {
+        let r = ResolvedResources::resolve(2, 1000, MemScope::PerCore, None);
+        assert_eq!(r.mem_safe, 1000);
+    }
+
+    #[test]
+    fn insert_resolved_reads_context_and_skips_without_mem() {
+        let mut ctx = tera::Context::new();
+        ctx.insert("nprocs", &2);
+        insert_resolved(&mut ctx, MemScope::PerCore, Some(Software::Orca));
+        assert!(ctx.get("mem_safe").is_none());
+
+        ctx.insert("mem", &2048);
+        insert_resolved(&mut ctx, MemScope::PerCore, Some(Software::Orca));
+        let json = ctx.into_json();
+        assert_eq!(json["mem_total"], 4096);
+        assert_eq!(json["mem_total_gb"], 4);
+        assert_eq!(json["mem_safe"], 1536);
+
+        for (mem, gb) in [(1500, 2), (500, 1), (1024, 1)] {
+            let mut ctx = tera::Context::new();
+            ctx.insert("mem", &mem);
+            insert_resolved(&mut ctx, MemScope::Total, None);
+            assert_eq!(ctx.into_json()["mem_total_gb"], gb, "{mem} MB");
+        }
+    }
+
+    #[test]
+    fn insert_resolved_parses_mem_strings() {
+        let mut ctx = tera::Context::new();
+        ctx.insert("mem", "4GB");
+        insert_resolved(&mut ctx, MemScope::PerCore, None);
+        let json = ctx.into_json();
+        assert_eq!(json["mem"], 4096);
+        assert_eq!(json["mem_safe"], 4096);
+
+        let mut ctx = tera::Context::new();
+        ctx.insert("mem", "4 parsecs");
+        insert_resolved(&mut ctx, MemScope::PerCore, None);
+        assert_eq!(ctx.get("mem").unwrap(), "4 parsecs");
+        assert!(ctx.get("mem_safe").is_none());
+    }
+
+    #[test]
+    fn parses_meminfo_total() {
+        let meminfo = "MemTotal:       16318128 kB\nMemFree:         1234567 kB\n";
+        assert_eq!(parse_meminfo(meminfo), Some(15935));
+        assert_eq!(parse_meminfo("MemFree: 1 kB\n"), None);
+    }
+
+    #[test]
+    fn declared_node_wins_over_local_machine() {
+        let config = NodeConfig {
+            cores: Some(64),
+            mem: None,
+            local: Some(true),
+        };
+        let node = Node::from_config(&config);
+        assert_eq!(node.cores, Some(64));
+        assert_eq!(node.mem, None);
+        assert_eq!(node.source, NodeSource::Declared);
+        let local = NodeConfig {
+            local: Some(true),
+            ..NodeConfig::default()
+        };
+        assert_eq!(Node::from_config(&local).source, NodeSource::Local);
+    }
+
+    #[test]
+    fn no_node_profile_checks_nothing() {
+        let node = Node::from_config(&NodeConfig::default());
+        assert_eq!((node.cores, node.mem), (None, None));
+    }
+}
@@ -0,0 +1,443 @@
+use crate::output::{JobOutput, Termination};
+use crate::software::Software;
+use crate::status::OUTPUT_EXTENSIONS;
+use crate::units::KCAL_PER_HARTREE;
+use color_eyre::eyre::{bail, Report as Error, Result, WrapErr};
+use comfy_table::{presets, Table};
+use log::{debug, warn};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// How `gedent results` prints its table.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ResultsFormat {
+    /// Aligned columns, rounded.
+    #[default]
+    Table,
+    /// Comma-separated values at full precision.
+    Csv,
+    /// A JSON object keyed by molecule name.
+    Json,
+}
+
+/// Which energy the relative energies compare.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum EnergyKind {
+    /// Electronic energy.
+    #[default]
+    Electronic,
+    /// Electronic energy plus the thermal correction to the enthalpy.
+    Enthalpy,
+    /// Electronic energy plus the thermal correction to the Gibbs energy.
+    Gibbs,
+}
+
+/// Energies of one output, in Hartree.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Results {
+    pub file: PathBuf,
+    pub software: String,
+    /// Last electronic energy printed (ORCA `FINAL SINGLE POINT ENERGY`,
+    /// Gaussian `SCF Done`).
+    pub energy: Option<f64>,
+    /// Zero-point energy.
+    pub zpe: Option<f64>,
+    /// Thermal correction to the enthalpy, ZPE included.
+    pub h_corr: Option<f64>,
+    /// Thermal correction to the Gibbs energy, ZPE included.
+    pub g_corr: Option<f64>,
+    pub imaginary: Option<usize>,
+    /// Wall time in seconds.
+    pub wall_time_s: Option<f64>,
+    /// Energy of the chosen kind against the reference, in kcal/mol.
+    pub relative_kcal_mol: Option<f64>,
+}
+
+impl Results {
+    pub fn read(path: &Path) -> Result<Self, Error> {
+        let text =
+            read_to_string(path).wrap_err(format!("Can't read output {}", path.display()))?;
+        let output =
+            JobOutput::parse(&text).wrap_err(format!("Can't parse output {}", path.display()))?;
+        if output.termination != Termination::Normal {
+            warn!("{}: {}", path.display(), output.termination);
+        }
+        let mut results = match output.software {
+            Software::Orca => parse_orca(&text),
+            _ => parse_gaussian(&text),
+        };
+        results.file = path.to_path_buf();
+        results.software = output.software.to_string();
+        results.imaginary = output.imaginary;
+        Ok(results)
+    }
+
+    /// Energy of `kind`, if the output has what it takes.
+    pub fn total(&self, kind: EnergyKind) -> Option<f64> {
+        let correction = match kind {
+            EnergyKind::Electronic => Some(0.0),
+            EnergyKind::Enthalpy => self.h_corr,
+            EnergyKind::Gibbs => self.g_corr,
+        };
+        Some(self.energy? + correction?)
+    }
+}
+
+fn parse_orca(text: &str) -> Results {
+    let enthalpy = last_number(text, "Total Enthalpy");
+    let electronic = last_number(text, "Electronic energy");
+    Results {
+        energy: last_number(text, "FINAL SINGLE POINT ENERGY"),
+        zpe: last_number(text, "Zero point energy"),
+        // Both are printed to 8 decimals; round off the noise of subtracting.
+        h_corr: enthalpy
+            .zip(electronic)
+            .map(|(h, e)| ((h - e) * 1e8).round() / 1e8),
+        g_corr: last_number(text, "G-E(el)"),
+        wall_time_s: text
+            .lines()
+            .rev()
+            .find(|l| l.contains("TOTAL RUN TIME:"))
+            .map(duration),
+        ..Results::default()
+    }
+}
+
+fn parse_gaussian(text: &str) -> Results {
+    // One `Elapsed time` per job step; a multi-step job ran for their sum.
+    let elapsed: Vec<f64> = text
+        .lines()
+        .filter(|l| l.contains("Elapsed time:"))
+        .map(duration)
+        .collect();
+    Results {
+        energy: last_number(text, "SCF Done:"),
+        zpe: last_number(text, "Zero-point correction="),
+        h_corr: last_number(text, "Thermal correction to Enthalpy="),
+        g_corr: last_number(text, "Thermal correction to Gibbs Free Energy="),
+        wall_time_s: (!elapsed.is_empty()).then(|| elapsed.iter().sum()),
+        ..Results::default()
+    }
+}
+
+/// First number after `label` on the last line containing it, as in
+/// `Zero point energy  ...  0.021 Eh` or `SCF Done:  E(RB3LYP) =  -76.4`.
+fn last_number(text: &str, label: &str) -> Option<f64> {
+    let line = text.lines().rev().find(|l| l.contains(label))?;
+    let (_, rest) = line.split_once(label)?;
+    rest.split_whitespace().find_map(|t| t.parse().ok())
+}
+
+/// Seconds in `0 days 1 hours 2 minutes 3.5 seconds 120 msec`.
+fn duration(line: &str) -> f64 {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    tokens
+        .windows(2)
+        .filter_map(|pair| {
+            let value: f64 = pair[0].parse().ok()?;
+            let unit = pair[1].trim_end_matches('.');
+            let seconds = if unit.starts_with("day") {
+                86_400.0
+            } else if unit.starts_with("hour") {
+                3_600.0
+            } else if unit.starts_with("min") {
+                60.0
+            } else if unit.starts_with("sec") {
+                1.0
+            } else if unit.starts_with("msec") {
+                1e-3
+            } else {
+                return None;
+            };
+            Some(value * seconds)
+        })
+        .sum()
+}
+
+/// Read every output in `paths`; directories are searched for `.out` and
+/// `.log` files that ORCA or Gaussian wrote, skipping hidden directories.
+pub fn collect(paths: &[PathBuf]) -> Result<Vec<Results>, Error> {
+    let mut results = Vec::new();
+    for path in paths {
+        if !path.is_dir() {
+            results.push(Results::read(path)?);
+            continue;
+        }
+        let files = WalkDir::new(path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+            .filter_map(std::result::Result::ok)
+            .filter(|e| e.file_type().is_file())
+            .map(walkdir::DirEntry::into_path)
+            .map(|p| p.strip_prefix(".").map(Path::to_path_buf).unwrap_or(p))
+            .filter(|p| {
+                p.extension()
+                    .is_some_and(|ext| OUTPUT_EXTENSIONS.iter().any(|e| ext == *e))
+            });
+        for file in files {
+            match Results::read(&file) {
+                Ok(read) => results.push(read),
+                Err(e) => debug!("Skipping {}: {e:#}", file.display()),
+            }
+        }
+    }
+    Ok(results)
+}
+
+/// Name of each result: the output's stem, or its path without extension
+/// where stems repeat.
+pub fn names(results: &[Results]) -> Vec<String> {
+    let stem = |r: &Results| {
+        r.file
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned()
+    };
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for r in results {
+        *counts.entry(stem(r)).or_default() += 1;
+    }
+    results
+        .iter()
+        .map(|r| {
+            let name = stem(r);
+            if counts[&name] > 1 {
+                r.file.with_extension("").display().to_string()
+            } else {
+                name
+            }
+        })
+        .collect()
+}
+
+/// Fill in energies of `kind` relative to the result named `reference`, or
+/// to the lowest one.
+pub fn set_relative(
+    results: &mut [Results],
+    names: &[String],
+    kind: EnergyKind,
+    reference: Option<&str>,
+) -> Result<(), Error> {
+    let reference = match reference {
+        Some(name) => {
+            let Some(index) = names.iter().position(|n| n == name) else {
+                bail!(
+                    "No output named {name}; choose one of: {}",
+                    names.join(", ")
+                );
+            };
+            let Some(energy) = results[index].total(kind) else {
+                bail!(
+                    "{name} has no {} energy to compare against",
+                    format!("{kind:?}").to_lowercase()
+                );
+            };
+            energy
+        }
+        None => match results
+            .iter()
+            .filter_map(|r| r.total(kind))
+            .min_by(f64::total_cmp)
+        {
+            Some(lowest) => lowest,
+            None => return Ok(()),
+        },
+    };
+    for r in results.iter_mut() {
+        r.relative_kcal_mol = r.total(kind).map(|e| (e - reference) * KCAL_PER_HARTREE);
+    }
+    Ok(())
+}
+
+/// Format `results`, named by `names`.
+pub fn render(results: &[Results], names: &[String], format: ResultsFormat) -> String {
+    let fixed = |value: Option<f64>, decimals: usize| {
+        value.map_or_else(String::new, |v| format!("{v:.decimals$}"))
+    };
+    let count = |value: Option<usize>| value.map_or_else(String::new, |v| v.to_string());
+    match format {
+        ResultsFormat::Table => {
+            let mut table = Table::new();
+            table.load_preset(presets::NOTHING);
+            table.set_header(vec![
+                "Name",
+                "E (Eh)",
+                "ZPE",
+                "H corr",
+                "G corr",
+                "Imag",
+                "Time",
+                "Rel (kcal/mol)",
+            ]);
+            for (r, name) in results.iter().zip(names) {
+                table.add_row(vec![
+                    name.clone(),
+                    fixed(r.energy, 8),
+                    fixed(r.zpe, 6),
+                    fixed(r.h_corr, 6),
+                    fixed(r.g_corr, 6),
+                    count(r.imaginary),
+                    r.wall_time_s.map(clock).unwrap_or_default(),
+                    fixed(r.relative_kcal_mol, 2),
+                ]);
+            }
+            table.to_string()
+        }
+        ResultsFormat::Csv => {
+            let full = |value: Option<f64>| value.map_or_else(String::new, |v| v.to_string());
+            let mut csv = String::from(
+                "name,file,software,energy,zpe,h_corr,g_corr,imaginary,wall_time_s,relative_kcal_mol",
+            );
+            for (r, name) in results.iter().zip(names) {
+                let fields = [
+                    name.clone(),
+                    r.file.display().to_string(),
+                    r.software.clone(),
+                    full(r.energy),
+                    full(r.zpe),
+                    full(r.h_corr),
+                    full(r.g_corr),
+                    count(r.imaginary),
+                    full(r.wall_time_s),
+                    full(r.relative_kcal_mol),
+                ];
+                csv.push('\n');
+                csv.push_str(&fields.map(|f| csv_field(&f)).join(","));
+            }
+            csv
+        }
+        ResultsFormat::Json => {
+            let object: serde_json::Map<String, serde_json::Value> = names
+                .iter()
+                .cloned()
+                .zip(results.iter().map(|r| serde_json::json!(r)))
+                .collect();
+            serde_json::to_string_pretty(&object).unwrap_or_default()
+        }
+    }
+}
+
+/// `field`, quoted if it holds a comma, quote or newline.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// `3725.4` seconds as `1:02:05`.
+fn clock(seconds: f64) -> String {
+    // Outputs report whole seconds at best; sub-second precision is noise.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let total = seconds.round() as u64;
+    format!("{}:{:02}:{:02}", total / 3600, total / 60 % 60, total % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    const ORCA_FREQ: &str = "\
+                                 * O   R   C   A *
+|  1> ! PBE0 def2-SVP Freq
+FINAL SINGLE POINT ENERGY       -76.300000000000
+Electronic energy                 ...    -76.30000000 Eh
+Zero point energy                ...      0.02100000 Eh      13.18 kcal/mol
+Total Enthalpy                    ...    -76.27500000 Eh
+G-E(el)                           ...      0.00300000 Eh      1.88 kcal/mol
+                             ****ORCA TERMINATED NORMALLY****
+TOTAL RUN TIME: 0 days 1 hours 2 minutes 5 seconds 400 msec
+";
+
+    const GAUSSIAN_FREQ: &str = "\
+ Entering Gaussian System, Link 0=g16
+ SCF Done:  E(RB3LYP) =  -76.4089  A.U. after   10 cycles
+ Zero-point correction=                           0.021000 (Hartree/Particle)
+ Thermal correction to Enthalpy=                  0.024800
+ Thermal correction to Gibbs Free Energy=         0.003200
+ Elapsed time:       0 days  0 hours  1 minutes 23.5 seconds.
+ Elapsed time:       0 days  0 hours  0 minutes  6.5 seconds.
+ Normal termination of Gaussian 16 at Sun Oct 18 12:00:00 2026.
+";
+
+    fn results(text: &str, file: &str) -> Results {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(Path::new(file).file_name().unwrap());
+        std::fs::write(&path, text).unwrap();
+        let mut results = Results::read(&path).unwrap();
+        results.file = PathBuf::from(file);
+        results
+    }
+
+    #[test]
+    fn orca_energies_and_timing() {
+        let r = results(ORCA_FREQ, "water.out");
+        assert_eq!(r.software, "orca");
+        assert_relative_eq!(r.energy.unwrap(), -76.3);
+        assert_relative_eq!(r.zpe.unwrap(), 0.021);
+        assert_eq!(r.h_corr, Some(0.025));
+        assert_relative_eq!(r.g_corr.unwrap(), 0.003);
+        assert_relative_eq!(r.wall_time_s.unwrap(), 3725.4, epsilon = 1e-9);
+        assert_relative_eq!(r.total(EnergyKind::Gibbs).unwrap(), -76.297);
+    }
+
+    #[test]
+    fn gaussian_energies_and_timing() {
+        let r = results(GAUSSIAN_FREQ, "water.log");
+        assert_eq!(r.software, "gaussian");
+        assert_relative_eq!(r.energy.unwrap(), -76.4089);
+        assert_relative_eq!(r.h_corr.unwrap(), 0.0248);
+        assert_relative_eq!(r.wall_time_s.unwrap(), 90.0);
+    }
+
+    #[test]
+    fn relative_energies_against_lowest_or_reference() {
+        let mut all = vec![
+            results(ORCA_FREQ, "a/water.out"),
+            results(GAUSSIAN_FREQ, "b/water.log"),
+            results(ORCA_FREQ, "dimer.out"),
+        ];
+        all[2].energy = Some(-76.2);
+        let names = names(&all);
+        assert_eq!(names, ["a/water", "b/water", "dimer"]);
+
+        set_relative(&mut all, &names, EnergyKind::Electronic, None).unwrap();
+        assert_relative_eq!(all[1].relative_kcal_mol.unwrap(), 0.0);
+        assert_relative_eq!(
+            all[0].relative_kcal_mol.unwrap(),
+            0.1089 * KCAL_PER_HARTREE,
+            epsilon = 1e-9
+        );
+
+        set_relative(&mut all, &names, EnergyKind::Gibbs, Some("dimer")).unwrap();
+        assert_relative_eq!(
+            all[0].relative_kcal_mol.unwrap(),
+            -0.1 * KCAL_PER_HARTREE,
+            epsilon = 1e-9
+        );
+        assert!(set_relative(&mut all, &names, EnergyKind::Gibbs, Some("water")).is_err());
+    }
+
+    #[test]
+    fn csv_and_json_are_keyed_by_name() {
+        let all = vec![results(ORCA_FREQ, "water.out")];
+        let names = names(&all);
+        let csv = render(&all, &names, ResultsFormat::Csv);
+        assert!(csv.starts_with("name,file,software,energy,"));
+        let row: Vec<&str> = csv.lines().nth(1).unwrap().split(',').collect();
+        assert_eq!(row[..5], ["water", "water.out", "orca", "-76.3", "0.021"]);
+        assert_eq!(row[7..], ["", "3725.4", ""]);
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&all, &names, ResultsFormat::Json)).unwrap();
+        assert_eq!(json["water"]["zpe"], 0.021);
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(clock(3725.4), "1:02:05");
+    }
+}
@@ -0,0 +1,177 @@
+use crate::config::{Config, SchedulerConfig, SchedulerKind};
+use crate::software::Software;
+use crate::template::Template;
+use crate::{INCLUDE_SCHEDULERS_DIR, SCHEDULERS_DIR};
+use color_eyre::eyre::{bail, eyre, Report as Error, Result};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+/// Submit scripts rendered next to each input by `gedent gen --submit-script`.
+///
+/// The script template `schedulers/<type>` in the gedent home sees the input's
+/// context plus `input` (the input's file name), `name` (its stem), `workdir`
+/// (the absolute directory of the input, where the job runs), `software` and a
+/// `scheduler` object with `partition`, `account`, `walltime`, `modules` and
+/// `program`, so batch resources always match the input.
+#[derive(Debug)]
+pub struct Scheduler {
+    kind: SchedulerKind,
+    template: Template,
+    settings: serde_json::Value,
+    software: Option<String>,
+}
+
+impl Scheduler {
+    /// Load the script template for `[scheduler] type`, for inputs of
+    /// `software`.
+    pub fn from_config(config: &SchedulerConfig, software: Option<&str>) -> Result<Self, Error> {
+        let kind = config.kind.ok_or_else(|| {
+            eyre!("--submit-script needs `type` (slurm, pbs or sge) in [scheduler]")
+        })?;
+        let template = script_template(kind)?;
+        let walltime = config
+            .walltime
+            .as_deref()
+            .map(normalize_walltime)
+            .transpose()?;
+        let program = config.program.clone().or_else(|| {
+            software
+                .and_then(Software::from_name)
+                .map(|s| s.executable().to_string())
+        });
+        let Some(program) = program else {
+            bail!("Can't tell which program to run; set `program` in [scheduler]");
+        };
+        let settings = json!({
+            "partition": config.partition,
+            "account": config.account,
+            "walltime": walltime,
+            "modules": config.modules.clone().unwrap_or_default(),
+            "program": program,
+        });
+        Ok(Self {
+            kind,
+            template,
+            settings,
+            software: software.map(str::to_lowercase),
+        })
+    }
+
+    /// Render the script for `input`, returning its path (the input's path
+    /// with the scheduler's extension) and content.
+    pub fn render(&self, context: &tera::Context, input: &Path) -> Result<(PathBuf, String)> {
+        for key in ["nprocs", "mem_total"] {
+            if !context.contains_key(key) {
+                bail!("--submit-script needs `nprocs` and `mem` in [resources]");
+            }
+        }
+        let file_name = input
+            .file_name()
+            .ok_or_else(|| eyre!("Input {} has no file name", input.display()))?
+            .to_string_lossy();
+        let stem = input
+            .file_stem()
+            .map_or_else(|| file_name.clone(), |s| s.to_string_lossy());
+        let dir = input.parent().unwrap_or_else(|| Path::new(""));
+        let workdir = std::env::current_dir()?.join(dir);
+        let mut ctx = context.clone();
+        ctx.insert("input", &file_name);
+        ctx.insert("name", &stem);
+        ctx.insert("workdir", &workdir.to_string_lossy());
+        ctx.insert("software", &self.software.as_deref().unwrap_or_default());
+        ctx.insert("scheduler", &self.settings);
+        let script = self.template.render(&ctx)?;
+        Ok((input.with_extension(self.kind.to_string()), script))
+    }
+}
+
+/// The script template for `kind`, from the gedent home or, for homes set up
+/// before it had a `schedulers` directory, the bundled one.
+fn script_template(kind: SchedulerKind) -> Result<Template> {
+    let name = format!("scheduler/{kind}");
+    let path = Config::gedent_home()?
+        .join(SCHEDULERS_DIR)
+        .join(kind.to_string());
+    if path.is_file() {
+        return Template::from_file(name, path);
+    }
+    let body = INCLUDE_SCHEDULERS_DIR
+        .get_file(kind.to_string())
+        .and_then(|f| f.contents_utf8())
+        .ok_or_else(|| eyre!("No submit script template for {kind}"))?;
+    Template::from_source(name, path, body.to_string())
+}
+
+/// Turn `[D-]H:MM[:SS]` into `H:MM:SS`, folding days into hours, which SLURM,
+/// PBS and SGE all accept.
+fn normalize_walltime(walltime: &str) -> Result<String> {
+    let invalid = || eyre!("Invalid walltime {walltime:?}; expected [D-]HH:MM[:SS]");
+    let (days, clock) = match walltime.split_once('-') {
+        Some((days, clock)) => (days.trim().parse::<u64>().map_err(|_| invalid())?, clock),
+        None => (0, walltime),
+    };
+    let fields = clock
+        .split(':')
+        .map(|f| f.trim().parse::<u64>().map_err(|_| invalid()))
+        .collect::<Result<Vec<_>>>()?;
+    let (hours, minutes, seconds) = match fields[..] {
+        [h, m] => (h, m, 0),
+        [h, m, s] => (h, m, s),
+        _ => return Err(invalid()),
+    };
+    if minutes >= 60 || seconds >= 60 {
+        return Err(invalid());
+    }
+    Ok(format!("{}:{minutes:02}:{seconds:02}", days * 24 + hours))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walltime_is_normalized() {
+        assert_eq!(normalize_walltime("24:00:00").unwrap(), "24:00:00");
+        assert_eq!(normalize_walltime("2-12:30").unwrap(), "60:30:00");
+        assert_eq!(normalize_walltime("0:05:30").unwrap(), "0:05:30");
+        for bad in ["24", "1:60", "a-1:00", "1:00:00:00", ""] {
+            assert!(normalize_walltime(bad).is_err(), "{bad}");
+        }
+    }
+
+    fn scheduler(body: &str) -> Scheduler {
+        Scheduler {
+            kind: SchedulerKind::Slurm,
+            template: Template::with_body("scheduler/slurm", body),
+            settings: json!({"partition": "short", "modules": ["orca"], "program": "orca"}),
+            software: Some("orca".to_string()),
+        }
+    }
+
+    #[test]
+    fn script_sits_next_to_input_and_sees_its_context() {
+        let mut ctx = tera::Context::new();
+        ctx.insert("nprocs", &8);
+        ctx.insert("mem_total", &16000);
+        let body = "{{ name }} {{ input }} {{ software }} {{ scheduler.partition }} {{ nprocs }}";
+        let (path, content) = scheduler(body)
+            .render(&ctx, Path::new("jobs/water/opt.inp"))
+            .unwrap();
+        assert_eq!(path, PathBuf::from("jobs/water/opt.slurm"));
+        assert_eq!(content, "opt opt.inp orca short 8");
+
+        let (_, workdir) = scheduler("{{ workdir }}")
+            .render(&ctx, Path::new("jobs/water/opt.inp"))
+            .unwrap();
+        let expected = std::env::current_dir().unwrap().join("jobs/water");
+        assert_eq!(Path::new(&workdir), expected);
+    }
+
+    #[test]
+    fn script_needs_resources() {
+        let err = scheduler("")
+            .render(&tera::Context::new(), Path::new("water.inp"))
+            .unwrap_err();
+        assert!(err.to_string().contains("[resources]"));
+    }
+}
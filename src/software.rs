@@ -0,0 +1,115 @@
+/// A computational chemistry program with bundled templates.
+///
+/// Parsed case-insensitively from `TemplateMeta.software`, `[gedent] software`
+/// and `--software`. Unknown names are not an error — gedent simply falls back
+/// to software-agnostic behaviour.
+#[derive(
+    Clone, Copy, Debug, Eq, Hash, PartialEq, strum::Display, strum::EnumString, strum::EnumIter,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Software {
+    /// ORCA.
+    Orca,
+    /// Gaussian 09/16.
+    Gaussian,
+    /// xtb (standalone semiempirical tight-binding).
+    Xtb,
+    /// CREST conformer search.
+    Crest,
+    /// ADF / AMS.
+    Adf,
+    /// `NWChem`.
+    Nwchem,
+}
+
+/// How a program's own memory keyword is expressed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoryKeyword {
+    /// Memory per process/core (ORCA `%maxcore`, `NWChem` `memory`).
+    PerCore,
+    /// Memory for the whole job (Gaussian `%mem`).
+    Total,
+}
+
+/// How `[resources]` map onto a program's parallelism and memory settings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResourceProfile {
+    /// Whether the program's memory keyword is per core or total.
+    pub memory: MemoryKeyword,
+    /// Fraction of the requested memory to hand to the program, leaving room
+    /// for the allocations it makes outside its own budget.
+    pub headroom: f64,
+    /// Whether `nprocs` maps to MPI processes (`true`) or to shared-memory
+    /// threads (`false`, e.g. Gaussian `%nproc` or `OMP_NUM_THREADS` for xtb).
+    pub mpi: bool,
+}
+
+impl Software {
+    /// Parse a software name, returning `None` for programs gedent has no data for.
+    pub fn from_name(name: &str) -> Option<Self> {
+        name.parse().ok()
+    }
+
+    /// Usual name of the program's executable, run by submit scripts when
+    /// `[scheduler] program` is unset.
+    pub const fn executable(self) -> &'static str {
+        match self {
+            Self::Orca => "orca",
+            Self::Gaussian => "g16",
+            Self::Xtb => "xtb",
+            Self::Crest => "crest",
+            Self::Adf => "ams",
+            Self::Nwchem => "nwchem",
+        }
+    }
+
+    /// Resource semantics of this program.
+    pub const fn resource_profile(self) -> ResourceProfile {
+        match self {
+            // ORCA routinely exceeds %maxcore; the manual recommends ~75% of
+            // the physical memory per core.
+            Self::Orca => ResourceProfile {
+                memory: MemoryKeyword::PerCore,
+                headroom: 0.75,
+                mpi: true,
+            },
+            Self::Gaussian => ResourceProfile {
+                memory: MemoryKeyword::Total,
+                headroom: 0.9,
+                mpi: false,
+            },
+            Self::Nwchem => ResourceProfile {
+                memory: MemoryKeyword::PerCore,
+                headroom: 0.8,
+                mpi: true,
+            },
+            Self::Adf => ResourceProfile {
+                memory: MemoryKeyword::Total,
+                headroom: 1.0,
+                mpi: true,
+            },
+            Self::Xtb | Self::Crest => ResourceProfile {
+                memory: MemoryKeyword::Total,
+                headroom: 1.0,
+                mpi: false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn software_parses_case_insensitive() {
+        assert_eq!(Software::from_name("ORCA"), Some(Software::Orca));
+        assert_eq!(Software::from_name("gaussian"), Some(Software::Gaussian));
+        assert_eq!(Software::from_name("molpro"), None);
+    }
+
+    #[test]
+    fn software_displays_lowercase() {
+        assert_eq!(Software::Nwchem.to_string(), "nwchem");
+    }
+}
@@ -0,0 +1,218 @@
+use crate::software::Software;
+
+/// An implicit solvent with the names each program knows it by.
+///
+/// Dielectric constants and refractive indices are the 298 K values from the
+/// Minnesota Solvent Descriptor Database, as used by SMD.
+#[derive(Debug)]
+pub struct Solvent {
+    /// Canonical lowercase name (e.g. `"dichloromethane"`).
+    pub canonical: &'static str,
+    /// Common abbreviations and formulas (e.g. `"dcm"`, `"ch2cl2"`).
+    pub aliases: &'static [&'static str],
+    /// Accepted identifiers per program, preferred first. Programs that are
+    /// absent have no built-in parameters for the solvent.
+    pub names: &'static [(Software, &'static [&'static str])],
+    /// Static dielectric constant ε.
+    pub epsilon: f64,
+    /// Refractive index n (optical dielectric constant is n²).
+    pub refractive_index: f64,
+}
+
+impl Solvent {
+    /// Identifiers `software` accepts, or `None` if it does not know the solvent.
+    pub fn names_for(&self, software: Software) -> Option<&'static [&'static str]> {
+        self.names
+            .iter()
+            .find(|(sw, _)| *sw == software)
+            .map(|(_, n)| *n)
+    }
+
+    /// Whether `value` is one of the identifiers `software` accepts.
+    pub fn accepted_by(&self, software: Software, value: &str) -> bool {
+        self.names_for(software)
+            .is_some_and(|n| n.iter().any(|n| n.eq_ignore_ascii_case(value)))
+    }
+
+    fn spellings(&self) -> impl Iterator<Item = &'static str> + '_ {
+        std::iter::once(self.canonical)
+            .chain(self.aliases.iter().copied())
+            .chain(self.names.iter().flat_map(|(_, n)| n.iter().copied()))
+    }
+}
+
+/// Find the solvent `name` refers to, by canonical name, alias or any
+/// program's identifier.
+pub fn lookup(name: &str) -> Option<&'static Solvent> {
+    let name = name.trim();
+    SOLVENTS
+        .iter()
+        .find(|s| s.spellings().any(|n| n.eq_ignore_ascii_case(name)))
+}
+
+/// Closest known solvent name to `name`, for "did you mean" hints.
+pub fn suggest(name: &str) -> Option<&'static str> {
+    let name = name.trim().to_lowercase();
+    SOLVENTS
+        .iter()
+        .flat_map(|s| s.spellings().map(move |n| (s.canonical, n)))
+        .map(|(canonical, n)| (canonical, strsim::jaro_winkler(&name, &n.to_lowercase())))
+        .filter(|(_, score)| *score > 0.8)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(canonical, _)| canonical)
+}
+
+/// Context keys [`insert_properties`] adds.
+pub const PROPERTY_KEYS: [&str; 2] = ["solvent_epsilon", "solvent_refractive_index"];
+
+/// Insert `solvent_epsilon` and `solvent_refractive_index` for the `solvent`
+/// already in `context`. Does nothing for unknown or unset solvents.
+pub fn insert_properties(context: &mut tera::Context) {
+    let Some(solvent) = context
+        .get("solvent")
+        .and_then(serde_json::Value::as_str)
+        .and_then(lookup)
+    else {
+        return;
+    };
+    let [epsilon, refractive_index] = PROPERTY_KEYS;
+    context.insert(epsilon, &solvent.epsilon);
+    context.insert(refractive_index, &solvent.refractive_index);
+}
+
+macro_rules! solvent {
+    ($canonical:literal, [$($alias:literal),*], $eps:literal, $n:literal, {$($sw:ident: [$($s:literal),+]),* $(,)?}) => {
+        Solvent {
+            canonical: $canonical,
+            aliases: &[$($alias),*],
+            names: &[$((Software::$sw, &[$($s),+])),*],
+            epsilon: $eps,
+            refractive_index: $n,
+        }
+    };
+}
+
+static SOLVENTS: &[Solvent] = &[
+    solvent!("water", ["h2o"], 78.3553, 1.3328, {
+        Orca: ["water"], Gaussian: ["Water"], Xtb: ["water", "h2o"], Crest: ["water", "h2o"], Adf: ["Water"],
+    }),
+    solvent!("acetonitrile", ["mecn", "ch3cn"], 35.688, 1.3442, {
+        Orca: ["acetonitrile"], Gaussian: ["Acetonitrile"], Xtb: ["acetonitrile"], Crest: ["acetonitrile"], Adf: ["Acetonitrile"],
+    }),
+    solvent!("methanol", ["meoh", "ch3oh"], 32.613, 1.3288, {
+        Orca: ["methanol"], Gaussian: ["Methanol"], Xtb: ["methanol"], Crest: ["methanol"], Adf: ["Methanol"],
+    }),
+    solvent!("ethanol", ["etoh"], 24.852, 1.3611, {
+        Orca: ["ethanol"], Gaussian: ["Ethanol"], Xtb: ["ethanol"], Crest: ["ethanol"], Adf: ["Ethanol"],
+    }),
+    solvent!("dimethylsulfoxide", ["dmso"], 46.826, 1.4783, {
+        Orca: ["dmso"], Gaussian: ["DiMethylSulfoxide", "DMSO"], Xtb: ["dmso"], Crest: ["dmso"], Adf: ["DMSO"],
+    }),
+    solvent!("tetrahydrofuran", ["thf"], 7.4257, 1.4050, {
+        Orca: ["thf"], Gaussian: ["TetraHydroFuran", "THF"], Xtb: ["thf"], Crest: ["thf"], Adf: ["THF"],
+    }),
+    solvent!("toluene", [], 2.3741, 1.4961, {
+        Orca: ["toluene"], Gaussian: ["Toluene"], Xtb: ["toluene"], Crest: ["toluene"], Adf: ["Toluene"],
+    }),
+    solvent!("dichloromethane", ["dcm", "ch2cl2", "methylenechloride"], 8.93, 1.4242, {
+        Orca: ["dichloromethane", "ch2cl2"], Gaussian: ["DiChloroMethane", "CH2Cl2"], Xtb: ["ch2cl2"], Crest: ["ch2cl2"], Adf: ["DiChloroMethane"],
+    }),
+    solvent!("chloroform", ["chcl3", "trichloromethane"], 4.7113, 1.4459, {
+        Orca: ["chloroform"], Gaussian: ["Chloroform"], Xtb: ["chcl3"], Crest: ["chcl3"], Adf: ["Chloroform"],
+    }),
+    solvent!("hexane", ["n-hexane"], 1.8819, 1.3749, {
+        Orca: ["hexane"], Gaussian: ["n-Hexane"], Xtb: ["hexane"], Crest: ["hexane"], Adf: ["Hexane"],
+    }),
+    solvent!("heptane", ["n-heptane"], 1.9113, 1.3878, {
+        Orca: ["heptane"], Gaussian: ["Heptane"],
+    }),
+    solvent!("cyclohexane", [], 2.0165, 1.4266, {
+        Orca: ["cyclohexane"], Gaussian: ["CycloHexane"], Adf: ["CycloHexane"],
+    }),
+    solvent!("benzene", [], 2.2706, 1.5011, {
+        Orca: ["benzene"], Gaussian: ["Benzene"], Xtb: ["benzene"], Crest: ["benzene"], Adf: ["Benzene"],
+    }),
+    solvent!("dimethylformamide", ["dmf", "n,n-dimethylformamide"], 37.219, 1.4305, {
+        Orca: ["dmf"], Gaussian: ["N,N-DiMethylFormamide", "DMF"], Xtb: ["dmf"], Crest: ["dmf"], Adf: ["DiMethylFormamide"],
+    }),
+    solvent!("acetone", ["propanone"], 20.493, 1.3588, {
+        Orca: ["acetone"], Gaussian: ["Acetone"], Xtb: ["acetone"], Crest: ["acetone"], Adf: ["Acetone"],
+    }),
+    solvent!("diethylether", ["ether", "et2o"], 4.2400, 1.3526, {
+        Orca: ["diethylether"], Gaussian: ["DiethylEther"], Xtb: ["ether"], Crest: ["ether"], Adf: ["DiethylEther"],
+    }),
+    solvent!("ethylacetate", ["etoac"], 5.9867, 1.3723, {
+        Orca: ["ethylacetate"], Gaussian: ["EthylEthanoate"], Xtb: ["ethylacetate"], Crest: ["ethylacetate"],
+    }),
+    solvent!("1,4-dioxane", ["dioxane"], 2.2099, 1.4224, {
+        Orca: ["1,4-dioxane"], Gaussian: ["1,4-Dioxane"], Xtb: ["dioxane"], Crest: ["dioxane"],
+    }),
+    solvent!("octanol", ["1-octanol", "n-octanol"], 9.8629, 1.4295, {
+        Orca: ["octanol"], Gaussian: ["1-Octanol"], Xtb: ["octanol"], Crest: ["octanol"],
+    }),
+    solvent!("pyridine", [], 12.978, 1.5095, {
+        Orca: ["pyridine"], Gaussian: ["Pyridine"], Adf: ["Pyridine"],
+    }),
+    solvent!("nitromethane", ["ch3no2"], 36.562, 1.3817, {
+        Orca: ["nitromethane"], Gaussian: ["NitroMethane"], Xtb: ["nitromethane"], Crest: ["nitromethane"],
+    }),
+    solvent!("carbontetrachloride", ["ccl4", "tetrachloromethane"], 2.2280, 1.4601, {
+        Orca: ["ccl4"], Gaussian: ["CarbonTetraChloride", "CCl4"], Adf: ["CarbonTetrachloride"],
+    }),
+    solvent!("carbondisulfide", ["cs2"], 2.6105, 1.6319, {
+        Orca: ["cs2"], Gaussian: ["CarbonDiSulfide", "CS2"], Xtb: ["cs2"], Crest: ["cs2"],
+    }),
+    solvent!("aniline", [], 6.8882, 1.5863, {
+        Orca: ["aniline"], Gaussian: ["Aniline"], Xtb: ["aniline"], Crest: ["aniline"],
+    }),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_by_alias_and_program_name() {
+        assert_eq!(lookup("DCM").map(|s| s.canonical), Some("dichloromethane"));
+        assert_eq!(
+            lookup("DiMethylSulfoxide").map(|s| s.canonical),
+            Some("dimethylsulfoxide")
+        );
+        assert_eq!(lookup("chcl3").map(|s| s.canonical), Some("chloroform"));
+        assert!(lookup("unobtainium").is_none());
+    }
+
+    #[test]
+    fn names_are_per_program() {
+        let thf = lookup("thf").unwrap();
+        assert_eq!(
+            thf.names_for(Software::Gaussian).unwrap()[0],
+            "TetraHydroFuran"
+        );
+        assert!(thf.accepted_by(Software::Xtb, "THF"));
+        assert!(lookup("heptane")
+            .unwrap()
+            .names_for(Software::Xtb)
+            .is_none());
+    }
+
+    #[test]
+    fn suggest_closest_match() {
+        assert_eq!(suggest("watr"), Some("water"));
+        assert_eq!(suggest("acetonitrle"), Some("acetonitrile"));
+        assert_eq!(suggest("zzzzzz"), None);
+    }
+
+    #[test]
+    fn properties_inserted_for_known_solvents() {
+        let mut ctx = tera::Context::new();
+        insert_properties(&mut ctx);
+        assert!(ctx.get("solvent_epsilon").is_none());
+
+        ctx.insert("solvent", "Water");
+        insert_properties(&mut ctx);
+        let json = ctx.into_json();
+        approx::assert_relative_eq!(json["solvent_epsilon"].as_f64().unwrap(), 78.3553);
+        approx::assert_relative_eq!(json["solvent_refractive_index"].as_f64().unwrap(), 1.3328);
+    }
+}
@@ -0,0 +1,282 @@
+use crate::manifest::{Manifest, SUFFIX};
+use crate::output::{JobOutput, Termination};
+use comfy_table::{presets, Table};
+use log::{debug, warn};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+/// Extensions of ORCA and Gaussian inputs, looked for besides the configured
+/// `default_extension`.
+pub const INPUT_EXTENSIONS: [&str; 3] = ["inp", "com", "gjf"];
+
+/// Extensions a job's output may have, next to its input with the same stem.
+pub const OUTPUT_EXTENSIONS: [&str; 2] = ["out", "log"];
+
+/// Where a job stands, judged from its output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum State {
+    /// No output yet.
+    Pending,
+    /// The output is still being written.
+    Running,
+    Finished,
+    /// The job ended without a usable result, with the reason.
+    Failed(String),
+    /// The output is not from ORCA or Gaussian.
+    Unknown,
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pending => write!(f, "pending"),
+            Self::Running => write!(f, "running"),
+            Self::Finished => write!(f, "finished"),
+            Self::Failed(_) => write!(f, "failed"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// An input and what became of it.
+#[derive(Debug)]
+pub struct Job {
+    pub input: PathBuf,
+    pub output: Option<PathBuf>,
+    pub state: State,
+}
+
+/// Every input under `dir` and its state. Inputs are the files listed in
+/// gedent manifests and files with one of `extensions`; hidden directories
+/// are skipped. An unfinished output untouched for `stale_after` counts as
+/// failed rather than running.
+pub fn scan(dir: &Path, extensions: &[String], stale_after: Duration) -> Vec<Job> {
+    find_inputs(dir, extensions)
+        .into_iter()
+        .map(|input| {
+            let Some(output) = output_of(&input) else {
+                return Job {
+                    input,
+                    output: None,
+                    state: State::Pending,
+                };
+            };
+            let age = std::fs::metadata(&output)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| SystemTime::now().duration_since(t).ok())
+                .unwrap_or_default();
+            let state = match JobOutput::read(&output) {
+                Ok(parsed) => classify(&parsed, age, stale_after),
+                Err(e) => {
+                    debug!("{e:#}");
+                    State::Unknown
+                }
+            };
+            Job {
+                input,
+                output: Some(output),
+                state,
+            }
+        })
+        .collect()
+}
+
+fn find_inputs(dir: &Path, extensions: &[String]) -> Vec<PathBuf> {
+    let mut inputs = Vec::new();
+    let files = WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.file_type().is_file());
+    for file in files {
+        let path = file.into_path();
+        if path.to_string_lossy().ends_with(SUFFIX) {
+            match Manifest::load(&path) {
+                Ok(manifest) => {
+                    let base = path.parent().unwrap_or_else(|| Path::new(""));
+                    inputs.extend(
+                        manifest
+                            .inputs
+                            .iter()
+                            .map(|e| base.join(&e.file))
+                            .filter(|p| p.is_file()),
+                    );
+                }
+                Err(_) => warn!("Skipping unreadable manifest {}", path.display()),
+            }
+        } else if path
+            .extension()
+            .is_some_and(|ext| extensions.iter().any(|e| ext == e.as_str()))
+        {
+            inputs.push(path);
+        }
+    }
+    inputs.sort();
+    inputs.dedup();
+    inputs
+}
+
+/// The most recently written `<stem>.out` or `<stem>.log` next to `input`.
+fn output_of(input: &Path) -> Option<PathBuf> {
+    OUTPUT_EXTENSIONS
+        .iter()
+        .map(|ext| input.with_extension(ext))
+        .filter(|p| p != input)
+        .filter_map(|p| Some((std::fs::metadata(&p).ok()?.modified().ok()?, p)))
+        .max()
+        .map(|(_, p)| p)
+}
+
+/// State of a job whose output was last written `age` ago.
+pub fn classify(output: &JobOutput, age: Duration, stale_after: Duration) -> State {
+    match &output.termination {
+        Termination::Normal => {
+            let expected = output.expected_imaginary();
+            match output.imaginary {
+                Some(found) if found != expected => State::Failed(format!(
+                    "{found} imaginary frequencies, expected {expected}"
+                )),
+                _ => State::Finished,
+            }
+        }
+        Termination::Interrupted if age < stale_after => State::Running,
+        other => State::Failed(other.to_string()),
+    }
+}
+
+/// Table of `jobs`, paths shown relative to `dir`.
+pub fn table(dir: &Path, jobs: &[Job]) -> Table {
+    let relative = |path: &Path| path.strip_prefix(dir).unwrap_or(path).display().to_string();
+    let mut table = Table::new();
+    table.load_preset(presets::NOTHING);
+    table.set_header(vec!["Input", "Output", "Status", "Details"]);
+    for job in jobs {
+        let details = match &job.state {
+            State::Failed(reason) => reason.clone(),
+            _ => String::new(),
+        };
+        table.add_row(vec![
+            relative(&job.input),
+            job.output.as_deref().map(relative).unwrap_or_default(),
+            job.state.to_string(),
+            details,
+        ]);
+    }
+    table
+}
+
+/// Count of jobs per state, e.g. `3 finished, 1 failed`.
+pub fn summary(jobs: &[Job]) -> String {
+    let counts: Vec<String> = ["finished", "failed", "running", "pending", "unknown"]
+        .into_iter()
+        .filter_map(|name| {
+            let n = jobs.iter().filter(|j| j.state.to_string() == name).count();
+            (n > 0).then(|| format!("{n} {name}"))
+        })
+        .collect();
+    if counts.is_empty() {
+        "no inputs found".to_string()
+    } else {
+        counts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::software::Software;
+
+    fn job_output(termination: Termination, jobtype: &str, imaginary: Option<usize>) -> JobOutput {
+        JobOutput {
+            software: Software::Orca,
+            termination,
+            geometry: None,
+            charge: None,
+            mult: None,
+            jobtype: Some(jobtype.to_string()),
+            imaginary,
+        }
+    }
+
+    #[test]
+    fn classification_checks_termination_and_frequencies() {
+        let hour = Duration::from_secs(3600);
+        let recent = Duration::from_secs(60);
+        let state = |termination, jobtype, imaginary, age| {
+            classify(&job_output(termination, jobtype, imaginary), age, hour)
+        };
+        assert_eq!(
+            state(Termination::Normal, "sp", None, recent),
+            State::Finished
+        );
+        assert_eq!(
+            state(Termination::Normal, "optts", Some(1), recent),
+            State::Finished
+        );
+        assert_eq!(
+            state(Termination::Normal, "optfreq", Some(1), recent),
+            State::Failed("1 imaginary frequencies, expected 0".to_string())
+        );
+        assert_eq!(
+            state(Termination::Interrupted, "opt", None, recent),
+            State::Running
+        );
+        assert!(matches!(
+            state(Termination::Interrupted, "opt", None, 2 * hour),
+            State::Failed(_)
+        ));
+        assert_eq!(
+            state(Termination::ScfNotConverged, "sp", None, recent),
+            State::Failed("SCF did not converge".to_string())
+        );
+    }
+
+    #[test]
+    fn inputs_come_from_extensions_and_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = |p: &str| dir.path().join(p);
+        std::fs::create_dir_all(path("jobs")).unwrap();
+        std::fs::create_dir_all(path(".git")).unwrap();
+        for file in [
+            "a.inp",
+            "jobs/b.gjf",
+            "jobs/b.log",
+            "c.xyz",
+            "d.in",
+            ".git/e.inp",
+        ] {
+            std::fs::write(path(file), "").unwrap();
+        }
+        std::fs::write(
+            path("jobs/sp.manifest.json"),
+            r#"{"gedent_version": "0", "command": [], "config_chain": [],
+                "inputs": [{"file": "d.in", "sha256": "", "template": {"path": "sp", "sha256": ""},
+                            "context": {}}]}"#,
+        )
+        .unwrap();
+        std::fs::write(path("jobs/d.in"), "").unwrap();
+
+        let extensions: Vec<String> = INPUT_EXTENSIONS.map(String::from).to_vec();
+        let jobs = scan(dir.path(), &extensions, Duration::from_secs(60));
+        let found: Vec<(String, String)> = jobs
+            .iter()
+            .map(|j| {
+                let rel = j.input.strip_prefix(dir.path()).unwrap();
+                (rel.display().to_string(), j.state.to_string())
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("a.inp".to_string(), "pending".to_string()),
+                ("jobs/b.gjf".to_string(), "unknown".to_string()),
+                ("jobs/d.in".to_string(), "pending".to_string()),
+            ]
+        );
+        assert_eq!(summary(&jobs), "2 pending, 1 unknown");
+    }
+}
@@ -0,0 +1,232 @@
+use color_eyre::eyre::{bail, eyre, Report as Error, Result, WrapErr};
+use serde::Serialize;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+/// Values to generate every combination of, from `--sweep KEY=A,B` and
+/// `--sweep-file`.
+///
+/// Axes keep the order they were given in; the last axis varies fastest.
+#[derive(Debug, Default)]
+pub struct Sweep {
+    axes: Vec<(String, Vec<toml::Value>)>,
+}
+
+impl Sweep {
+    /// Collect the axes of `--sweep` specs and an optional sweep file, a TOML
+    /// table of arrays (`method = ["pbe0", "b3lyp"]`).
+    pub fn parse(specs: &[String], file: Option<&Path>) -> Result<Self, Error> {
+        let mut sweep = Self::default();
+        if let Some(file) = file {
+            let body = read_to_string(file)
+                .wrap_err(format!("Can't read sweep file {}", file.display()))?;
+            let table: toml::Table = toml::from_str(&body)
+                .wrap_err(format!("Can't parse sweep file {}", file.display()))?;
+            for (key, value) in table {
+                let values = match value {
+                    toml::Value::Array(values) => values,
+                    other => vec![other],
+                };
+                sweep.push(key, values)?;
+            }
+        }
+        for spec in specs {
+            let (key, values) = spec
+                .split_once('=')
+                .ok_or_else(|| eyre!("--sweep must be KEY=VALUE,VALUE,..., got {spec:?}"))?;
+            let values = split_values(values)
+                .into_iter()
+                .map(|v| crate::parse_value(v.trim()))
+                .collect();
+            sweep.push(key.trim().to_string(), values)?;
+        }
+        Ok(sweep)
+    }
+
+    fn push(&mut self, key: String, values: Vec<toml::Value>) -> Result<()> {
+        if key.is_empty() {
+            bail!("Sweep key cannot be empty");
+        }
+        if values.is_empty() {
+            bail!("Sweep over `{key}` has no values");
+        }
+        if self.axes.iter().any(|(k, _)| *k == key) {
+            bail!("`{key}` is swept more than once");
+        }
+        self.axes.push((key, values));
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.axes.is_empty()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.axes.iter().map(|(k, _)| k.as_str())
+    }
+
+    /// Every combination of one value per axis.
+    pub fn combinations(&self) -> Vec<Vec<(String, toml::Value)>> {
+        self.axes
+            .iter()
+            .fold(vec![vec![]], |combinations, (key, values)| {
+                combinations
+                    .iter()
+                    .flat_map(|combination| {
+                        values.iter().map(move |value| {
+                            let mut next = combination.clone();
+                            next.push((key.clone(), value.clone()));
+                            next
+                        })
+                    })
+                    .collect()
+            })
+    }
+
+    /// The swept values as a JSON object, for the manifest.
+    pub fn to_json(&self) -> serde_json::Value {
+        self.axes
+            .iter()
+            .map(|(key, values)| (key.clone(), serde_json::json!(values)))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+}
+
+/// Split the values of a `--sweep` spec on the commas that are not inside
+/// brackets, braces or quotes, so `[1,2],[1,3]` gives two arrays.
+fn split_values(values: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0_usize;
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in values.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => depth = depth.saturating_sub(1),
+            (None, ',') if depth == 0 => {
+                parts.push(&values[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&values[start..]);
+    parts
+}
+
+/// Make `pattern` tell combinations apart: keys it doesn't mention yet are
+/// appended as `_{key}` before a trailing `.{ext}`.
+pub fn unique_pattern<'a>(pattern: &str, keys: impl Iterator<Item = &'a str>) -> String {
+    let mut suffix = String::new();
+    for key in keys.filter(|key| !pattern.contains(&format!("{{{key}}}"))) {
+        suffix.push_str("_{");
+        suffix.push_str(key);
+        suffix.push('}');
+    }
+    pattern.strip_suffix(".{ext}").map_or_else(
+        || format!("{pattern}{suffix}"),
+        |stem| format!("{stem}{suffix}.{{ext}}"),
+    )
+}
+
+/// Record of a sweep: the axes and which file holds which combination.
+#[derive(Debug, Serialize)]
+pub struct Manifest {
+    pub template: String,
+    pub sweep: serde_json::Value,
+    pub inputs: Vec<Entry>,
+}
+
+/// One generated input of a sweep.
+#[derive(Debug, Serialize)]
+pub struct Entry {
+    pub file: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub molecule: Option<PathBuf>,
+    pub parameters: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn specs(specs: &[&str]) -> Vec<String> {
+        specs.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn combinations_are_the_cartesian_product() {
+        let sweep = Sweep::parse(
+            &specs(&[
+                "method=pbe0, b3lyp",
+                "basis_set=def2-svp,def2-tzvp",
+                "charge=0,1",
+            ]),
+            None,
+        )
+        .unwrap();
+        let combinations = sweep.combinations();
+        assert_eq!(combinations.len(), 8);
+        assert_eq!(
+            combinations[1],
+            [
+                ("method".to_string(), toml::Value::from("pbe0")),
+                ("basis_set".to_string(), toml::Value::from("def2-svp")),
+                ("charge".to_string(), toml::Value::Integer(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn commas_inside_arrays_and_strings_do_not_split() {
+        assert_eq!(split_values("[1,2],[1, 3]"), ["[1,2]", "[1, 3]"]);
+        assert_eq!(split_values("\"a,b\",'c,d',e"), ["\"a,b\"", "'c,d'", "e"]);
+        let sweep = Sweep::parse(&specs(&["scan_atoms=[1,2],[1,3]"]), None).unwrap();
+        let combinations = sweep.combinations();
+        assert_eq!(combinations.len(), 2);
+        assert_eq!(
+            combinations[1][0].1,
+            toml::Value::Array(vec![toml::Value::Integer(1), toml::Value::Integer(3)])
+        );
+    }
+
+    #[test]
+    fn sweep_file_and_flags_combine() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("sweep.toml");
+        std::fs::write(
+            &file,
+            "method = [\"pbe0\", \"tpss\"]\nsolvent = \"water\"\n",
+        )
+        .unwrap();
+        let sweep = Sweep::parse(&specs(&["basis_set=def2-svp"]), Some(&file)).unwrap();
+        assert_eq!(
+            sweep.keys().collect::<Vec<_>>(),
+            ["method", "solvent", "basis_set"]
+        );
+        assert_eq!(sweep.combinations().len(), 2);
+
+        assert!(Sweep::parse(&specs(&["method=tpss"]), Some(&file)).is_err());
+        assert!(Sweep::parse(&specs(&["method"]), None).is_err());
+    }
+
+    #[test]
+    fn pattern_gains_missing_keys() {
+        let keys = ["method", "basis_set"];
+        assert_eq!(
+            unique_pattern("{name}.{ext}", keys.into_iter()),
+            "{name}_{method}_{basis_set}.{ext}"
+        );
+        assert_eq!(
+            unique_pattern("{method}/{name}.{ext}", keys.into_iter()),
+            "{method}/{name}_{basis_set}.{ext}"
+        );
+        assert_eq!(
+            unique_pattern("{name}", keys.into_iter()),
+            "{name}_{method}_{basis_set}"
+        );
+    }
+}
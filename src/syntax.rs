@@ -0,0 +1,447 @@
+use crate::software::Software;
+use crate::validation::{Check, Diagnostic};
+
+/// ORCA `%` keywords that take their value on the same line and open no block.
+const ORCA_ONE_LINE: &[&str] = &["maxcore", "moinp", "base", "pointcharges"];
+
+/// ORCA `%` keywords that read the file named on the same line, or open a block
+/// closed by `end` when no file is given.
+const ORCA_FILE_OR_BLOCK: &[&str] = &["compound"];
+
+/// ORCA sub-blocks that need their own `end` inside a `%` block, unless the
+/// `end` is on the same line.
+const ORCA_SUBBLOCKS: &[&str] = &[
+    "scan",
+    "constraints",
+    "modify_internal",
+    "fragments",
+    "coords",
+    "newgto",
+    "newecp",
+    "newauxjgto",
+    "newauxcgto",
+    "newauxjkgto",
+    "addgto",
+];
+
+/// ORCA coordinate sections given inline and closed by a lone `*`.
+const ORCA_INLINE_COORDS: &[&str] = &["xyz", "int", "gzmt"];
+
+/// ORCA coordinate sections that read a file and have no closing `*`.
+const ORCA_FILE_COORDS: &[&str] = &["xyzfile", "gzmtfile", "pdbfile"];
+
+/// Check a rendered input for the syntax errors of its program's input format.
+///
+/// Programs without a checker yield no diagnostics.
+pub fn check(software: Software, content: &str) -> Vec<Diagnostic> {
+    match software {
+        Software::Orca => check_orca(content),
+        Software::Gaussian => check_gaussian(content),
+        Software::Xtb => check_xtb(content),
+        Software::Crest => check_crest(content),
+        Software::Adf | Software::Nwchem => vec![],
+    }
+}
+
+/// Non-empty lines with ORCA `#` comments removed, with 1-based line numbers.
+fn orca_lines(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    content.lines().enumerate().filter_map(|(i, line)| {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        (!line.is_empty()).then_some((i + 1, line))
+    })
+}
+
+fn check_orca(content: &str) -> Vec<Diagnostic> {
+    let mut diags = vec![];
+    // Open `%` block: name, line it was opened on and sub-block depth.
+    let mut block: Option<(String, usize, usize)> = None;
+    // Open inline coordinate section: line it was opened on.
+    let mut coords_open: Option<usize> = None;
+    let mut has_coords = false;
+
+    let unterminated = |name: &str, line: usize| {
+        Diagnostic::error(
+            Check::OrcaBlock,
+            format!("`%{name}` block opened on line {line} is not closed by `end`"),
+        )
+    };
+
+    for (n, line) in orca_lines(content) {
+        if let Some(start) = coords_open {
+            if line == "*" {
+                coords_open = None;
+                continue;
+            }
+            if !(line.starts_with('%') || line.starts_with('!')) {
+                continue;
+            }
+            diags.push(Diagnostic::error(
+                Check::OrcaCoordinates,
+                format!("coordinate section opened on line {start} is not closed by `*`"),
+            ));
+            coords_open = None;
+        }
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let first = words[0].to_ascii_lowercase();
+        let ends_inline = words.len() > 1 && words[words.len() - 1].eq_ignore_ascii_case("end");
+
+        if let Some((name, start, depth)) = &mut block {
+            if line.starts_with('%') || line.starts_with('*') || line.starts_with('!') {
+                diags.push(unterminated(name, *start));
+                block = None;
+            } else if first == "end" && words.len() == 1 {
+                if *depth == 0 {
+                    block = None;
+                } else {
+                    *depth -= 1;
+                }
+                continue;
+            } else {
+                if ORCA_SUBBLOCKS.contains(&first.as_str()) && !ends_inline {
+                    *depth += 1;
+                }
+                continue;
+            }
+        }
+
+        if let Some(rest) = line.strip_prefix('%') {
+            let name = rest
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            if name == "coords" {
+                has_coords = true;
+            }
+            let reads_file = ORCA_FILE_OR_BLOCK.contains(&name.as_str()) && words.len() > 1;
+            if !ORCA_ONE_LINE.contains(&name.as_str()) && !reads_file && !ends_inline {
+                block = Some((name, n, 0));
+            }
+        } else if let Some(rest) = line.strip_prefix('*') {
+            has_coords = true;
+            check_orca_coords_header(rest, n, &mut diags);
+            let kind = rest
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            if ORCA_INLINE_COORDS.contains(&kind.as_str()) {
+                coords_open = Some(n);
+            }
+        } else if first == "end" {
+            diags.push(Diagnostic::error(
+                Check::OrcaBlock,
+                format!("`end` on line {n} does not close any block"),
+            ));
+        }
+    }
+
+    if let Some((name, start, _)) = &block {
+        diags.push(unterminated(name, *start));
+    }
+    if let Some(start) = coords_open {
+        diags.push(Diagnostic::error(
+            Check::OrcaCoordinates,
+            format!("coordinate section opened on line {start} is not closed by `*`"),
+        ));
+    }
+    if !has_coords {
+        diags.push(Diagnostic::error(
+            Check::OrcaCoordinates,
+            "input has no coordinate section (`*xyz`, `*xyzfile` or `%coords`)",
+        ));
+    }
+    diags
+}
+
+/// Check the `*xyz charge mult` header of an ORCA coordinate section.
+fn check_orca_coords_header(rest: &str, line: usize, diags: &mut Vec<Diagnostic>) {
+    let words: Vec<&str> = rest.split_whitespace().collect();
+    let Some(kind) = words.first().map(|w| w.to_ascii_lowercase()) else {
+        diags.push(Diagnostic::error(
+            Check::OrcaCoordinates,
+            format!("`*` on line {line} does not close any coordinate section"),
+        ));
+        return;
+    };
+    if !ORCA_INLINE_COORDS.contains(&kind.as_str()) && !ORCA_FILE_COORDS.contains(&kind.as_str()) {
+        diags.push(Diagnostic::error(
+            Check::OrcaCoordinates,
+            format!("unknown coordinate type `*{kind}` on line {line}"),
+        ));
+        return;
+    }
+    let charge_mult = words
+        .get(1..3)
+        .is_some_and(|cm| cm.iter().all(|w| w.parse::<i64>().is_ok()));
+    if !charge_mult {
+        diags.push(Diagnostic::error(
+            Check::OrcaCoordinates,
+            format!("`*{kind}` on line {line} needs an integer charge and multiplicity"),
+        ));
+    }
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+fn check_gaussian(content: &str) -> Vec<Diagnostic> {
+    let error = |message: String| vec![Diagnostic::error(Check::GaussianStructure, message)];
+    let lines: Vec<&str> = content.lines().collect();
+    let mut i = lines
+        .iter()
+        .position(|l| !l.trim_start().starts_with('%'))
+        .unwrap_or(lines.len());
+
+    match lines.get(i) {
+        Some(line) if line.trim_start().starts_with('#') => {}
+        Some(line) if is_blank(line) => {
+            return error(format!(
+                "blank line {} before the route section; Gaussian reads it as an empty route",
+                i + 1
+            ))
+        }
+        _ => return error("no route section (a line starting with `#`)".to_string()),
+    }
+    let all_check = lines[i..]
+        .iter()
+        .take_while(|l| !is_blank(l))
+        .any(|l| l.to_ascii_lowercase().contains("allcheck"));
+    // Skip the route section and the blank line that ends it.
+    while i < lines.len() && !is_blank(lines[i]) {
+        i += 1;
+    }
+    i += 1;
+
+    if !all_check {
+        if lines.get(i).map_or(true, |l| is_blank(l)) {
+            return error("missing title section after the route section".to_string());
+        }
+        while i < lines.len() && !is_blank(lines[i]) {
+            i += 1;
+        }
+        i += 1;
+        let Some(line) = lines.get(i) else {
+            return error("missing charge and multiplicity after the title section".to_string());
+        };
+        let words: Vec<&str> = line
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|w| !w.is_empty())
+            .collect();
+        if words.len() < 2 || words.iter().any(|w| w.parse::<i64>().is_err()) {
+            return error(format!(
+                "expected charge and multiplicity on line {}, found `{}`",
+                i + 1,
+                line.trim()
+            ));
+        }
+    }
+
+    if !lines.last().is_some_and(|l| is_blank(l)) {
+        return error(
+            "input does not end with a blank line, which Gaussian needs after the last section"
+                .to_string(),
+        );
+    }
+    vec![]
+}
+
+fn check_xtb(content: &str) -> Vec<Diagnostic> {
+    let mut diags = vec![];
+    let mut end: Option<usize> = None;
+    let mut has_groups = false;
+    for (i, line) in content.lines().enumerate() {
+        let Some(group) = line.trim().strip_prefix('$') else {
+            continue;
+        };
+        let group = group.split_whitespace().next().unwrap_or_default();
+        has_groups = true;
+        if let Some(end) = end {
+            diags.push(Diagnostic::warning(
+                Check::XtbGroups,
+                format!(
+                    "`${group}` on line {} comes after `$end` on line {end} and is ignored",
+                    i + 1
+                ),
+            ));
+        } else if group == "end" {
+            end = Some(i + 1);
+        }
+    }
+    if has_groups && end.is_none() {
+        diags.push(Diagnostic::warning(
+            Check::XtbGroups,
+            "detailed input does not end with `$end`",
+        ));
+    }
+    diags
+}
+
+fn check_crest(content: &str) -> Vec<Diagnostic> {
+    match toml::from_str::<toml::Table>(content) {
+        Ok(_) => vec![],
+        Err(e) => vec![Diagnostic::error(
+            Check::CrestToml,
+            format!("input is not valid TOML: {}", e.message()),
+        )],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(software: Software, content: &str) -> Vec<String> {
+        check(software, content)
+            .into_iter()
+            .map(|d| format!("{} {}", d.check.code(), d.message))
+            .collect()
+    }
+
+    const ORCA_OK: &str = "! B3LYP def2-SVP Opt
+%pal nprocs 4 end
+%maxcore 3000
+%geom
+ scan
+  B 0 1 = 1.0, 1.5, 10
+ end
+ ts_active_atoms { 0 1 } end
+end
+# comment
+*xyz 0 1
+O 0.0 0.0 0.0
+H 0.96 0.0 0.0
+*
+";
+
+    #[test]
+    fn orca_accepts_balanced_input() {
+        assert!(check(Software::Orca, ORCA_OK).is_empty());
+        assert!(check(Software::Orca, "! XTB\n*xyzfile 0 1 mol.xyz\n").is_empty());
+    }
+
+    #[test]
+    fn orca_reports_unterminated_block() {
+        let input = "! B3LYP\n%pal\n nprocs 4\n\n*xyz 0 1\nH 0 0 0\n*\n";
+        assert_eq!(
+            messages(Software::Orca, input),
+            ["I001 `%pal` block opened on line 2 is not closed by `end`"]
+        );
+        // A sub-block's `end` doesn't close the outer block.
+        let input = "%geom\n scan\n  B 0 1 = 1.0, 1.5, 10\n end\n*xyz 0 1\nH 0 0 0\n*\n";
+        assert_eq!(
+            messages(Software::Orca, input),
+            ["I001 `%geom` block opened on line 1 is not closed by `end`"]
+        );
+        let input = "%cpcm\n smd true\n";
+        assert_eq!(
+            messages(Software::Orca, input)[0],
+            "I001 `%cpcm` block opened on line 1 is not closed by `end`"
+        );
+    }
+
+    #[test]
+    fn orca_accepts_one_line_keywords_and_blocks() {
+        let input = "! B3LYP\n%compound \"opt_then_sp.cmp\"\n%moinp \"guess.gbw\"\n\
+                     %scf maxiter 500 end\n%geom maxiter 100 end\n*xyz 0 1\nH 0 0 0\n*\n";
+        assert!(messages(Software::Orca, input).is_empty());
+        // Without a file, `%compound` opens a script block.
+        let input = "%compound\n Variable E = 0.0;\n E = 1.0;\nend\n*xyz 0 1\nH 0 0 0\n*\n";
+        assert!(messages(Software::Orca, input).is_empty());
+        let input = "%compound\n Variable E = 0.0;\n*xyz 0 1\nH 0 0 0\n*\n";
+        assert_eq!(
+            messages(Software::Orca, input),
+            ["I001 `%compound` block opened on line 1 is not closed by `end`"]
+        );
+    }
+
+    #[test]
+    fn orca_reports_stray_end() {
+        let input = "%pal nprocs 4 end\nend\n*xyz 0 1\nH 0 0 0\n*\n";
+        assert_eq!(
+            messages(Software::Orca, input),
+            ["I001 `end` on line 2 does not close any block"]
+        );
+    }
+
+    #[test]
+    fn orca_reports_coordinate_problems() {
+        assert_eq!(
+            messages(Software::Orca, "! B3LYP\n*xyz 0 1\nH 0 0 0\n"),
+            ["I002 coordinate section opened on line 2 is not closed by `*`"]
+        );
+        assert_eq!(
+            messages(Software::Orca, "! B3LYP\n*xyz 0\nH 0 0 0\n*\n"),
+            ["I002 `*xyz` on line 2 needs an integer charge and multiplicity"]
+        );
+        assert_eq!(
+            messages(Software::Orca, "! B3LYP\n%pal nprocs 4 end\n"),
+            ["I002 input has no coordinate section (`*xyz`, `*xyzfile` or `%coords`)"]
+        );
+    }
+
+    const GAUSSIAN_OK: &str =
+        "%chk=w.chk\r\n%nproc=4\r\n# B3LYP/def2SVP opt\r\n\r\ntitle\r\n\r\n0 1\r\nH 0 0 0\r\n\r\n";
+
+    #[test]
+    fn gaussian_accepts_well_formed_input() {
+        assert!(check(Software::Gaussian, GAUSSIAN_OK).is_empty());
+        assert!(check(Software::Gaussian, "# opt geom=allcheck\n\n").is_empty());
+    }
+
+    #[test]
+    fn gaussian_reports_structure_problems() {
+        assert_eq!(
+            messages(Software::Gaussian, &format!("\r\n{GAUSSIAN_OK}")),
+            ["I003 blank line 1 before the route section; Gaussian reads it as an empty route"]
+        );
+        assert_eq!(
+            messages(Software::Gaussian, GAUSSIAN_OK.trim_end()),
+            ["I003 input does not end with a blank line, which Gaussian needs after the last section"]
+        );
+        assert_eq!(
+            messages(Software::Gaussian, "%nproc=4\nopt\n\ntitle\n\n0 1\n"),
+            ["I003 no route section (a line starting with `#`)"]
+        );
+        assert_eq!(
+            messages(Software::Gaussian, "# opt\n\n\n0 1\nH 0 0 0\n\n"),
+            ["I003 missing title section after the route section"]
+        );
+        assert_eq!(
+            messages(Software::Gaussian, "# opt\n\ntitle\n\n 1\nH 0 0 0\n\n"),
+            ["I003 expected charge and multiplicity on line 5, found `1`"]
+        );
+    }
+
+    #[test]
+    fn xtb_reports_missing_and_early_end() {
+        assert!(check(Software::Xtb, "$constrain\n force constant=0.5\n$end\n").is_empty());
+        assert!(check(Software::Xtb, "no groups here\n").is_empty());
+        assert_eq!(
+            messages(Software::Xtb, "$constrain\n force constant=0.5\n"),
+            ["I004 detailed input does not end with `$end`"]
+        );
+        assert_eq!(
+            messages(Software::Xtb, "$md\n$end\n$fix\n atoms: 1\n"),
+            ["I004 `$fix` on line 3 comes after `$end` on line 2 and is ignored"]
+        );
+    }
+
+    #[test]
+    fn crest_requires_valid_toml() {
+        assert!(check(Software::Crest, "threads=4\n[calculation]\n").is_empty());
+        let msgs = messages(Software::Crest, "threads=\n");
+        assert_eq!(msgs.len(), 1);
+        assert!(
+            msgs[0].starts_with("I005 input is not valid TOML"),
+            "{msgs:?}"
+        );
+    }
+
+    #[test]
+    fn other_programs_are_not_checked() {
+        assert!(check(Software::Nwchem, "garbage").is_empty());
+    }
+}
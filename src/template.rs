@@ -1,12 +1,17 @@
 use crate::config::Config;
 use crate::elements::Element;
+use crate::knowledge::{self, KeywordKind};
+use crate::lint;
 use crate::molecule::Atom;
+use crate::params::{self, ParamSpec};
+use crate::software::Software;
+use crate::units::{EnergyUnit, LengthUnit, Memory, MemoryUnit};
 use crate::Molecule;
 use color_eyre::eyre::{bail, Report as Error, Result, WrapErr};
 use comfy_table::{presets, Table};
 use log::debug;
 use serde_json::value::{from_value, to_value, Value};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::{copy, read_dir, read_to_string};
 use std::path::{Path, PathBuf};
 use tera::Tera;
@@ -15,6 +20,50 @@ use walkdir::WalkDir;
 const PRESETS_DIR: &str = "presets";
 const TEMPLATES_DIR: &str = "templates";
 
+/// Context keys [`Template::render_with_molecule`] adds.
+pub const MOLECULE_KEYS: [&str; 2] = ["name", "Molecule"];
+
+type TeraFunction = fn(&HashMap<String, Value>) -> Result<Value, tera::Error>;
+type TeraFilter = fn(&Value, &HashMap<String, Value>) -> Result<Value, tera::Error>;
+
+/// Functions gedent registers on top of Tera's built-ins.
+const FUNCTIONS: &[(&str, TeraFunction)] = &[
+    ("print_coords", print_coords),
+    ("natoms", natoms),
+    ("count_element", count_element),
+    ("element_list", element_list),
+    ("atom_symbol", atom_symbol),
+    ("atom_coords", atom_coords),
+    ("measure", measure),
+];
+
+/// Filters gedent registers on top of Tera's built-ins.
+const FILTERS: &[(&str, TeraFilter)] = &[
+    ("to_bohr", to_bohr),
+    ("to_angstrom", to_angstrom),
+    ("to_hartree", to_hartree),
+    ("to_kcal", to_kcal),
+    ("to_kj", to_kj),
+    ("to_ev", to_ev),
+];
+
+/// Filters registered per render because they capture the template's
+/// software or the context.
+const STATEFUL_FILTERS: [&str; 2] = ["for_software", "mem_as"];
+
+/// Names of the functions gedent adds to Tera.
+pub fn function_names() -> impl Iterator<Item = &'static str> {
+    FUNCTIONS.iter().map(|(name, _)| *name)
+}
+
+/// Names of the filters gedent adds to Tera.
+pub fn filter_names() -> impl Iterator<Item = &'static str> {
+    FILTERS
+        .iter()
+        .map(|(name, _)| *name)
+        .chain(STATEFUL_FILTERS)
+}
+
 /// Metadata parsed from a template's frontmatter comment block.
 ///
 /// Frontmatter is a Tera comment at the top of the template file:
@@ -23,23 +72,56 @@ const TEMPLATES_DIR: &str = "templates";
 /// software = "orca"
 /// jobtype  = "sp"
 /// requires = ["method", "basis_set", "charge", "mult", "nprocs", "mem", "Molecule"]
+/// optional = ["maxiter"]
 /// description = "Single point energy"
+///
+/// [params.nimages]
+/// type = "int"
+/// default = 12
 /// #}
 /// ```
 #[derive(Clone, Debug, Default)]
 pub struct TemplateMeta {
     /// Software the template targets (e.g. `"orca"`). Used for disambiguation
-    /// when multiple templates share a short name.
-    #[allow(dead_code)] // reserved for method/software compatibility checks (item 19)
+    /// when multiple templates share a short name, and to check model keywords
+    /// against what the program understands.
     pub software: Option<String>,
-    /// Job type (e.g. `"sp"`, `"opt"`). Reserved for the workflow layer.
-    #[allow(dead_code)] // reserved for workflow layer (item 20)
+    /// Job type (e.g. `"sp"`, `"opt"`). A `"zora"` job counts as requesting a
+    /// relativistic Hamiltonian during basis set checks, and workflows use it
+    /// to find the files a step leaves for the next.
     pub jobtype: Option<String>,
     /// Context variables that must be present before rendering. gedent reports
     /// a clear error listing any that are missing.
     pub requires: Vec<String>,
+    /// Context variables the template uses only when set (guarded with
+    /// `is defined` or `default`), e.g. `maxiter`. Declaring them keeps
+    /// `gedent template lint` quiet.
+    pub optional: Vec<String>,
     /// Human-readable description shown in `gedent template list`.
     pub description: Option<String>,
+    /// Typed schemas from the `[params]` table, checked before rendering.
+    /// Required parameters are also listed in `requires`, the others in
+    /// `optional`.
+    pub params: BTreeMap<String, ParamSpec>,
+    /// Context variables the body pipes through `for_software`. Derived from
+    /// the body rather than the frontmatter; validation checks their
+    /// translated spelling instead of the raw value.
+    pub translated: Vec<String>,
+    /// Context variables the body reads, declared or not. Derived from the
+    /// body; validation uses it to explain keys gedent could not derive.
+    pub reads: Vec<String>,
+}
+
+impl TemplateMeta {
+    /// Whether the template declares or translates context variable `key`,
+    /// i.e. whether its value can end up in the input.
+    pub fn uses(&self, key: &str) -> bool {
+        self.requires
+            .iter()
+            .chain(&self.optional)
+            .chain(&self.translated)
+            .any(|k| k == key)
+    }
 }
 
 /// A loaded template ready for rendering.
@@ -49,6 +131,8 @@ pub struct Template {
     pub name: String,
     /// Parsed frontmatter.
     pub meta: TemplateMeta,
+    /// File the template was loaded from.
+    pub path: PathBuf,
     /// Raw template body (Tera source).
     body: String,
 }
@@ -83,13 +167,23 @@ impl Template {
 
     pub fn render(&self, context: &tera::Context) -> Result<String, Error> {
         let mut tera = Tera::default();
-        tera.register_function("print_coords", print_coords);
-        tera.register_function("natoms", natoms);
-        tera.register_function("count_element", count_element);
-        tera.register_function("element_list", element_list);
-        tera.register_function("atom_symbol", atom_symbol);
-        tera.register_function("atom_coords", atom_coords);
-        tera.register_function("measure", measure);
+        for (name, function) in FUNCTIONS {
+            tera.register_function(name, *function);
+        }
+        for (name, filter) in FILTERS {
+            tera.register_filter(name, *filter);
+        }
+        let software = self.meta.software.as_deref().and_then(Software::from_name);
+        tera.register_filter(
+            "for_software",
+            move |value: &Value, args: &HashMap<String, Value>| for_software(value, args, software),
+        );
+        // `mem_as(total=true)` needs nprocs, which filters can't see, so capture it here.
+        let nprocs = context.get("nprocs").and_then(Value::as_i64);
+        tera.register_filter(
+            "mem_as",
+            move |value: &Value, args: &HashMap<String, Value>| mem_as(value, args, nprocs),
+        );
         tera.add_raw_template(&self.name, &self.body)?;
         Ok(tera.render(&self.name, context)?)
     }
@@ -104,12 +198,18 @@ impl Template {
         molecule: &Molecule,
         stem: &str,
     ) -> Result<String, Error> {
+        let [name_key, molecule_key] = MOLECULE_KEYS;
         let mut ctx = context.clone();
-        ctx.insert("name", stem);
-        ctx.insert("Molecule", molecule);
+        ctx.insert(name_key, stem);
+        ctx.insert(molecule_key, molecule);
         self.render(&ctx)
     }
 
+    /// Raw template body (Tera source, frontmatter included).
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
     pub fn get_templates(templates_home: &Path) -> Vec<String> {
         let home_len = templates_home.to_string_lossy().len();
         WalkDir::new(templates_home)
@@ -127,16 +227,43 @@ impl Template {
     /// is used as a tiebreaker.
     pub fn get(template_name: String, software: Option<&str>) -> Result<Self, Error> {
         let path = Self::find_path(&template_name, software)?;
+        Self::from_file(template_name, path)
+    }
+
+    /// Load the template at `path`, known as `template_name`.
+    pub fn from_file(template_name: String, path: PathBuf) -> Result<Self, Error> {
         let body =
             read_to_string(&path).wrap_err(format!("Can't read template {template_name}"))?;
-        let meta = parse_frontmatter(&body);
+        Self::from_source(template_name, path, body)
+    }
+
+    /// The template `template_name` with source `body`, as if read from
+    /// `path`.
+    pub fn from_source(template_name: String, path: PathBuf, body: String) -> Result<Self, Error> {
+        let meta = parse_frontmatter(&body).wrap_err(format!(
+            "Can't parse frontmatter of template {template_name}"
+        ))?;
         Ok(Self {
             name: template_name,
             meta,
+            path,
             body,
         })
     }
 
+    /// Load every installed template, sorted by full name (`orca/sp`).
+    pub fn all() -> Result<Vec<Self>, Error> {
+        let templates_home: PathBuf = [Config::gedent_home()?, Into::into(TEMPLATES_DIR)]
+            .iter()
+            .collect();
+        let mut names = Self::get_templates(&templates_home);
+        names.sort();
+        names
+            .into_iter()
+            .map(|name| Self::get(name, None))
+            .collect()
+    }
+
     pub fn print_template(template: &str, software: Option<&str>) -> Result<(), Error> {
         let template_path = Self::find_path(template, software)?;
         let body = read_to_string(&template_path)
@@ -145,6 +272,59 @@ impl Template {
         Ok(())
     }
 
+    /// Print a template's frontmatter: description, variables and the
+    /// `[params]` schema.
+    pub fn print_info(template: &str, software: Option<&str>) -> Result<(), Error> {
+        let template = Self::get(template.to_string(), software)?;
+        let meta = &template.meta;
+        println!("{}", template.path.display());
+        if let Some(description) = &meta.description {
+            println!("{description}");
+        }
+        println!();
+        let mut table = Table::new();
+        table.load_preset(presets::NOTHING);
+        for (key, value) in [
+            ("software", meta.software.clone().unwrap_or_default()),
+            ("jobtype", meta.jobtype.clone().unwrap_or_default()),
+            ("requires", meta.requires.join(", ")),
+            ("optional", meta.optional.join(", ")),
+        ] {
+            table.add_row(vec![key.to_string(), value]);
+        }
+        println!("{table}");
+        if meta.params.is_empty() {
+            return Ok(());
+        }
+
+        println!("\nParameters:");
+        let mut table = Table::new();
+        table.load_preset(presets::NOTHING);
+        table.set_header(vec![
+            "  name",
+            "type",
+            "default",
+            "constraints",
+            "description",
+        ]);
+        for (name, spec) in &meta.params {
+            let default = match &spec.default {
+                Some(value) => value.to_string(),
+                None if spec.is_required() => "(required)".to_string(),
+                None => String::new(),
+            };
+            table.add_row(vec![
+                format!("  {name}"),
+                spec.type_label(),
+                default,
+                spec.constraint_label(),
+                spec.description.clone().unwrap_or_default(),
+            ]);
+        }
+        println!("{table}");
+        Ok(())
+    }
+
     pub fn edit_template(template: &str, software: Option<&str>) -> Result<(), Error> {
         let template_path = Self::find_path(template, software)?;
         edit::edit_file(template_path)?;
@@ -167,7 +347,7 @@ impl Template {
             );
             let desc = read_to_string(templates_home.join(t))
                 .ok()
-                .and_then(|body| parse_frontmatter(&body).description)
+                .and_then(|body| parse_frontmatter(&body).ok()?.description)
                 .unwrap_or_default();
             match groups.last_mut() {
                 Some((g_sw, entries)) if *g_sw == sw => entries.push((name, desc)),
@@ -297,6 +477,7 @@ impl Template {
         Self {
             name: String::new(),
             meta: TemplateMeta::default(),
+            path: PathBuf::new(),
             body: String::new(),
         }
     }
@@ -306,27 +487,55 @@ impl Template {
         Self {
             name: name.to_string(),
             meta: TemplateMeta::default(),
+            path: PathBuf::from(name),
             body: body.to_string(),
         }
     }
 }
 
-fn parse_frontmatter(body: &str) -> TemplateMeta {
+/// Parse the frontmatter of `body` and find the variables it reads and
+/// translates.
+///
+/// A missing or non-TOML comment block yields empty metadata, but a
+/// malformed `[params]` schema is an error: ignoring it would silently drop
+/// its defaults and checks.
+pub fn parse_frontmatter(body: &str) -> Result<TemplateMeta, Error> {
+    Ok(TemplateMeta {
+        translated: lint::translated_vars(body),
+        reads: lint::read_vars(body),
+        ..parse_header(body)?
+    })
+}
+
+fn parse_header(body: &str) -> Result<TemplateMeta, Error> {
     let Some(start) = body.find("{#") else {
-        return TemplateMeta::default();
+        return Ok(TemplateMeta::default());
     };
     let inner_start = start + 2;
     let Some(end_offset) = body[inner_start..].find("#}") else {
-        return TemplateMeta::default();
+        return Ok(TemplateMeta::default());
     };
     let end = inner_start + end_offset;
     // Strip optional `-` from whitespace-trim comment syntax ({#- ... -#})
     let raw = body[inner_start..end].trim_matches('-').trim();
     let table: toml::Table = match toml::from_str(raw) {
         Ok(t) => t,
-        Err(_) => return TemplateMeta::default(),
+        Err(_) => return Ok(TemplateMeta::default()),
     };
-    TemplateMeta {
+    let params = params::parse(&table)?;
+    let mut requires = string_list(&table, "requires");
+    let mut optional = string_list(&table, "optional");
+    for (name, spec) in &params {
+        if requires.contains(name) || optional.contains(name) {
+            continue;
+        }
+        if spec.is_required() {
+            requires.push(name.clone());
+        } else {
+            optional.push(name.clone());
+        }
+    }
+    Ok(TemplateMeta {
         software: table
             .get("software")
             .and_then(|v| v.as_str())
@@ -335,21 +544,29 @@ fn parse_frontmatter(body: &str) -> TemplateMeta {
             .get("jobtype")
             .and_then(|v| v.as_str())
             .map(str::to_string),
-        requires: table
-            .get("requires")
-            .and_then(|v| v.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|v| v.as_str())
-                    .map(str::to_string)
-                    .collect()
-            })
-            .unwrap_or_default(),
+        requires,
+        optional,
         description: table
             .get("description")
             .and_then(|v| v.as_str())
             .map(str::to_string),
-    }
+        params,
+        translated: vec![],
+        reads: vec![],
+    })
+}
+
+fn string_list(table: &toml::Table, key: &str) -> Vec<String> {
+    table
+        .get(key)
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 // ── Tera function helpers ─────────────────────────────────────────────────────
@@ -501,6 +718,192 @@ fn measure(args: &HashMap<String, Value>) -> Result<Value, tera::Error> {
     }
 }
 
+// ── Tera filters ──────────────────────────────────────────────────────────────
+
+/// Apply `f` to a number or to every number in an array of numbers.
+fn map_numbers(value: &Value, fn_name: &str, f: &dyn Fn(f64) -> f64) -> Result<Value, tera::Error> {
+    match value {
+        Value::Array(items) => Ok(Value::Array(
+            items
+                .iter()
+                .map(|v| map_numbers(v, fn_name, f))
+                .collect::<Result<_, _>>()?,
+        )),
+        v => {
+            let x = v.as_f64().ok_or_else(|| {
+                tera::Error::msg(format!(
+                    "{fn_name}: expected a number or array of numbers, got {v}"
+                ))
+            })?;
+            Ok(to_value(f(x))?)
+        }
+    }
+}
+
+/// Read an optional string `arg` and parse it into a unit, falling back to `default`.
+fn unit_arg<U: std::str::FromStr>(
+    args: &HashMap<String, Value>,
+    arg: &str,
+    default: U,
+    fn_name: &str,
+) -> Result<U, tera::Error> {
+    args.get(arg).map_or(Ok(default), |v| {
+        let s = v
+            .as_str()
+            .ok_or_else(|| tera::Error::msg(format!("{fn_name}: `{arg}` must be a string")))?;
+        s.parse()
+            .map_err(|_| tera::Error::msg(format!("{fn_name}: unknown unit {s:?}")))
+    })
+}
+
+fn convert_length(
+    value: &Value,
+    args: &HashMap<String, Value>,
+    default_from: LengthUnit,
+    to: LengthUnit,
+    fn_name: &str,
+) -> Result<Value, tera::Error> {
+    let from = unit_arg(args, "from", default_from, fn_name)?;
+    map_numbers(value, fn_name, &|x| from.convert(x, to))
+}
+
+fn convert_energy(
+    value: &Value,
+    args: &HashMap<String, Value>,
+    default_from: EnergyUnit,
+    to: EnergyUnit,
+    fn_name: &str,
+) -> Result<Value, tera::Error> {
+    let from = unit_arg(args, "from", default_from, fn_name)?;
+    map_numbers(value, fn_name, &|x| from.convert(x, to))
+}
+
+fn to_bohr(value: &Value, args: &HashMap<String, Value>) -> Result<Value, tera::Error> {
+    convert_length(
+        value,
+        args,
+        LengthUnit::Angstrom,
+        LengthUnit::Bohr,
+        "to_bohr",
+    )
+}
+
+fn to_angstrom(value: &Value, args: &HashMap<String, Value>) -> Result<Value, tera::Error> {
+    convert_length(
+        value,
+        args,
+        LengthUnit::Bohr,
+        LengthUnit::Angstrom,
+        "to_angstrom",
+    )
+}
+
+fn to_hartree(value: &Value, args: &HashMap<String, Value>) -> Result<Value, tera::Error> {
+    convert_energy(
+        value,
+        args,
+        EnergyUnit::KcalMol,
+        EnergyUnit::Hartree,
+        "to_hartree",
+    )
+}
+
+fn to_kcal(value: &Value, args: &HashMap<String, Value>) -> Result<Value, tera::Error> {
+    convert_energy(
+        value,
+        args,
+        EnergyUnit::Hartree,
+        EnergyUnit::KcalMol,
+        "to_kcal",
+    )
+}
+
+fn to_kj(value: &Value, args: &HashMap<String, Value>) -> Result<Value, tera::Error> {
+    convert_energy(value, args, EnergyUnit::Hartree, EnergyUnit::KjMol, "to_kj")
+}
+
+fn to_ev(value: &Value, args: &HashMap<String, Value>) -> Result<Value, tera::Error> {
+    convert_energy(value, args, EnergyUnit::Hartree, EnergyUnit::Ev, "to_ev")
+}
+
+/// `{{ mem | mem_as(unit="GB", total=true) }}` — convert a memory value (MB
+/// integer or unit string) to a whole number of `unit`. With `total=true` the
+/// per-core value is multiplied by `nprocs` from the render context.
+fn mem_as(
+    value: &Value,
+    args: &HashMap<String, Value>,
+    nprocs: Option<i64>,
+) -> Result<Value, tera::Error> {
+    let mem = match value {
+        Value::String(s) => s.parse::<Memory>().map_err(tera::Error::msg)?,
+        v => Memory::from_mb(v.as_i64().ok_or_else(|| {
+            tera::Error::msg(format!(
+                "mem_as: expected an integer (MB) or a string like \"4GB\", got {v}"
+            ))
+        })?),
+    };
+    let unit = unit_arg(args, "unit", MemoryUnit::MB, "mem_as")?;
+    let total = args.get("total").and_then(Value::as_bool).unwrap_or(false);
+    let factor = if total {
+        nprocs.ok_or_else(|| {
+            tera::Error::msg("mem_as: total=true requires `nprocs` to be defined in context")
+        })?
+    } else {
+        1
+    };
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    let amount = (mem.value_in(unit) * factor as f64).floor() as i64;
+    if amount == 0 && mem.as_mb() > 0 {
+        return Err(tera::Error::msg(format!(
+            "mem_as: {mem} is less than 1 {unit}; use a smaller unit"
+        )));
+    }
+    Ok(to_value(amount)?)
+}
+
+/// `{{ basis_set | for_software }}` — spell a model keyword the way the
+/// template's software expects (`d3bj` → `EmpiricalDispersion=GD3BJ` for
+/// Gaussian). `software=` overrides the target and `kind=` (`method`,
+/// `basis_set`, `dispersion`, `solvation_model`) restricts the lookup. Unknown
+/// keywords pass through unchanged.
+fn for_software(
+    value: &Value,
+    args: &HashMap<String, Value>,
+    software: Option<Software>,
+) -> Result<Value, tera::Error> {
+    let Value::String(keyword) = value else {
+        return Ok(value.clone());
+    };
+    let software = match args.get("software") {
+        Some(v) => {
+            let name = v
+                .as_str()
+                .ok_or_else(|| tera::Error::msg("for_software: `software` must be a string"))?;
+            Some(Software::from_name(name).ok_or_else(|| {
+                tera::Error::msg(format!("for_software: unknown software {name:?}"))
+            })?)
+        }
+        None => software,
+    };
+    let kind = match args.get("kind") {
+        Some(v) => Some(
+            v.as_str()
+                .and_then(|k| k.parse::<KeywordKind>().ok())
+                .ok_or_else(|| {
+                    tera::Error::msg(format!(
+                        "for_software: `kind` must be one of method, basis_set, \
+                         dispersion, solvation_model or solvent, got {v}"
+                    ))
+                })?,
+        ),
+        None => None,
+    };
+    let translated = software.and_then(|sw| knowledge::translate(keyword, sw, kind));
+    Ok(Value::String(
+        translated.map_or_else(|| keyword.clone(), str::to_string),
+    ))
+}
+
 // ── Geometry primitives ───────────────────────────────────────────────────────
 
 fn vec3(a: &Atom, b: &Atom) -> [f64; 3] {
@@ -703,11 +1106,12 @@ end
 
     #[test]
     fn parse_frontmatter_works() {
-        let body = "{#\nsoftware = \"orca\"\njobtype = \"sp\"\nrequires = [\"method\", \"basis_set\"]\ndescription = \"Single point\"\n#}\n! {{ method }}";
-        let meta = parse_frontmatter(body);
+        let body = "{#\nsoftware = \"orca\"\njobtype = \"sp\"\nrequires = [\"method\", \"basis_set\"]\noptional = [\"maxiter\"]\ndescription = \"Single point\"\n#}\n! {{ method }}";
+        let meta = parse_frontmatter(body).unwrap();
         assert_eq!(meta.software.as_deref(), Some("orca"));
         assert_eq!(meta.jobtype.as_deref(), Some("sp"));
         assert_eq!(meta.requires, vec!["method", "basis_set"]);
+        assert_eq!(meta.optional, vec!["maxiter"]);
         assert_eq!(meta.description.as_deref(), Some("Single point"));
     }
 
@@ -715,7 +1119,7 @@ end
     fn parse_frontmatter_whitespace_trim_syntax() {
         // {#- ... -#} is valid Tera whitespace-trimming comment syntax
         let body = "{#-\nsoftware = \"orca\"\njobtype = \"sp\"\nrequires = [\"method\"]\ndescription = \"Single point\"\n-#}\n! {{ method }}";
-        let meta = parse_frontmatter(body);
+        let meta = parse_frontmatter(body).unwrap();
         assert_eq!(meta.software.as_deref(), Some("orca"));
         assert_eq!(meta.description.as_deref(), Some("Single point"));
     }
@@ -723,11 +1127,27 @@ end
     #[test]
     fn parse_frontmatter_missing_returns_default() {
         let body = "! {{ method }} {{ basis_set }}";
-        let meta = parse_frontmatter(body);
+        let meta = parse_frontmatter(body).unwrap();
         assert!(meta.software.is_none());
         assert!(meta.requires.is_empty());
     }
 
+    #[test]
+    fn parse_frontmatter_merges_params_into_requires_and_optional() {
+        let body = "{#\nrequires = [\"method\"]\n[params.scan_steps]\ntype = \"int\"\n[params.nimages]\ntype = \"int\"\ndefault = 12\n#}";
+        let meta = parse_frontmatter(body).unwrap();
+        assert_eq!(meta.requires, vec!["method", "scan_steps"]);
+        assert_eq!(meta.optional, vec!["nimages"]);
+        assert_eq!(meta.params.len(), 2);
+    }
+
+    #[test]
+    fn parse_frontmatter_rejects_bad_params() {
+        let body = "{#\n[params.nimages]\ntype = \"int\"\ndefault = \"twelve\"\n#}";
+        let err = format!("{:#}", parse_frontmatter(body).unwrap_err());
+        assert!(err.contains("`nimages`"), "{err}");
+    }
+
     // ── natoms ────────────────────────────────────────────────────────────────
 
     #[test]
@@ -981,6 +1401,168 @@ end
         assert!(err.contains("out of range"));
     }
 
+    // ── unit filters ──────────────────────────────────────────────────────────
+
+    fn render_ctx(body: &str, context: &tera::Context) -> Result<String, Error> {
+        Template::with_body("t", body).render(context)
+    }
+
+    #[test]
+    fn to_bohr_converts_scalars_and_arrays() {
+        let out = render_ctx(
+            "{{ 1.0 | to_bohr | round(precision=4) }}",
+            &tera::Context::new(),
+        );
+        assert_eq!(out.unwrap(), "1.8897");
+        let out = render(
+            "{{ atom_coords(molecule=Molecule, i=1) | to_bohr | first | round(precision=3) }}",
+            &geo_mol(),
+        );
+        assert_eq!(out, "1.89");
+    }
+
+    #[test]
+    fn to_angstrom_inverts_to_bohr() {
+        let out = render_ctx(
+            "{{ 2.5 | to_bohr | to_angstrom | round(precision=6) }}",
+            &tera::Context::new(),
+        );
+        assert_eq!(out.unwrap(), "2.5");
+    }
+
+    #[test]
+    fn to_kcal_from_hartree_and_explicit_unit() {
+        let ctx = tera::Context::new();
+        assert_eq!(
+            render_ctx("{{ 0.01 | to_kcal | round(precision=3) }}", &ctx).unwrap(),
+            "6.275"
+        );
+        assert_eq!(
+            render_ctx(
+                "{{ 4.184 | to_kcal(from=\"kJ/mol\") | round(precision=3) }}",
+                &ctx
+            )
+            .unwrap(),
+            "1"
+        );
+    }
+
+    #[test]
+    fn unit_filter_rejects_unknown_unit() {
+        let err = render_ctx(
+            "{{ 1.0 | to_kcal(from=\"furlong\") }}",
+            &tera::Context::new(),
+        );
+        assert!(format!("{:?}", err.unwrap_err()).contains("unknown unit"));
+    }
+
+    #[test]
+    fn mem_as_converts_per_core_and_total() {
+        let mut ctx = tera::Context::new();
+        ctx.insert("mem", &3072);
+        ctx.insert("nprocs", &4);
+        assert_eq!(
+            render_ctx("{{ mem | mem_as(unit=\"GB\") }}", &ctx).unwrap(),
+            "3"
+        );
+        assert_eq!(
+            render_ctx("{{ mem | mem_as(unit=\"GB\", total=true) }}", &ctx).unwrap(),
+            "12"
+        );
+        assert_eq!(
+            render_ctx("{{ \"1GB\" | mem_as(unit=\"MW\") }}", &ctx).unwrap(),
+            "128"
+        );
+    }
+
+    #[test]
+    fn mem_as_total_without_nprocs_errors() {
+        let mut ctx = tera::Context::new();
+        ctx.insert("mem", &1000);
+        let err = render_ctx("{{ mem | mem_as(total=true) }}", &ctx).unwrap_err();
+        assert!(format!("{err:?}").contains("nprocs"));
+    }
+
+    #[test]
+    fn mem_as_refuses_to_round_to_zero() {
+        let mut ctx = tera::Context::new();
+        ctx.insert("mem", &500);
+        let err = render_ctx("{{ mem | mem_as(unit=\"GB\") }}", &ctx).unwrap_err();
+        assert!(format!("{err:?}").contains("smaller unit"));
+    }
+
+    // ── for_software ──────────────────────────────────────────────────────────
+
+    fn render_for(software: &str, body: &str, pairs: &[(&str, &str)]) -> Result<String, Error> {
+        let mut template = Template::with_body("t", body);
+        template.meta.software = Some(software.to_string());
+        let mut ctx = tera::Context::new();
+        for (k, v) in pairs {
+            ctx.insert(*k, v);
+        }
+        template.render(&ctx)
+    }
+
+    #[test]
+    fn for_software_translates_to_template_software() {
+        let body = "{{ method | for_software }}/{{ basis_set | for_software }} {{ dispersion | for_software }}";
+        let pairs = [
+            ("method", "pbe0"),
+            ("basis_set", "def2-svp"),
+            ("dispersion", "d3bj"),
+        ];
+        assert_eq!(
+            render_for("gaussian", body, &pairs).unwrap(),
+            "PBE1PBE/Def2SVP EmpiricalDispersion=GD3BJ"
+        );
+        assert_eq!(
+            render_for("orca", body, &pairs).unwrap(),
+            "PBE0/def2-SVP D3BJ"
+        );
+    }
+
+    #[test]
+    fn for_software_passes_unknown_values_through() {
+        let out = render_for(
+            "orca",
+            "{{ method | for_software }}",
+            &[("method", "my-dft")],
+        );
+        assert_eq!(out.unwrap(), "my-dft");
+        let out = render_ctx("{{ 'pbe0' | for_software }}", &tera::Context::new());
+        assert_eq!(out.unwrap(), "pbe0");
+    }
+
+    #[test]
+    fn for_software_accepts_software_and_kind_args() {
+        let out = render_ctx(
+            "{{ 'pbe0' | for_software(software='adf', kind='method') }}",
+            &tera::Context::new(),
+        );
+        assert_eq!(out.unwrap(), "Hybrid PBE0");
+        let err = render_ctx(
+            "{{ 'pbe0' | for_software(kind='functional') }}",
+            &tera::Context::new(),
+        );
+        assert!(err.is_err());
+        let err = render_ctx(
+            "{{ 'pbe0' | for_software(software='molpro') }}",
+            &tera::Context::new(),
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn translated_vars_found_in_body() {
+        let body = include_str!("../templates/gaussian/opt");
+        let meta = parse_frontmatter(body).unwrap();
+        assert!(meta.translated.contains(&"solvation_model".to_string()));
+        assert!(parse_frontmatter("{{ method }}")
+            .unwrap()
+            .translated
+            .is_empty());
+    }
+
     // ── print_coords ──────────────────────────────────────────────────────────
 
     #[test]
@@ -0,0 +1,317 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Bohr radius in Å (CODATA 2018).
+pub const ANGSTROM_PER_BOHR: f64 = 0.529_177_210_903;
+/// kcal/mol per Hartree.
+pub const KCAL_PER_HARTREE: f64 = 627.509_474_063;
+/// kJ/mol per Hartree.
+pub const KJ_PER_HARTREE: f64 = 2_625.499_639_48;
+/// eV per Hartree.
+pub const EV_PER_HARTREE: f64 = 27.211_386_245_988;
+/// cm⁻¹ per Hartree.
+pub const WAVENUMBER_PER_HARTREE: f64 = 219_474.631_363_2;
+
+/// Unit of length accepted by the `to_bohr` / `to_angstrom` filters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(ascii_case_insensitive)]
+pub enum LengthUnit {
+    /// Ångström — the unit of `Molecule` coordinates.
+    #[strum(to_string = "angstrom", serialize = "ang", serialize = "a")]
+    Angstrom,
+    /// Bohr (atomic unit of length).
+    #[strum(to_string = "bohr", serialize = "au")]
+    Bohr,
+}
+
+impl LengthUnit {
+    /// Size of one unit in Å.
+    const fn in_angstrom(self) -> f64 {
+        match self {
+            Self::Angstrom => 1.0,
+            Self::Bohr => ANGSTROM_PER_BOHR,
+        }
+    }
+
+    /// Convert `value` from `self` to `to`.
+    pub fn convert(self, value: f64, to: Self) -> f64 {
+        value * self.in_angstrom() / to.in_angstrom()
+    }
+}
+
+/// Unit of energy accepted by the energy conversion filters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(ascii_case_insensitive)]
+pub enum EnergyUnit {
+    /// Hartree (atomic unit of energy) — what the programs print.
+    #[strum(to_string = "hartree", serialize = "eh", serialize = "au")]
+    Hartree,
+    /// kcal/mol.
+    #[strum(to_string = "kcal/mol", serialize = "kcal")]
+    KcalMol,
+    /// kJ/mol.
+    #[strum(to_string = "kJ/mol", serialize = "kj")]
+    KjMol,
+    /// Electronvolt.
+    #[strum(to_string = "eV")]
+    Ev,
+    /// Wavenumber (cm⁻¹).
+    #[strum(to_string = "cm-1", serialize = "wavenumber")]
+    Wavenumber,
+}
+
+impl EnergyUnit {
+    /// Number of units in one Hartree.
+    const fn per_hartree(self) -> f64 {
+        match self {
+            Self::Hartree => 1.0,
+            Self::KcalMol => KCAL_PER_HARTREE,
+            Self::KjMol => KJ_PER_HARTREE,
+            Self::Ev => EV_PER_HARTREE,
+            Self::Wavenumber => WAVENUMBER_PER_HARTREE,
+        }
+    }
+
+    /// Convert `value` from `self` to `to`.
+    pub fn convert(self, value: f64, to: Self) -> f64 {
+        value / self.per_hartree() * to.per_hartree()
+    }
+}
+
+/// Unit of memory. Binary multiples are used throughout (1 GB = 1024 MB) and
+/// a word is 8 bytes, matching the Gaussian and `NWChem` conventions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(ascii_case_insensitive)]
+pub enum MemoryUnit {
+    /// Megabytes — the unit of bare integers in `[resources] mem`.
+    #[strum(to_string = "MB", serialize = "M")]
+    MB,
+    /// Gigabytes.
+    #[strum(to_string = "GB", serialize = "G")]
+    GB,
+    /// Terabytes.
+    #[strum(to_string = "TB", serialize = "T")]
+    TB,
+    /// Words (8 bytes).
+    #[strum(to_string = "W", serialize = "words")]
+    W,
+    /// Megawords.
+    #[strum(to_string = "MW")]
+    MW,
+    /// Gigawords.
+    #[strum(to_string = "GW")]
+    GW,
+}
+
+impl MemoryUnit {
+    /// Size of one unit in MB.
+    const fn in_mb(self) -> f64 {
+        match self {
+            Self::MB => 1.0,
+            Self::GB => 1024.0,
+            Self::TB => 1024.0 * 1024.0,
+            Self::W => 8.0 / (1024.0 * 1024.0),
+            Self::MW => 8.0,
+            Self::GW => 8.0 * 1024.0,
+        }
+    }
+}
+
+/// An amount of memory together with the unit it was written in.
+///
+/// Parsed from `[resources] mem` and `--mem`: bare numbers are MB (the
+/// historical meaning), strings may carry a unit suffix (`"4GB"`, `"500 MW"`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Memory {
+    amount: f64,
+    unit: MemoryUnit,
+}
+
+impl Memory {
+    /// Memory of `mb` megabytes.
+    #[allow(clippy::cast_precision_loss)]
+    pub const fn from_mb(mb: i64) -> Self {
+        Self {
+            amount: mb as f64,
+            unit: MemoryUnit::MB,
+        }
+    }
+
+    /// Value in `unit`, unrounded.
+    pub fn value_in(self, unit: MemoryUnit) -> f64 {
+        self.amount * self.unit.in_mb() / unit.in_mb()
+    }
+
+    /// Value in whole MB (rounded), the unit exposed as `mem` in the Tera context.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn as_mb(self) -> i64 {
+        self.value_in(MemoryUnit::MB).round() as i64
+    }
+}
+
+impl FromStr for Memory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        // No unit starts with `e`, so exponents (`1e3`) belong to the number.
+        let split = s
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-')))
+            .unwrap_or(s.len());
+        let (number, unit) = s.split_at(split);
+        let amount: f64 = number
+            .parse()
+            .map_err(|_| format!("invalid memory amount {s:?}, expected e.g. 3000 or \"4GB\""))?;
+        if amount <= 0.0 || !amount.is_finite() {
+            return Err(format!("memory must be positive, got {s:?}"));
+        }
+        let unit = match unit.trim() {
+            "" => MemoryUnit::MB,
+            u => u.parse().map_err(|_| {
+                format!("unknown memory unit {u:?} in {s:?} (use MB, GB, TB, W, MW or GW)")
+            })?,
+        };
+        Ok(Self { amount, unit })
+    }
+}
+
+impl fmt::Display for Memory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.amount, self.unit)
+    }
+}
+
+impl Serialize for Memory {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // Keep plain MB integers as integers so existing configs round-trip unchanged.
+        #[allow(clippy::cast_possible_truncation, clippy::float_cmp)]
+        if self.unit == MemoryUnit::MB && self.amount.fract() == 0.0 {
+            serializer.serialize_i64(self.amount as i64)
+        } else {
+            serializer.serialize_str(&self.to_string())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Memory {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct MemoryVisitor;
+
+        impl serde::de::Visitor<'_> for MemoryVisitor {
+            type Value = Memory;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an integer number of MB or a string such as \"4GB\"")
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Memory, E> {
+                if v <= 0 {
+                    return Err(E::custom(format!("memory must be positive, got {v}")));
+                }
+                Ok(Memory::from_mb(v))
+            }
+
+            #[allow(clippy::cast_possible_wrap)]
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Memory, E> {
+                self.visit_i64(v as i64)
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Memory, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Memory, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(MemoryVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn length_roundtrip() {
+        let bohr = LengthUnit::Angstrom.convert(1.0, LengthUnit::Bohr);
+        approx::assert_relative_eq!(bohr, 1.889_726_124_6, epsilon = 1e-9);
+        approx::assert_relative_eq!(LengthUnit::Bohr.convert(bohr, LengthUnit::Angstrom), 1.0);
+    }
+
+    #[test]
+    fn energy_conversions() {
+        approx::assert_relative_eq!(
+            EnergyUnit::Hartree.convert(1.0, EnergyUnit::KcalMol),
+            KCAL_PER_HARTREE
+        );
+        approx::assert_relative_eq!(
+            EnergyUnit::KcalMol.convert(KCAL_PER_HARTREE, EnergyUnit::Ev),
+            EV_PER_HARTREE,
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn unit_names_parse_case_insensitive() {
+        assert_eq!("Bohr".parse::<LengthUnit>(), Ok(LengthUnit::Bohr));
+        assert_eq!("kcal/mol".parse::<EnergyUnit>(), Ok(EnergyUnit::KcalMol));
+        assert_eq!("gb".parse::<MemoryUnit>(), Ok(MemoryUnit::GB));
+    }
+
+    #[test]
+    fn memory_parses_bare_number_as_mb() {
+        let mem: Memory = "3000".parse().unwrap();
+        assert_eq!(mem.as_mb(), 3000);
+    }
+
+    #[test]
+    fn memory_parses_unit_suffix() {
+        assert_eq!("4GB".parse::<Memory>().unwrap().as_mb(), 4096);
+        assert_eq!("1.5 gb".parse::<Memory>().unwrap().as_mb(), 1536);
+        assert_eq!("100MW".parse::<Memory>().unwrap().as_mb(), 800);
+    }
+
+    #[test]
+    fn memory_parses_sign_and_exponent() {
+        assert_eq!("1e3".parse::<Memory>().unwrap().as_mb(), 1000);
+        assert_eq!("+2.5e-1 GB".parse::<Memory>().unwrap().as_mb(), 256);
+        for bad in ["0", "0GB", "-4GB", "-1e3"] {
+            assert!(bad.parse::<Memory>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn memory_rejects_unknown_unit() {
+        assert!("4 parsecs".parse::<Memory>().is_err());
+        assert!("GB".parse::<Memory>().is_err());
+    }
+
+    #[test]
+    fn memory_value_in_other_units() {
+        let mem = Memory::from_mb(2048);
+        approx::assert_relative_eq!(mem.value_in(MemoryUnit::GB), 2.0);
+        approx::assert_relative_eq!(mem.value_in(MemoryUnit::MW), 256.0);
+    }
+
+    #[test]
+    fn memory_toml_roundtrip_keeps_integers() {
+        #[derive(Serialize, Deserialize)]
+        struct T {
+            mem: Memory,
+        }
+        let t: T = toml::from_str("mem = 3000").unwrap();
+        assert_eq!(toml::to_string(&t).unwrap().trim(), "mem = 3000");
+        let t: T = toml::from_str("mem = \"4GB\"").unwrap();
+        assert_eq!(t.mem.as_mb(), 4096);
+        assert_eq!(toml::to_string(&t).unwrap().trim(), "mem = \"4GB\"");
+    }
+}
@@ -1,8 +1,20 @@
+use crate::config::ValidationConfig;
+use crate::knowledge::{self, KeywordKind};
 use crate::molecule::Molecule;
+use crate::params;
+use crate::resources::{self, Node};
+use crate::software::Software;
+use crate::solvents;
+use crate::template::TemplateMeta;
+use crate::units::Memory;
+use color_eyre::eyre::{bail, eyre, Result};
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::path::{Path, PathBuf};
 
 /// Severity of a validation [`Diagnostic`].
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Severity {
     /// Generation is aborted; all errors across all inputs are reported first.
     Error,
@@ -10,57 +22,497 @@ pub enum Severity {
     Warning,
 }
 
-/// A single validation finding with a severity and a human-readable message.
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        })
+    }
+}
+
+/// Every validation check, with a stable code for scripts to match on.
+///
+/// Codes are grouped by prefix: `G` geometry and electronic structure, `K`
+/// model keywords, `S` solvent, `B` basis set, `R` resources, `T` template
+/// (including `gedent template lint`), `I` syntax of the rendered input. Codes are never
+/// reused; retired checks keep their number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::EnumIter)]
+pub enum Check {
+    /// Electron count and multiplicity have incompatible parity.
+    ChargeMultParity,
+    /// Charge removes more electrons than the molecule has.
+    NegativeElectronCount,
+    /// Multiplicity below 1.
+    InvalidMultiplicity,
+    /// More unpaired electrons than electrons.
+    UnpairedExceedsElectrons,
+    /// Two atoms at (almost) the same position.
+    SuperposedAtoms,
+    /// Two atoms closer than half the sum of their covalent radii.
+    CloseContact,
+    /// More disconnected fragments than the project expects.
+    TooManyFragments,
+    /// Atom with no bonded neighbours.
+    IsolatedAtom,
+    /// Atom with more bonds than its usual valence.
+    AbnormalValence,
+    /// First-row transition metal run as a closed-shell singlet.
+    ClosedShellTransitionMetal,
+    /// More unpaired electrons than the open d/f shells plausibly provide.
+    HighMultiplicity,
+    /// Model keyword spelled for another program.
+    KeywordSpelling,
+    /// Model keyword the program does not provide.
+    KeywordUnsupported,
+    /// Basis set, dispersion or solvation model gedent doesn't know.
+    UnknownKeyword,
+    /// Solvation model the program does not implement.
+    SolvationModelUnsupported,
+    /// ALPB requested with a non-xtb method.
+    AlpbRequiresXtb,
+    /// xtb in ORCA combined with a solvation model other than ALPB.
+    XtbRequiresAlpb,
+    /// Basis set or dispersion set for a composite "3c" method.
+    CompositeMethodExtras,
+    /// Solvent name gedent doesn't know.
+    UnknownSolvent,
+    /// Solvent the program has no parameters for.
+    SolventUnsupported,
+    /// Solvent spelled for another program.
+    SolventSpelling,
+    /// Element the basis set has no functions for.
+    BasisElementCoverage,
+    /// Heavy element treated all-electron without a relativistic Hamiltonian.
+    HeavyElementAllElectron,
+    /// Relativistically contracted basis set without a relativistic Hamiltonian.
+    RelativisticBasisWithoutHamiltonian,
+    /// ECPs combined with a relativistic Hamiltonian.
+    EcpWithRelativisticHamiltonian,
+    /// Job needs more memory than the node has.
+    MemoryOversubscribed,
+    /// Job needs more cores than the node has.
+    CoresOversubscribed,
+    /// More processes than atoms for a method that parallelises poorly.
+    MoreProcsThanAtoms,
+    /// Variable listed in `requires` but missing from the context.
+    MissingVariable,
+    /// Template reads a variable nobody declares or provides.
+    UndeclaredVariable,
+    /// Frontmatter declares a variable the template never reads.
+    UnusedVariable,
+    /// Template calls a function that does not exist.
+    UnknownFunction,
+    /// Template applies a filter that does not exist.
+    UnknownFilter,
+    /// Template does not parse.
+    TemplateSyntax,
+    /// Parameter value does not match the template's `[params]` schema.
+    InvalidParameter,
+    /// ORCA `%` block not closed by `end`, or a stray `end`.
+    OrcaBlock,
+    /// ORCA coordinate section missing, malformed or not closed by `*`.
+    OrcaCoordinates,
+    /// Gaussian input sections out of order or not separated by blank lines.
+    GaussianStructure,
+    /// xtb detailed input groups after `$end`, or no `$end`.
+    XtbGroups,
+    /// CREST input is not valid TOML.
+    CrestToml,
+}
+
+impl Check {
+    /// Find a check by code (`"G006"`) or name (`"close-contact"`), ignoring case.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        <Self as strum::IntoEnumIterator>::iter()
+            .find(|c| c.code().eq_ignore_ascii_case(s) || c.name().eq_ignore_ascii_case(s))
+    }
+
+    /// Stable code, e.g. `"G001"`.
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::ChargeMultParity => "G001",
+            Self::NegativeElectronCount => "G002",
+            Self::InvalidMultiplicity => "G003",
+            Self::UnpairedExceedsElectrons => "G004",
+            Self::SuperposedAtoms => "G005",
+            Self::CloseContact => "G006",
+            Self::TooManyFragments => "G007",
+            Self::IsolatedAtom => "G008",
+            Self::AbnormalValence => "G009",
+            Self::ClosedShellTransitionMetal => "G010",
+            Self::HighMultiplicity => "G011",
+            Self::KeywordSpelling => "K001",
+            Self::KeywordUnsupported => "K002",
+            Self::UnknownKeyword => "K003",
+            Self::SolvationModelUnsupported => "K004",
+            Self::AlpbRequiresXtb => "K005",
+            Self::XtbRequiresAlpb => "K006",
+            Self::CompositeMethodExtras => "K007",
+            Self::UnknownSolvent => "S001",
+            Self::SolventUnsupported => "S002",
+            Self::SolventSpelling => "S003",
+            Self::BasisElementCoverage => "B001",
+            Self::HeavyElementAllElectron => "B002",
+            Self::RelativisticBasisWithoutHamiltonian => "B003",
+            Self::EcpWithRelativisticHamiltonian => "B004",
+            Self::MemoryOversubscribed => "R001",
+            Self::CoresOversubscribed => "R002",
+            Self::MoreProcsThanAtoms => "R003",
+            Self::MissingVariable => "T001",
+            Self::UndeclaredVariable => "T002",
+            Self::UnusedVariable => "T003",
+            Self::UnknownFunction => "T004",
+            Self::UnknownFilter => "T005",
+            Self::TemplateSyntax => "T006",
+            Self::InvalidParameter => "T007",
+            Self::OrcaBlock => "I001",
+            Self::OrcaCoordinates => "I002",
+            Self::GaussianStructure => "I003",
+            Self::XtbGroups => "I004",
+            Self::CrestToml => "I005",
+        }
+    }
+
+    /// Stable kebab-case name, e.g. `"charge-mult-parity"`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::ChargeMultParity => "charge-mult-parity",
+            Self::NegativeElectronCount => "negative-electron-count",
+            Self::InvalidMultiplicity => "invalid-multiplicity",
+            Self::UnpairedExceedsElectrons => "unpaired-exceeds-electrons",
+            Self::SuperposedAtoms => "superposed-atoms",
+            Self::CloseContact => "close-contact",
+            Self::TooManyFragments => "too-many-fragments",
+            Self::IsolatedAtom => "isolated-atom",
+            Self::AbnormalValence => "abnormal-valence",
+            Self::ClosedShellTransitionMetal => "closed-shell-transition-metal",
+            Self::HighMultiplicity => "high-multiplicity",
+            Self::KeywordSpelling => "keyword-spelling",
+            Self::KeywordUnsupported => "keyword-unsupported",
+            Self::UnknownKeyword => "unknown-keyword",
+            Self::SolvationModelUnsupported => "solvation-model-unsupported",
+            Self::AlpbRequiresXtb => "alpb-requires-xtb",
+            Self::XtbRequiresAlpb => "xtb-requires-alpb",
+            Self::CompositeMethodExtras => "composite-method-extras",
+            Self::UnknownSolvent => "unknown-solvent",
+            Self::SolventUnsupported => "solvent-unsupported",
+            Self::SolventSpelling => "solvent-spelling",
+            Self::BasisElementCoverage => "basis-element-coverage",
+            Self::HeavyElementAllElectron => "heavy-element-all-electron",
+            Self::RelativisticBasisWithoutHamiltonian => "relativistic-basis-without-hamiltonian",
+            Self::EcpWithRelativisticHamiltonian => "ecp-with-relativistic-hamiltonian",
+            Self::MemoryOversubscribed => "memory-oversubscribed",
+            Self::CoresOversubscribed => "cores-oversubscribed",
+            Self::MoreProcsThanAtoms => "more-procs-than-atoms",
+            Self::MissingVariable => "missing-variable",
+            Self::UndeclaredVariable => "undeclared-variable",
+            Self::UnusedVariable => "unused-variable",
+            Self::UnknownFunction => "unknown-function",
+            Self::UnknownFilter => "unknown-filter",
+            Self::TemplateSyntax => "template-syntax",
+            Self::InvalidParameter => "invalid-parameter",
+            Self::OrcaBlock => "orca-block",
+            Self::OrcaCoordinates => "orca-coordinates",
+            Self::GaussianStructure => "gaussian-structure",
+            Self::XtbGroups => "xtb-groups",
+            Self::CrestToml => "crest-toml",
+        }
+    }
+
+    /// One-line description of what the check looks for.
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::ChargeMultParity => "Electron count and multiplicity have incompatible parity",
+            Self::NegativeElectronCount => "Charge removes more electrons than the molecule has",
+            Self::InvalidMultiplicity => "Multiplicity must be at least 1",
+            Self::UnpairedExceedsElectrons => {
+                "Multiplicity needs more unpaired electrons than exist"
+            }
+            Self::SuperposedAtoms => "Two atoms are at the same position",
+            Self::CloseContact => "Two atoms are closer than half their covalent radii",
+            Self::TooManyFragments => "Structure has more disconnected fragments than expected",
+            Self::IsolatedAtom => "Atom has no bonded neighbours",
+            Self::AbnormalValence => "Atom has more bonds than its usual valence",
+            Self::ClosedShellTransitionMetal => {
+                "First-row transition metal complex is run as a closed-shell singlet"
+            }
+            Self::HighMultiplicity => {
+                "Multiplicity needs more unpaired electrons than the open shells provide"
+            }
+            Self::KeywordSpelling => "Model keyword is spelled for another program",
+            Self::KeywordUnsupported => "Model keyword is not available in the target program",
+            Self::UnknownKeyword => "Basis set, dispersion or solvation model is unknown",
+            Self::SolvationModelUnsupported => {
+                "Target program does not implement the solvation model"
+            }
+            Self::AlpbRequiresXtb => "ALPB solvation needs an xtb method",
+            Self::XtbRequiresAlpb => "xtb methods in ORCA need ALPB solvation",
+            Self::CompositeMethodExtras => {
+                "Composite method brings its own basis set and dispersion"
+            }
+            Self::UnknownSolvent => "Solvent is unknown",
+            Self::SolventUnsupported => "Target program has no parameters for the solvent",
+            Self::SolventSpelling => "Solvent is spelled for another program",
+            Self::BasisElementCoverage => "Basis set does not cover an element of the molecule",
+            Self::HeavyElementAllElectron => {
+                "Heavy element is all-electron without a relativistic Hamiltonian"
+            }
+            Self::RelativisticBasisWithoutHamiltonian => {
+                "Relativistic basis set used without a relativistic Hamiltonian"
+            }
+            Self::EcpWithRelativisticHamiltonian => {
+                "ECPs are combined with a relativistic Hamiltonian"
+            }
+            Self::MemoryOversubscribed => "Job needs more memory than the node has",
+            Self::CoresOversubscribed => "Job needs more cores than the node has",
+            Self::MoreProcsThanAtoms => {
+                "More processes than atoms for a method that parallelises poorly"
+            }
+            Self::MissingVariable => "Variable required by the template is not defined",
+            Self::UndeclaredVariable => {
+                "Template uses a variable that is neither declared nor provided"
+            }
+            Self::UnusedVariable => "Frontmatter declares a variable the template never uses",
+            Self::UnknownFunction => "Template calls an unknown function",
+            Self::UnknownFilter => "Template applies an unknown filter",
+            Self::TemplateSyntax => "Template does not parse",
+            Self::InvalidParameter => "Parameter value does not match the template's schema",
+            Self::OrcaBlock => "ORCA block is not closed by `end`",
+            Self::OrcaCoordinates => "ORCA coordinate section is missing or malformed",
+            Self::GaussianStructure => "Gaussian input sections are malformed",
+            Self::XtbGroups => "xtb detailed input groups are not terminated by `$end`",
+            Self::CrestToml => "CREST input is not valid TOML",
+        }
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.code(), self.name())
+    }
+}
+
+/// A single validation finding.
 #[derive(Debug)]
 pub struct Diagnostic {
+    /// Check that produced this finding.
+    pub check: Check,
     /// Whether this finding aborts generation or just warns.
     pub severity: Severity,
     /// Human-readable description of the problem.
     pub message: String,
+    /// File the finding refers to (molecule, template or generated input), if any.
+    pub path: Option<PathBuf>,
+    /// 1-based indices of the atoms involved, if any.
+    pub atoms: Vec<usize>,
 }
 
 impl Diagnostic {
-    /// Create an error-severity diagnostic.
-    pub fn error(message: impl Into<String>) -> Self {
+    fn new(check: Check, severity: Severity, message: impl Into<String>) -> Self {
         Self {
-            severity: Severity::Error,
+            check,
+            severity,
             message: message.into(),
+            path: None,
+            atoms: vec![],
         }
     }
 
+    /// Create an error-severity diagnostic.
+    pub fn error(check: Check, message: impl Into<String>) -> Self {
+        Self::new(check, Severity::Error, message)
+    }
+
     /// Create a warning-severity diagnostic.
-    pub fn warning(message: impl Into<String>) -> Self {
-        Self {
-            severity: Severity::Warning,
-            message: message.into(),
-        }
+    pub fn warning(check: Check, message: impl Into<String>) -> Self {
+        Self::new(check, Severity::Warning, message)
+    }
+
+    /// Attach the 1-based indices of the atoms involved.
+    #[must_use]
+    pub fn with_atoms(mut self, atoms: Vec<usize>) -> Self {
+        self.atoms = atoms;
+        self
+    }
+
+    /// Attach the file the finding refers to.
+    #[must_use]
+    pub fn with_path(mut self, path: &Path) -> Self {
+        self.path = Some(path.to_path_buf());
+        self
     }
 }
 
 impl fmt::Display for Diagnostic {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let prefix = match self.severity {
-            Severity::Error => "error",
-            Severity::Warning => "warning",
+        write!(
+            f,
+            "{}[{}]: {}",
+            self.severity,
+            self.check.code(),
+            self.message
+        )
+    }
+}
+
+/// Project policy for which checks run and how severe their findings are,
+/// resolved from the `[validation]` config section.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Policy {
+    /// Checks whose findings are dropped.
+    disabled: Vec<Check>,
+    /// Per-check severity overrides.
+    severities: Vec<(Check, Severity)>,
+    /// Distance in Å below which two atoms are superposed (error).
+    pub superposed_distance: f64,
+    /// Fraction of the sum of covalent radii below which two atoms are in
+    /// close contact (warning).
+    pub close_contact_factor: f64,
+    /// Multiple of the sum of covalent radii below which two atoms are bonded.
+    pub bond_factor: f64,
+    /// Disconnected fragments allowed before warning.
+    pub max_fragments: usize,
+    /// Promote every remaining warning to an error.
+    pub strict: bool,
+    /// Node that `[resources]` are checked against. Unlimited by default; set
+    /// from `[node]`, or the local machine with `local = true`, when generating.
+    pub node: Node,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            disabled: vec![],
+            severities: vec![],
+            superposed_distance: 0.5,
+            close_contact_factor: 0.5,
+            bond_factor: 1.2,
+            max_fragments: 1,
+            strict: false,
+            node: Node::default(),
+        }
+    }
+}
+
+impl Policy {
+    /// Resolve `config`, erroring on unknown check codes or names and on
+    /// non-positive thresholds.
+    pub fn from_config(config: &ValidationConfig) -> Result<Self> {
+        let check = |s: &str| {
+            Check::parse(s).ok_or_else(|| {
+                eyre!(
+                    "Unknown check {s:?} in [validation]; use a code such as \"G006\" \
+                     or a name such as \"close-contact\"."
+                )
+            })
         };
-        write!(f, "{prefix}: {}", self.message)
+        let disabled = config
+            .disable
+            .iter()
+            .flatten()
+            .map(|s| check(s))
+            .collect::<Result<_>>()?;
+        let severities = config
+            .severity
+            .iter()
+            .map(|(s, severity)| Ok((check(s)?, *severity)))
+            .collect::<Result<_>>()?;
+
+        let default = Self::default();
+        let superposed_distance = config
+            .superposed_distance
+            .unwrap_or(default.superposed_distance);
+        let close_contact_factor = config
+            .close_contact_factor
+            .unwrap_or(default.close_contact_factor);
+        let bond_factor = config.bond_factor.unwrap_or(default.bond_factor);
+        for (key, value) in [
+            ("superposed_distance", superposed_distance),
+            ("close_contact_factor", close_contact_factor),
+            ("bond_factor", bond_factor),
+        ] {
+            if value.is_nan() || value <= 0.0 {
+                bail!("[validation] {key} must be positive, got {value}.");
+            }
+        }
+        Ok(Self {
+            disabled,
+            severities,
+            superposed_distance,
+            close_contact_factor,
+            bond_factor,
+            max_fragments: config.max_fragments.unwrap_or(default.max_fragments),
+            strict: config.strict.unwrap_or(false),
+            node: default.node,
+        })
+    }
+
+    /// Whether findings of `check` are reported at all.
+    pub fn enabled(&self, check: Check) -> bool {
+        !self.disabled.contains(&check)
+    }
+
+    /// Drop disabled findings and regrade the rest: severity overrides first,
+    /// then `strict` promotes what is still a warning.
+    pub fn apply(&self, diags: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        diags
+            .into_iter()
+            .filter(|d| self.enabled(d.check))
+            .map(|mut d| {
+                if let Some((_, severity)) = self.severities.iter().find(|(c, _)| *c == d.check) {
+                    d.severity = *severity;
+                }
+                if self.strict {
+                    d.severity = Severity::Error;
+                }
+                d
+            })
+            .collect()
     }
 }
 
-/// Run all validation checks and return every finding as a [`Vec<Diagnostic>`].
+/// Run all validation checks and return every finding as a [`Vec<Diagnostic>`],
+/// filtered and graded by `policy`.
 ///
 /// Molecule-specific checks (charge/mult parity, superposed atoms) are skipped
-/// when `molecule` is `None`.
+/// when `molecule` is `None`; keyword checks are skipped when `meta.software`
+/// is not a program gedent has data for.
 pub fn validate(
     molecule: Option<&Molecule>,
     context: &tera::Context,
-    requires: &[String],
+    meta: &TemplateMeta,
+    policy: &Policy,
 ) -> Vec<Diagnostic> {
+    let requires = meta.requires.as_slice();
     let mut diags = vec![];
     if let Some(mol) = molecule {
-        diags.extend(check_superposed_atoms(mol));
-        diags.extend(check_charge_mult(mol, context));
+        diags.extend(check_superposed_atoms(
+            mol,
+            policy.superposed_distance,
+            policy.close_contact_factor,
+        ));
+        diags.extend(check_connectivity(
+            mol,
+            policy.bond_factor,
+            policy.max_fragments,
+        ));
+        let charge_mult = check_charge_mult(mol, context);
+        if charge_mult.is_empty() {
+            diags.extend(check_spin_state(mol, context));
+        }
+        diags.extend(charge_mult);
+        // Programs such as CREST never see `basis_set`.
+        if meta.uses("basis_set") {
+            diags.extend(check_basis_coverage(mol, context, meta));
+        }
     }
+    if let Some(software) = meta.software.as_deref().and_then(Software::from_name) {
+        diags.extend(check_keywords(context, software, meta));
+        diags.extend(check_solvent(context, software, &meta.translated));
+    }
+    diags.extend(check_resources(molecule, context, meta, &policy.node));
     // "Molecule" is injected per-render in render_with_molecule(), not into the
     // base context. Skip it from the missing-vars check when a molecule is provided.
     let filtered_requires: Vec<String>;
@@ -74,8 +526,9 @@ pub fn validate(
     } else {
         requires
     };
+    diags.extend(params::check(&meta.params, context));
     diags.extend(check_missing_vars(context, effective_requires));
-    diags
+    policy.apply(diags)
 }
 
 fn check_charge_mult(molecule: &Molecule, context: &tera::Context) -> Vec<Diagnostic> {
@@ -98,39 +551,165 @@ fn check_charge_mult(molecule: &Molecule, context: &tera::Context) -> Vec<Diagno
     let mut diags = vec![];
 
     if electrons < 0 {
-        diags.push(Diagnostic::error(format!(
-            "charge {charge} gives a negative electron count ({electrons})"
-        )));
+        diags.push(Diagnostic::error(
+            Check::NegativeElectronCount,
+            format!("charge {charge} gives a negative electron count ({electrons})"),
+        ));
         return diags;
     }
 
     if mult < 1 {
-        diags.push(Diagnostic::error(format!(
-            "multiplicity must be >= 1, got {mult}"
-        )));
+        diags.push(Diagnostic::error(
+            Check::InvalidMultiplicity,
+            format!("multiplicity must be >= 1, got {mult}"),
+        ));
         return diags;
     }
 
     let unpaired = mult - 1;
     if electrons < unpaired {
-        diags.push(Diagnostic::error(format!(
-            "multiplicity {mult} requires {unpaired} unpaired electrons \
+        diags.push(Diagnostic::error(
+            Check::UnpairedExceedsElectrons,
+            format!(
+                "multiplicity {mult} requires {unpaired} unpaired electrons \
              but molecule only has {electrons} electrons"
-        )));
+            ),
+        ));
     } else if (electrons - unpaired) % 2 != 0 {
-        diags.push(Diagnostic::error(format!(
-            "charge {charge} and multiplicity {mult} are inconsistent: \
-             {electrons} electrons cannot accommodate {unpaired} unpaired electrons"
-        )));
+        let suggested = consistent_multiplicities(electrons, plausible_unpaired(molecule));
+        diags.push(Diagnostic::error(
+            Check::ChargeMultParity,
+            format!(
+                "charge {charge} and multiplicity {mult} are inconsistent: \
+             {electrons} electrons cannot accommodate {unpaired} unpaired electrons \
+             (with charge {charge}, try multiplicity {})",
+                or_list(&suggested)
+            ),
+        ));
     }
 
     diags
 }
 
-fn check_superposed_atoms(molecule: &Molecule) -> Vec<Diagnostic> {
-    const ERROR_THRESHOLD: f64 = 0.5; // Å — definitely same point
-    const RADII_FACTOR: f64 = 0.5; // fraction of sum of covalent radii
+/// Unpaired electrons credited to main-group atoms on top of the open d/f
+/// shells: enough for a triplet such as O2 or a carbene.
+const MAIN_GROUP_UNPAIRED: i64 = 2;
+
+/// Words in the xyz comment line that acknowledge a low-spin metal.
+const LOW_SPIN_NOTES: &[&str] = &[
+    "singlet",
+    "low-spin",
+    "low spin",
+    "closed-shell",
+    "closed shell",
+];
+
+/// Most unpaired electrons the molecule plausibly has: a half-filled d or f
+/// shell per metal plus a main-group diradical.
+fn plausible_unpaired(molecule: &Molecule) -> i64 {
+    let shells: usize = molecule
+        .atoms
+        .iter()
+        .map(|a| a.element.max_unpaired())
+        .sum();
+    i64::try_from(shells).unwrap_or(i64::MAX) + MAIN_GROUP_UNPAIRED
+}
+
+/// Multiplicities with the parity of `electrons`, up to `max_unpaired`
+/// unpaired electrons.
+fn consistent_multiplicities(electrons: i64, max_unpaired: i64) -> Vec<i64> {
+    let max_unpaired = max_unpaired.min(electrons).max(electrons % 2);
+    (electrons % 2..=max_unpaired)
+        .step_by(2)
+        .map(|unpaired| unpaired + 1)
+        .collect()
+}
+
+/// `[1, 3, 5]` → `"1, 3 or 5"`.
+fn or_list(values: &[i64]) -> String {
+    let values: Vec<String> = values.iter().map(ToString::to_string).collect();
+    match values.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} or {last}", rest.join(", ")),
+        _ => values.concat(),
+    }
+}
+
+/// Spin-state heuristics for a charge and multiplicity that passed
+/// [`check_charge_mult`]: a first-row transition metal run as a singlet
+/// (unless the xyz comment line mentions the spin state), and more unpaired
+/// electrons than the open shells can provide.
+fn check_spin_state(molecule: &Molecule, context: &tera::Context) -> Vec<Diagnostic> {
+    let json = context.clone().into_json();
+    let charge = json.get("charge").and_then(serde_json::Value::as_i64);
+    let mult = json.get("mult").and_then(serde_json::Value::as_i64);
+    let (Some(charge), Some(mult)) = (charge, mult) else {
+        return vec![];
+    };
+    let electrons: i64 = molecule
+        .atoms
+        .iter()
+        .map(|a| i64::from(a.element as u8))
+        .sum::<i64>()
+        - charge;
+    let max_unpaired = plausible_unpaired(molecule);
+    let suggested = consistent_multiplicities(electrons, max_unpaired);
+    let mut diags = vec![];
+
+    let metals: Vec<usize> = molecule
+        .atoms
+        .iter()
+        .enumerate()
+        .filter(|(_, a)| a.element.is_first_row_transition_metal())
+        .map(|(i, _)| i + 1)
+        .collect();
+    let noted = molecule.description.as_deref().is_some_and(|d| {
+        let d = d.to_ascii_lowercase();
+        LOW_SPIN_NOTES.iter().any(|note| d.contains(note))
+    });
+    if mult == 1 && !metals.is_empty() && !noted {
+        let mut symbols: Vec<String> = metals
+            .iter()
+            .map(|&i| molecule.atoms[i - 1].element.to_string())
+            .collect();
+        symbols.sort();
+        symbols.dedup();
+        diags.push(
+            Diagnostic::warning(
+                Check::ClosedShellTransitionMetal,
+                format!(
+                    "{} is run as a closed-shell singlet; first-row transition metals often \
+                     have high-spin ground states (with charge {charge}, try multiplicity {}), \
+                     or say \"low-spin\" in the xyz comment line if the singlet is intended",
+                    symbols.join(", "),
+                    or_list(&suggested)
+                ),
+            )
+            .with_atoms(metals),
+        );
+    }
+
+    let unpaired = mult - 1;
+    if unpaired > max_unpaired {
+        diags.push(Diagnostic::warning(
+            Check::HighMultiplicity,
+            format!(
+                "multiplicity {mult} needs {unpaired} unpaired electrons, more than the \
+                 {max_unpaired} the open d/f shells and a main-group diradical plausibly \
+                 provide (with charge {charge}, try multiplicity {})",
+                or_list(&suggested)
+            ),
+        ));
+    }
+    diags
+}
 
+/// Atoms closer than `superposed_distance` (Å) are an error; atoms closer than
+/// `radii_factor` times the sum of their covalent radii are a warning.
+fn check_superposed_atoms(
+    molecule: &Molecule,
+    superposed_distance: f64,
+    radii_factor: f64,
+) -> Vec<Diagnostic> {
     let mut diags = vec![];
     let atoms = &molecule.atoms;
     for i in 0..atoms.len() {
@@ -140,29 +719,41 @@ fn check_superposed_atoms(molecule: &Molecule) -> Vec<Diagnostic> {
             let dz = atoms[i].z - atoms[j].z;
             let dist = dz.mul_add(dz, dx.mul_add(dx, dy * dy)).sqrt();
 
-            if dist < ERROR_THRESHOLD {
-                diags.push(Diagnostic::error(format!(
-                    "atoms {} ({}) and {} ({}) are superposed: distance {dist:.3} Å",
-                    i + 1,
-                    atoms[i].element,
-                    j + 1,
-                    atoms[j].element,
-                )));
+            if dist < superposed_distance {
+                diags.push(
+                    Diagnostic::error(
+                        Check::SuperposedAtoms,
+                        format!(
+                            "atoms {} ({}) and {} ({}) are superposed: distance {dist:.3} Å",
+                            i + 1,
+                            atoms[i].element,
+                            j + 1,
+                            atoms[j].element,
+                        ),
+                    )
+                    .with_atoms(vec![i + 1, j + 1]),
+                );
             } else {
                 let ri = atoms[i].element.get_radius().map(f64::from);
                 let rj = atoms[j].element.get_radius().map(f64::from);
                 if let (Some(ri), Some(rj)) = (ri, rj) {
-                    let threshold = RADII_FACTOR * (ri + rj);
+                    let threshold = radii_factor * (ri + rj);
                     if dist < threshold {
-                        diags.push(Diagnostic::warning(format!(
-                            "atoms {} ({}) and {} ({}) are unusually close: \
+                        diags.push(
+                            Diagnostic::warning(
+                                Check::CloseContact,
+                                format!(
+                                    "atoms {} ({}) and {} ({}) are unusually close: \
                              {dist:.3} Å (sum of covalent radii = {:.3} Å)",
-                            i + 1,
-                            atoms[i].element,
-                            j + 1,
-                            atoms[j].element,
-                            ri + rj,
-                        )));
+                                    i + 1,
+                                    atoms[i].element,
+                                    j + 1,
+                                    atoms[j].element,
+                                    ri + rj,
+                                ),
+                            )
+                            .with_atoms(vec![i + 1, j + 1]),
+                        );
                     }
                 }
             }
@@ -171,15 +762,509 @@ fn check_superposed_atoms(molecule: &Molecule) -> Vec<Diagnostic> {
     diags
 }
 
+/// Perceive bonds (atoms closer than `bond_factor` times the sum of their
+/// covalent radii) and report fragments beyond `max_fragments`, atoms without
+/// neighbours and atoms bonded to more non-metals than their usual valence.
+/// Atoms without neighbours are reported only as such, not as fragments.
+fn check_connectivity(
+    molecule: &Molecule,
+    bond_factor: f64,
+    max_fragments: usize,
+) -> Vec<Diagnostic> {
+    let atoms = &molecule.atoms;
+    if atoms.len() < 2 {
+        return vec![];
+    }
+    let has_radius = |i: usize| atoms[i].element.get_radius().is_some();
+    let neighbours = molecule.neighbours(bond_factor);
+    // Dummy atoms have no radius and never bond; leave them out, and the
+    // isolated atoms that get their own warning below.
+    let fragments: Vec<Vec<usize>> = Molecule::fragments(&neighbours)
+        .into_iter()
+        .filter(|f| f.len() > 1 && f.iter().any(|&i| has_radius(i)))
+        .collect();
+    // Distance from the atoms of `fragment` to the nearest atom outside it.
+    let gap = |fragment: &[usize]| {
+        fragment
+            .iter()
+            .flat_map(|&i| {
+                (0..atoms.len())
+                    .filter(|j| has_radius(*j) && !fragment.contains(j))
+                    .map(move |j| molecule.distance(i, j))
+            })
+            .fold(f64::INFINITY, f64::min)
+    };
+
+    let mut diags = vec![];
+    if fragments.len() > max_fragments {
+        let largest = fragments
+            .iter()
+            .map(Vec::len)
+            .enumerate()
+            .fold(
+                (0, 0),
+                |best, (i, n)| if n > best.1 { (i, n) } else { best },
+            )
+            .0;
+        let strays: Vec<&Vec<usize>> = fragments
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != largest)
+            .map(|(_, f)| f)
+            .collect();
+        let details: Vec<String> = strays
+            .iter()
+            .map(|f| {
+                format!(
+                    "{} atom(s) from atom {} are {:.2} Å from the rest",
+                    f.len(),
+                    f[0] + 1,
+                    gap(f)
+                )
+            })
+            .collect();
+        diags.push(
+            Diagnostic::warning(
+                Check::TooManyFragments,
+                format!(
+                    "structure has {} disconnected fragments, expected at most {max_fragments}: {}",
+                    fragments.len(),
+                    details.join("; ")
+                ),
+            )
+            .with_atoms(
+                strays
+                    .iter()
+                    .flat_map(|f| f.iter().map(|i| i + 1))
+                    .collect(),
+            ),
+        );
+    }
+
+    for (i, bonded) in neighbours.iter().enumerate() {
+        let element = atoms[i].element;
+        if bonded.is_empty() && has_radius(i) {
+            diags.push(
+                Diagnostic::warning(
+                    Check::IsolatedAtom,
+                    format!(
+                        "atom {} ({element}) has no bonded neighbours; the nearest atom is {:.2} Å away",
+                        i + 1,
+                        gap(&[i]),
+                    ),
+                )
+                .with_atoms(vec![i + 1]),
+            );
+        }
+        if let Some(valence) = element.max_valence() {
+            let bonds = bonded
+                .iter()
+                .filter(|&&j| !atoms[j].element.is_metal())
+                .count();
+            if bonds > valence {
+                diags.push(
+                    Diagnostic::warning(
+                        Check::AbnormalValence,
+                        format!(
+                            "atom {} ({element}) is bonded to {bonds} atoms, more than its usual valence of {valence}",
+                            i + 1,
+                        ),
+                    )
+                    .with_atoms(vec![i + 1]),
+                );
+            }
+        }
+    }
+    diags
+}
+
+/// Context keys holding model keywords, with the kind of keyword they hold.
+const KEYWORD_KEYS: [(&str, KeywordKind); 4] = [
+    ("method", KeywordKind::Method),
+    ("basis_set", KeywordKind::BasisSet),
+    ("dispersion", KeywordKind::Dispersion),
+    ("solvation_model", KeywordKind::SolvationModel),
+];
+
+/// Check model keywords against what `software` understands.
+///
+/// Only keys the template uses (see [`TemplateMeta::uses`]) are checked, and
+/// only when set. `solvation_model` is only checked when `solvation` is on.
+/// Keys in `translated` are rendered through `for_software`, so their
+/// translated spelling is what gets checked.
+fn check_keywords(
+    context: &tera::Context,
+    software: Software,
+    meta: &TemplateMeta,
+) -> Vec<Diagnostic> {
+    let (requires, translated) = (&meta.requires, &meta.translated);
+    let json = context.clone().into_json();
+    let get = |key: &str| json.get(key).and_then(serde_json::Value::as_str);
+    let solvation = json
+        .get("solvation")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
+
+    let mut diags = vec![];
+    for (key, kind) in KEYWORD_KEYS {
+        let Some(value) = get(key).filter(|_| meta.uses(key)) else {
+            continue;
+        };
+        let value = if translated.iter().any(|t| t == key) {
+            knowledge::translate(value, software, Some(kind)).unwrap_or(value)
+        } else {
+            value
+        };
+        if kind == KeywordKind::SolvationModel && !solvation {
+            continue;
+        }
+        match knowledge::lookup(kind, value) {
+            Some(kw) => match kw.spellings_for(software) {
+                Some(spellings) if !kw.accepted_by(software, value) => {
+                    diags.push(Diagnostic::warning(
+                        Check::KeywordSpelling,
+                        format!(
+                            "{key} `{value}` is not how {software} spells {kind} `{}`; use `{}`",
+                            kw.canonical, spellings[0]
+                        ),
+                    ));
+                }
+                Some(_) => {}
+                None if kind == KeywordKind::SolvationModel => {
+                    // Only fatal when the template always uses the model.
+                    let used = requires.iter().chain(translated).any(|k| k == key);
+                    let message = format!(
+                        "{software} does not support the `{}` solvation model",
+                        kw.canonical
+                    );
+                    diags.push(if used {
+                        Diagnostic::error(Check::SolvationModelUnsupported, message)
+                    } else {
+                        Diagnostic::warning(Check::SolvationModelUnsupported, message)
+                    });
+                }
+                None => diags.push(Diagnostic::warning(
+                    Check::KeywordUnsupported,
+                    format!("{kind} `{value}` is not available in {software}"),
+                )),
+            },
+            // Method lists are far from exhaustive; only flag the short lists.
+            None if kind != KeywordKind::Method => diags.push(Diagnostic::warning(
+                Check::UnknownKeyword,
+                format!("unknown {kind} `{value}` for {software}"),
+            )),
+            None => {}
+        }
+    }
+
+    let method = get("method");
+    let model = get("solvation_model").filter(|_| solvation && meta.uses("solvation_model"));
+    let is_alpb = model
+        .and_then(|m| knowledge::lookup(KeywordKind::SolvationModel, m))
+        .is_some_and(|kw| kw.canonical == "alpb");
+    if let Some(method) = method.filter(|_| requires.iter().any(|r| r == "method")) {
+        let xtb = knowledge::is_xtb_method(method);
+        if is_alpb && !xtb {
+            diags.push(Diagnostic::error(
+                Check::AlpbRequiresXtb,
+                format!("ALPB solvation is only available with xtb methods, not `{method}`"),
+            ));
+        } else if xtb && software == Software::Orca && model.is_some() && !is_alpb {
+            diags.push(Diagnostic::error(
+                Check::XtbRequiresAlpb,
+                format!(
+                    "xtb methods in ORCA require ALPB solvation, got `{}`",
+                    model.unwrap_or_default()
+                ),
+            ));
+        }
+        if knowledge::is_composite_method(method) {
+            for key in ["basis_set", "dispersion"] {
+                if get(key).is_some() && requires.iter().any(|r| r == key) {
+                    diags.push(Diagnostic::warning(
+                        Check::CompositeMethodExtras,
+                        format!(
+                            "`{method}` is a composite method with its own basis set and \
+                         dispersion; `{key}` should be left unset"
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    diags
+}
+
+/// Check `solvent` against the solvents `software` has parameters for, with a
+/// "did you mean" hint for names gedent doesn't know. The table only holds
+/// common solvents, so an unknown name is a warning. Only checked when
+/// `solvation` is on.
+fn check_solvent(
+    context: &tera::Context,
+    software: Software,
+    translated: &[String],
+) -> Vec<Diagnostic> {
+    let json = context.clone().into_json();
+    let solvation = json
+        .get("solvation")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
+    let Some(value) = json.get("solvent").and_then(serde_json::Value::as_str) else {
+        return vec![];
+    };
+    if !solvation {
+        return vec![];
+    }
+    let Some(solvent) = solvents::lookup(value) else {
+        let hint = solvents::suggest(value)
+            .map(|s| format!("; did you mean `{s}`?"))
+            .unwrap_or_default();
+        return vec![Diagnostic::warning(
+            Check::UnknownSolvent,
+            format!("solvent `{value}` is not in gedent's solvent table{hint}"),
+        )];
+    };
+    match solvent.names_for(software) {
+        None => vec![Diagnostic::error(
+            Check::SolventUnsupported,
+            format!(
+                "{software} has no built-in parameters for solvent `{}`",
+                solvent.canonical
+            ),
+        )],
+        Some(names)
+            if !translated.iter().any(|t| t == "solvent")
+                && !solvent.accepted_by(software, value) =>
+        {
+            vec![Diagnostic::warning(
+                Check::SolventSpelling,
+                format!(
+                    "solvent `{value}` is not how {software} spells `{}`; use `{}`",
+                    solvent.canonical, names[0]
+                ),
+            )]
+        }
+        Some(_) => vec![],
+    }
+}
+
+/// Heaviest element that is routinely treated all-electron without a
+/// relativistic Hamiltonian (Kr).
+const MAX_NONRELATIVISTIC_Z: u8 = 36;
+
+/// Whether the job asks for a scalar-relativistic Hamiltonian: a `zora`
+/// template, ZORA/DKH/X2C in `method` or a truthy `relativistic` variable.
+/// ADF uses ZORA by default.
+fn has_relativistic_treatment(context: &tera::Context, meta: &TemplateMeta) -> bool {
+    let json = context.clone().into_json();
+    let method = json
+        .get("method")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default()
+        .to_lowercase();
+    let relativistic = match json.get("relativistic") {
+        Some(serde_json::Value::Bool(b)) => *b,
+        Some(serde_json::Value::String(s)) => !s.is_empty(),
+        _ => false,
+    };
+    meta.jobtype.as_deref() == Some("zora")
+        || meta.software.as_deref().and_then(Software::from_name) == Some(Software::Adf)
+        || ["zora", "dkh", "x2c", "douglas"]
+            .iter()
+            .any(|h| method.contains(h))
+        || relativistic
+}
+
+/// Check that `basis_set` covers every element of `molecule`, and that heavy
+/// elements get either an ECP or a relativistic Hamiltonian matching the
+/// basis. Elements listed in `zoraatoms` get their own basis and are skipped.
+fn check_basis_coverage(
+    molecule: &Molecule,
+    context: &tera::Context,
+    meta: &TemplateMeta,
+) -> Vec<Diagnostic> {
+    let json = context.clone().into_json();
+    let Some(basis) = json.get("basis_set").and_then(serde_json::Value::as_str) else {
+        return vec![];
+    };
+    let Some(coverage) = knowledge::basis_coverage(basis) else {
+        return vec![];
+    };
+    let overridden: Vec<&str> = json
+        .get("zoraatoms")
+        .and_then(serde_json::Value::as_array)
+        .map(|a| a.iter().filter_map(serde_json::Value::as_str).collect())
+        .unwrap_or_default();
+    let relativistic = has_relativistic_treatment(context, meta);
+
+    let mut elements = vec![];
+    for atom in &molecule.atoms {
+        let symbol = atom.element.to_string();
+        if atom.element as u8 == 0
+            || elements.contains(&atom.element)
+            || overridden.iter().any(|o| o.eq_ignore_ascii_case(&symbol))
+        {
+            continue;
+        }
+        elements.push(atom.element);
+    }
+
+    let indices_of = |element| {
+        molecule
+            .atoms
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| a.element == element)
+            .map(|(i, _)| i + 1)
+            .collect()
+    };
+    let mut diags = vec![];
+    let mut ecp_elements = vec![];
+    for element in elements {
+        let z = element as u8;
+        if !coverage.covers(z) {
+            diags.push(
+                Diagnostic::error(
+                    Check::BasisElementCoverage,
+                    format!("basis set `{basis}` does not cover {element} (Z = {z})"),
+                )
+                .with_atoms(indices_of(element)),
+            );
+        } else if coverage.has_ecp(z) {
+            ecp_elements.push(element.to_string());
+        } else if z > MAX_NONRELATIVISTIC_Z && !relativistic {
+            diags.push(
+                Diagnostic::warning(
+                    Check::HeavyElementAllElectron,
+                    format!(
+                        "{element} (Z = {z}) is treated all-electron with `{basis}` but no \
+                 relativistic Hamiltonian is requested; use ZORA/DKH/X2C or a basis \
+                 set with an ECP"
+                    ),
+                )
+                .with_atoms(indices_of(element)),
+            );
+        }
+    }
+    if coverage.relativistic && !relativistic {
+        diags.push(Diagnostic::warning(
+            Check::RelativisticBasisWithoutHamiltonian,
+            format!(
+                "`{basis}` is contracted for a relativistic Hamiltonian, but the job \
+             requests none (add ZORA, DKH or X2C)"
+            ),
+        ));
+    }
+    if relativistic && !ecp_elements.is_empty() {
+        diags.push(Diagnostic::warning(
+            Check::EcpWithRelativisticHamiltonian,
+            format!(
+                "`{basis}` puts ECPs on {} while a relativistic Hamiltonian is requested; \
+             use an all-electron relativistic basis instead",
+                ecp_elements.join(", ")
+            ),
+        ));
+    }
+    diags
+}
+
+/// Compare the resolved `[resources]` with `node`: total memory and cores,
+/// and for xtb more processes than atoms.
+fn check_resources(
+    molecule: Option<&Molecule>,
+    context: &tera::Context,
+    meta: &TemplateMeta,
+    node: &Node,
+) -> Vec<Diagnostic> {
+    let json = context.clone().into_json();
+    let int = |key: &str| json.get(key).and_then(serde_json::Value::as_i64);
+    let mut diags = vec![];
+    let nprocs = int("nprocs");
+
+    // `resources::insert_resolved` turns every valid `mem` into MB.
+    if let Some(mem) = json.get("mem").filter(|mem| !mem.is_i64()) {
+        let reason = Memory::deserialize(mem).err().map_or_else(
+            || "expected e.g. 3000 or \"4GB\"".to_string(),
+            |e| e.to_string(),
+        );
+        diags.push(Diagnostic::error(
+            Check::InvalidParameter,
+            format!("`mem` = {mem} is not an amount of memory: {reason}"),
+        ));
+    } else if json.get("mem").is_none() && !meta.requires.iter().any(|r| r == "mem") {
+        // Every derived key needs `mem`, even those computed from `nprocs`;
+        // a required `mem` is reported as missing on its own.
+        let derived: Vec<String> = resources::DERIVED_KEYS
+            .iter()
+            .filter(|(key, _)| meta.reads.iter().any(|r| r == key))
+            .map(|(key, _)| format!("`{key}`"))
+            .collect();
+        if !derived.is_empty() {
+            diags.push(Diagnostic::error(
+                Check::MissingVariable,
+                format!(
+                    "template uses {} but `mem` is not defined in context; \
+                     set `[resources] mem` or pass --mem",
+                    derived.join(", ")
+                ),
+            ));
+        }
+    }
+
+    if let (Some(total), Some(available)) = (int("mem_total"), node.mem) {
+        if total > available {
+            let per_core = int("mem_per_core").unwrap_or(total);
+            diags.push(Diagnostic::warning(
+                Check::MemoryOversubscribed,
+                format!(
+                    "job needs {total} MB ({} × {per_core} MB) but {} has {available} MB",
+                    nprocs.unwrap_or(1),
+                    node.describe()
+                ),
+            ));
+        }
+    }
+    if let (Some(nprocs), Some(cores)) = (nprocs, node.cores) {
+        if nprocs > cores {
+            diags.push(Diagnostic::warning(
+                Check::CoresOversubscribed,
+                format!(
+                    "nprocs = {nprocs} exceeds the {cores} cores of {}",
+                    node.describe()
+                ),
+            ));
+        }
+    }
+
+    // xtb parallelises over small matrices; cores beyond one per atom idle.
+    let method = json.get("method").and_then(serde_json::Value::as_str);
+    let xtb = method.is_some_and(knowledge::is_xtb_method)
+        || meta.software.as_deref().and_then(Software::from_name) == Some(Software::Xtb);
+    if let (Some(mol), Some(nprocs), true) = (molecule, nprocs, xtb) {
+        let natoms = mol.atoms.len();
+        if usize::try_from(nprocs).is_ok_and(|n| n > natoms) {
+            diags.push(Diagnostic::warning(
+                Check::MoreProcsThanAtoms,
+                format!(
+                    "nprocs = {nprocs} exceeds the {natoms} atoms; xtb gains nothing from \
+                     more cores than atoms"
+                ),
+            ));
+        }
+    }
+    diags
+}
+
 fn check_missing_vars(context: &tera::Context, requires: &[String]) -> Vec<Diagnostic> {
     let json = context.clone().into_json();
     requires
         .iter()
         .filter(|k| json.get(k.as_str()).is_none())
         .map(|k| {
-            Diagnostic::error(format!(
-                "template requires `{k}` but it is not defined in context"
-            ))
+            Diagnostic::error(
+                Check::MissingVariable,
+                format!("template requires `{k}` but it is not defined in context"),
+            )
         })
         .collect()
 }
@@ -200,6 +1285,22 @@ mod tests {
         }
     }
 
+    fn meta(software: Option<&str>, requires: &[&str]) -> TemplateMeta {
+        TemplateMeta {
+            software: software.map(str::to_string),
+            requires: requires.iter().map(|s| (*s).to_string()).collect(),
+            ..TemplateMeta::default()
+        }
+    }
+
+    fn ctx_with_strs(pairs: &[(&str, &str)]) -> tera::Context {
+        let mut ctx = tera::Context::new();
+        for (k, v) in pairs {
+            ctx.insert(*k, v);
+        }
+        ctx
+    }
+
     fn ctx_with_ints(pairs: &[(&str, i64)]) -> tera::Context {
         let mut ctx = tera::Context::new();
         for (k, v) in pairs {
@@ -274,7 +1375,7 @@ mod tests {
             (Element::C, 0.0, 0.0, 0.0),
             (Element::H, 0.1, 0.0, 0.0), // 0.1 Å — clearly superposed
         ]);
-        let diags = check_superposed_atoms(&mol);
+        let diags = check_superposed_atoms(&mol, 0.5, 0.5);
         assert_eq!(diags.len(), 1);
         assert_eq!(diags[0].severity, Severity::Error);
     }
@@ -285,7 +1386,7 @@ mod tests {
             (Element::C, 0.0, 0.0, 0.0),
             (Element::H, 1.089, 0.0, 0.0), // typical C-H bond length
         ]);
-        assert!(check_superposed_atoms(&mol).is_empty());
+        assert!(check_superposed_atoms(&mol, 0.5, 0.5).is_empty());
     }
 
     #[test]
@@ -296,7 +1397,7 @@ mod tests {
             (Element::H, 0.0, 0.0, 0.0),
             (Element::H, 0.0, 0.0, 0.0),
         ]);
-        assert_eq!(check_superposed_atoms(&mol).len(), 3);
+        assert_eq!(check_superposed_atoms(&mol, 0.5, 0.5).len(), 3);
     }
 
     #[test]
@@ -311,11 +1412,155 @@ mod tests {
             (Element::C, 0.0, 0.0, 0.0),
             (Element::C, 0.52, 0.0, 0.0),
         ]);
-        let diags = check_superposed_atoms(&mol);
+        let diags = check_superposed_atoms(&mol, 0.5, 0.5);
         assert_eq!(diags.len(), 1);
         assert_eq!(diags[0].severity, Severity::Warning);
     }
 
+    // ── spin state ─────────────────────────────────────────────────────────────
+
+    fn fecl2() -> Molecule {
+        make_molecule(vec![
+            (Element::Fe, 0.0, 0.0, 0.0),
+            (Element::Cl, 2.2, 0.0, 0.0),
+            (Element::Cl, -2.2, 0.0, 0.0),
+        ])
+    }
+
+    #[test]
+    fn spin_state_warns_on_singlet_first_row_metal() {
+        let diags = check_spin_state(&fecl2(), &ctx_with_ints(&[("charge", 0), ("mult", 1)]));
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].check, Check::ClosedShellTransitionMetal);
+        assert_eq!(diags[0].severity, Severity::Warning);
+        assert_eq!(diags[0].atoms, vec![1]);
+        assert!(
+            diags[0].message.contains("try multiplicity 1, 3, 5 or 7"),
+            "{}",
+            diags[0].message
+        );
+        assert!(
+            check_spin_state(&fecl2(), &ctx_with_ints(&[("charge", 0), ("mult", 5)])).is_empty()
+        );
+    }
+
+    #[test]
+    fn spin_state_accepts_noted_low_spin() {
+        let mut mol = fecl2();
+        mol.description = Some("Fe(II), Low-Spin".to_string());
+        assert!(check_spin_state(&mol, &ctx_with_ints(&[("charge", 0), ("mult", 1)])).is_empty());
+    }
+
+    #[test]
+    fn spin_state_warns_on_high_multiplicity() {
+        let diags = check_spin_state(&fecl2(), &ctx_with_ints(&[("charge", 0), ("mult", 9)]));
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].check, Check::HighMultiplicity);
+        assert!(diags[0]
+            .message
+            .contains("8 unpaired electrons, more than the 7"));
+
+        // CH3 radical as a quartet: no d/f shells to host a third unpaired electron.
+        let methyl = make_molecule(vec![
+            (Element::C, 0.0, 0.0, 0.0),
+            (Element::H, 1.0, 0.0, 0.0),
+            (Element::H, -1.0, 0.0, 0.0),
+            (Element::H, 0.0, 1.0, 0.0),
+        ]);
+        let diags = check_spin_state(&methyl, &ctx_with_ints(&[("charge", 0), ("mult", 4)]));
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0]
+            .message
+            .ends_with("(with charge 0, try multiplicity 2)"));
+        // Triplet O2-like diradicals are fine.
+        let o2 = make_molecule(vec![
+            (Element::O, 0.0, 0.0, 0.0),
+            (Element::O, 1.2, 0.0, 0.0),
+        ]);
+        assert!(check_spin_state(&o2, &ctx_with_ints(&[("charge", 0), ("mult", 3)])).is_empty());
+    }
+
+    #[test]
+    fn parity_error_suggests_multiplicities() {
+        let diags = check_charge_mult(
+            &make_molecule(water_at(0.0)),
+            &ctx_with_ints(&[("charge", 0), ("mult", 2)]),
+        );
+        assert_eq!(diags[0].check, Check::ChargeMultParity);
+        assert!(diags[0]
+            .message
+            .ends_with("(with charge 0, try multiplicity 1 or 3)"));
+        assert_eq!(consistent_multiplicities(1, 2), vec![2]);
+        assert_eq!(consistent_multiplicities(0, 2), vec![1]);
+    }
+
+    // ── connectivity ───────────────────────────────────────────────────────────
+
+    fn water_at(x: f64) -> Vec<(Element, f64, f64, f64)> {
+        vec![
+            (Element::O, x, 0.0, 0.0),
+            (Element::H, x + 0.757, 0.586, 0.0),
+            (Element::H, x - 0.757, 0.586, 0.0),
+        ]
+    }
+
+    #[test]
+    fn connectivity_ok_for_single_molecule() {
+        let mol = make_molecule(water_at(0.0));
+        assert!(check_connectivity(&mol, 1.2, 1).is_empty());
+    }
+
+    #[test]
+    fn connectivity_reports_drifted_fragment() {
+        let mut atoms = water_at(0.0);
+        atoms.extend(water_at(20.0));
+        let mol = make_molecule(atoms);
+        let diags = check_connectivity(&mol, 1.2, 1);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].check, Check::TooManyFragments);
+        assert_eq!(diags[0].atoms, vec![4, 5, 6]);
+        assert!(
+            diags[0].message.contains("2 disconnected fragments"),
+            "{}",
+            diags[0].message
+        );
+        assert!(check_connectivity(&mol, 1.2, 2).is_empty());
+    }
+
+    #[test]
+    fn connectivity_reports_isolated_atom() {
+        let mut atoms = water_at(0.0);
+        atoms.push((Element::H, 0.0, 3.0, 0.0));
+        let mol = make_molecule(atoms);
+        // Only as an isolated atom, not also as an extra fragment.
+        let diags = check_connectivity(&mol, 1.2, 1);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].check, Check::IsolatedAtom);
+        assert_eq!(diags[0].atoms, vec![4]);
+    }
+
+    #[test]
+    fn connectivity_reports_overvalent_atoms_but_not_metal_bonds() {
+        // Pentacoordinate carbon: four H plus a fifth squeezed in.
+        let mol = make_molecule(vec![
+            (Element::C, 0.0, 0.0, 0.0),
+            (Element::H, 1.09, 0.0, 0.0),
+            (Element::H, -1.09, 0.0, 0.0),
+            (Element::H, 0.0, 1.09, 0.0),
+            (Element::H, 0.0, -1.09, 0.0),
+            (Element::H, 0.0, 0.0, 1.09),
+        ]);
+        let diags = check_connectivity(&mol, 1.2, 1);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].check, Check::AbnormalValence);
+        assert_eq!(diags[0].atoms, vec![1]);
+
+        // Water bound to iron: O has three neighbours, but one is a metal.
+        let mut atoms = water_at(0.0);
+        atoms.push((Element::Fe, 0.0, -2.0, 0.0));
+        assert!(check_connectivity(&make_molecule(atoms), 1.2, 1).is_empty());
+    }
+
     // ── missing vars ───────────────────────────────────────────────────────────
 
     #[test]
@@ -355,8 +1600,12 @@ mod tests {
         ]);
         // 2 electrons, charge=0, mult=2 → (2-1)=1 unpaired, (2-1)%2 != 0 → parity error
         let ctx = ctx_with_ints(&[("charge", 0), ("mult", 2)]);
-        let requires = vec!["basis_set".to_string()];
-        let diags = validate(Some(&mol), &ctx, &requires);
+        let diags = validate(
+            Some(&mol),
+            &ctx,
+            &meta(None, &["basis_set"]),
+            &Policy::default(),
+        );
         // superposed(1) + charge/mult parity(1) + missing basis_set(1) = 3
         assert_eq!(diags.len(), 3);
         assert!(diags.iter().all(|d| d.severity == Severity::Error));
@@ -364,9 +1613,482 @@ mod tests {
 
     #[test]
     fn validate_no_molecule_skips_geometry_checks() {
-        let requires = vec!["method".to_string()];
-        let diags = validate(None, &tera::Context::new(), &requires);
+        let diags = validate(
+            None,
+            &tera::Context::new(),
+            &meta(None, &["method"]),
+            &Policy::default(),
+        );
         assert_eq!(diags.len(), 1);
         assert!(diags[0].message.contains("method"));
     }
+
+    // ── keywords ───────────────────────────────────────────────────────────────
+
+    /// Metadata of a template requiring `requires` and translating `translated`.
+    fn kw_meta(requires: &[&str], translated: &[&str]) -> TemplateMeta {
+        TemplateMeta {
+            translated: translated.iter().map(|s| (*s).to_string()).collect(),
+            ..meta(None, requires)
+        }
+    }
+
+    /// A template that requires every keyword key.
+    fn all_keys() -> TemplateMeta {
+        kw_meta(&KEYWORD_KEYS.map(|(key, _)| key), &[])
+    }
+
+    #[test]
+    fn keywords_warn_on_foreign_spelling() {
+        let ctx = ctx_with_strs(&[("dispersion", "d3bj")]);
+        let diags = check_keywords(&ctx, Software::Gaussian, &all_keys());
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].severity, Severity::Warning);
+        assert!(diags[0].message.contains("EmpiricalDispersion=GD3BJ"));
+        assert!(check_keywords(&ctx, Software::Orca, &all_keys()).is_empty());
+    }
+
+    #[test]
+    fn keywords_warn_on_basis_orca_does_not_ship() {
+        let ctx = ctx_with_strs(&[("basis_set", "SDD")]);
+        let diags = check_keywords(&ctx, Software::Orca, &all_keys());
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0].message.contains("not available in orca"));
+
+        let ctx = ctx_with_strs(&[("basis_set", "def2-qzvppp")]);
+        assert_eq!(check_keywords(&ctx, Software::Orca, &all_keys()).len(), 1);
+    }
+
+    #[test]
+    fn keywords_accept_native_spellings() {
+        let ctx = ctx_with_strs(&[
+            ("method", "PBE1PBE"),
+            ("basis_set", "Def2TZVP"),
+            ("dispersion", "EmpiricalDispersion=GD3BJ"),
+        ]);
+        assert!(check_keywords(&ctx, Software::Gaussian, &all_keys()).is_empty());
+    }
+
+    #[test]
+    fn keywords_check_translated_spelling() {
+        let ctx = ctx_with_strs(&[("method", "pbe0"), ("dispersion", "d3bj")]);
+        let translated = kw_meta(&[], &["method", "dispersion"]);
+        assert!(check_keywords(&ctx, Software::Gaussian, &translated).is_empty());
+
+        // Translation can't help when the program lacks the keyword.
+        let ctx = ctx_with_strs(&[("dispersion", "d4")]);
+        assert_eq!(
+            check_keywords(&ctx, Software::Gaussian, &translated).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn keywords_skip_unknown_methods() {
+        let ctx = ctx_with_strs(&[("method", "my-custom-functional")]);
+        assert!(check_keywords(&ctx, Software::Orca, &all_keys()).is_empty());
+    }
+
+    #[test]
+    fn keywords_skip_keys_the_template_does_not_use() {
+        let ctx = ctx_with_strs(&[
+            ("method", "pbe0"),
+            ("basis_set", "def2-svp"),
+            ("dispersion", "d3bj"),
+        ]);
+        assert!(check_keywords(&ctx, Software::Xtb, &kw_meta(&["charge"], &[])).is_empty());
+        let optional = TemplateMeta {
+            optional: vec!["dispersion".to_string()],
+            ..TemplateMeta::default()
+        };
+        assert_eq!(check_keywords(&ctx, Software::Xtb, &optional).len(), 1);
+    }
+
+    #[test]
+    fn alpb_requires_xtb_method() {
+        let mut ctx = ctx_with_strs(&[("method", "pbe0"), ("solvation_model", "alpb")]);
+        ctx.insert("solvation", &true);
+        let meta = kw_meta(&["method", "solvation_model"], &[]);
+        let diags = check_keywords(&ctx, Software::Orca, &meta);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].severity, Severity::Error);
+
+        ctx.insert("method", "XTB2");
+        assert!(check_keywords(&ctx, Software::Orca, &meta).is_empty());
+    }
+
+    #[test]
+    fn orca_xtb_requires_alpb() {
+        let mut ctx = ctx_with_strs(&[("method", "XTB2"), ("solvation_model", "CPCM")]);
+        ctx.insert("solvation", &true);
+        let meta = kw_meta(&["method", "solvation_model"], &[]);
+        let diags = check_keywords(&ctx, Software::Orca, &meta);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].severity, Severity::Error);
+
+        // Nothing to combine when the template ignores the model.
+        let meta = kw_meta(&["method"], &[]);
+        assert!(check_keywords(&ctx, Software::Orca, &meta).is_empty());
+    }
+
+    #[test]
+    fn solvation_model_ignored_without_solvation() {
+        let ctx = ctx_with_strs(&[("method", "pbe0"), ("solvation_model", "alpb")]);
+        let meta = kw_meta(&["method", "solvation_model"], &[]);
+        assert!(check_keywords(&ctx, Software::Orca, &meta).is_empty());
+    }
+
+    #[test]
+    fn unsupported_solvation_model_is_error() {
+        let mut ctx = ctx_with_strs(&[("solvation_model", "smd")]);
+        ctx.insert("solvation", &true);
+        let diags = check_keywords(&ctx, Software::Xtb, &kw_meta(&["solvation_model"], &[]));
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].severity, Severity::Error);
+
+        // Templates that only use the model when set get a warning.
+        let optional = TemplateMeta {
+            optional: vec!["solvation_model".to_string()],
+            ..TemplateMeta::default()
+        };
+        let diags = check_keywords(&ctx, Software::Xtb, &optional);
+        assert_eq!(diags[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn composite_method_warns_about_basis() {
+        let ctx = ctx_with_strs(&[("method", "PBEh-3c"), ("basis_set", "def2-SVP")]);
+        let meta = kw_meta(&["method", "basis_set"], &[]);
+        let diags = check_keywords(&ctx, Software::Orca, &meta);
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0].message.contains("composite"));
+    }
+
+    #[test]
+    fn validate_runs_keyword_checks_for_known_software() {
+        let ctx = ctx_with_strs(&[("dispersion", "d3bj")]);
+        let policy = Policy::default();
+        assert_eq!(
+            validate(
+                None,
+                &ctx,
+                &meta(Some("gaussian"), &["dispersion"]),
+                &policy
+            )
+            .len(),
+            1
+        );
+        assert!(validate(None, &ctx, &meta(Some("gaussian"), &[]), &policy).is_empty());
+        assert!(validate(None, &ctx, &meta(Some("molpro"), &["dispersion"]), &policy).is_empty());
+        assert!(validate(None, &ctx, &meta(None, &["dispersion"]), &policy).is_empty());
+    }
+
+    // ── solvent ────────────────────────────────────────────────────────────────
+
+    fn solvated(solvent: &str) -> tera::Context {
+        let mut ctx = ctx_with_strs(&[("solvent", solvent)]);
+        ctx.insert("solvation", &true);
+        ctx
+    }
+
+    #[test]
+    fn solvent_typo_suggests_closest() {
+        let diags = check_solvent(&solvated("watr"), Software::Orca, &[]);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].severity, Severity::Warning);
+        assert!(diags[0].message.contains("did you mean `water`"));
+    }
+
+    #[test]
+    fn solvent_unsupported_by_software() {
+        let diags = check_solvent(&solvated("heptane"), Software::Xtb, &[]);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].severity, Severity::Error);
+        assert!(check_solvent(&solvated("heptane"), Software::Orca, &[]).is_empty());
+    }
+
+    #[test]
+    fn solvent_spelling_warns_unless_translated() {
+        let diags = check_solvent(&solvated("dcm"), Software::Xtb, &[]);
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0].message.contains("`ch2cl2`"));
+        let translated = ["solvent".to_string()];
+        assert!(check_solvent(&solvated("dcm"), Software::Xtb, &translated).is_empty());
+    }
+
+    #[test]
+    fn solvent_ignored_without_solvation() {
+        let ctx = ctx_with_strs(&[("solvent", "watr")]);
+        assert!(check_solvent(&ctx, Software::Orca, &[]).is_empty());
+    }
+
+    // ── basis set coverage ─────────────────────────────────────────────────────
+
+    fn basis_ctx(basis: &str, method: &str) -> tera::Context {
+        ctx_with_strs(&[("basis_set", basis), ("method", method)])
+    }
+
+    #[test]
+    fn basis_coverage_errors_on_uncovered_element() {
+        // Fe with a Pople triple-zeta basis
+        let mol = make_molecule(vec![
+            (Element::Fe, 0.0, 0.0, 0.0),
+            (Element::C, 1.8, 0.0, 0.0),
+        ]);
+        let diags = check_basis_coverage(&mol, &basis_ctx("6-311G**", "b3lyp"), &meta(None, &[]));
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].severity, Severity::Error);
+        assert!(diags[0].message.contains("Fe"));
+    }
+
+    #[test]
+    fn basis_coverage_accepts_ecp_for_heavy_elements() {
+        let mol = make_molecule(vec![
+            (Element::I, 0.0, 0.0, 0.0),
+            (Element::H, 1.6, 0.0, 0.0),
+        ]);
+        let ctx = basis_ctx("def2-SVP", "pbe0");
+        assert!(check_basis_coverage(&mol, &ctx, &meta(None, &[])).is_empty());
+    }
+
+    #[test]
+    fn basis_coverage_warns_all_electron_heavy_without_relativity() {
+        let mol = make_molecule(vec![
+            (Element::I, 0.0, 0.0, 0.0),
+            (Element::H, 1.6, 0.0, 0.0),
+        ]);
+        let diags = check_basis_coverage(&mol, &basis_ctx("sto-3g", "hf"), &meta(None, &[]));
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn basis_coverage_relativistic_basis_needs_hamiltonian() {
+        let mol = make_molecule(vec![(Element::Pt, 0.0, 0.0, 0.0)]);
+        let diags = check_basis_coverage(&mol, &basis_ctx("x2c-TZVPall", "pbe0"), &meta(None, &[]));
+        assert_eq!(diags.len(), 2);
+        assert!(diags.iter().all(|d| d.severity == Severity::Warning));
+        let ctx = basis_ctx("x2c-TZVPall", "pbe0 x2c");
+        assert!(check_basis_coverage(&mol, &ctx, &meta(None, &[])).is_empty());
+    }
+
+    #[test]
+    fn basis_coverage_warns_ecp_with_zora() {
+        let mol = make_molecule(vec![(Element::Pt, 0.0, 0.0, 0.0)]);
+        let zora = TemplateMeta {
+            jobtype: Some("zora".into()),
+            ..TemplateMeta::default()
+        };
+        let diags = check_basis_coverage(&mol, &basis_ctx("def2-TZVP", "pbe0"), &zora);
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0].message.contains("ECP"));
+    }
+
+    #[test]
+    fn basis_coverage_skips_zoraatoms_and_unknown_basis() {
+        let mol = make_molecule(vec![(Element::Ce, 0.0, 0.0, 0.0)]);
+        let mut ctx = basis_ctx("def2-SVP", "pbe0");
+        assert_eq!(check_basis_coverage(&mol, &ctx, &meta(None, &[])).len(), 1);
+        ctx.insert("zoraatoms", &["Ce"]);
+        assert!(check_basis_coverage(&mol, &ctx, &meta(None, &[])).is_empty());
+        let ctx = basis_ctx("my-basis", "pbe0");
+        assert!(check_basis_coverage(&mol, &ctx, &meta(None, &[])).is_empty());
+    }
+
+    #[test]
+    fn basis_coverage_only_for_templates_using_basis_set() {
+        let mol = make_molecule(vec![(Element::Gd, 0.0, 0.0, 0.0)]);
+        let ctx = basis_ctx("def2-SVP", "gfn2-xtb");
+        let policy = Policy::default();
+        let coverage = |meta: &TemplateMeta| {
+            validate(Some(&mol), &ctx, meta, &policy)
+                .iter()
+                .filter(|d| d.check == Check::BasisElementCoverage)
+                .count()
+        };
+        assert_eq!(coverage(&meta(Some("crest"), &["Molecule"])), 0);
+        assert_eq!(coverage(&meta(Some("orca"), &["basis_set"])), 1);
+    }
+
+    // ── codes ──────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn check_codes_are_unique_and_well_formed() {
+        use strum::IntoEnumIterator;
+        let mut codes: Vec<&str> = Check::iter().map(Check::code).collect();
+        let mut names: Vec<&str> = Check::iter().map(Check::name).collect();
+        for code in &codes {
+            assert_eq!(code.len(), 4);
+            assert!("GKSBRTI".contains(&code[..1]), "{code}");
+        }
+        codes.sort_unstable();
+        codes.dedup();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(codes.len(), Check::iter().count());
+        assert_eq!(names.len(), Check::iter().count());
+    }
+
+    #[test]
+    fn diagnostics_carry_check_and_atoms() {
+        let mol = make_molecule(vec![
+            (Element::C, 0.0, 0.0, 0.0),
+            (Element::H, 0.1, 0.0, 0.0),
+        ]);
+        let diags = check_superposed_atoms(&mol, 0.5, 0.5);
+        assert_eq!(diags[0].check, Check::SuperposedAtoms);
+        assert_eq!(diags[0].atoms, vec![1, 2]);
+        assert_eq!(diags[0].to_string().split(':').next(), Some("error[G005]"));
+
+        let mol = make_molecule(vec![(Element::H, 0.0, 0.0, 0.0)]);
+        let diags = check_charge_mult(&mol, &ctx_with_ints(&[("charge", 0), ("mult", 1)]));
+        assert_eq!(diags[0].check, Check::ChargeMultParity);
+    }
+
+    // ── policy ─────────────────────────────────────────────────────────────────
+
+    fn policy(toml: &str) -> Result<Policy> {
+        Policy::from_config(&toml::from_str::<ValidationConfig>(toml).unwrap())
+    }
+
+    /// Two carbons 0.6 Å apart: a close contact, not superposed by default.
+    fn short_contact() -> Molecule {
+        make_molecule(vec![
+            (Element::C, 0.0, 0.0, 0.0),
+            (Element::C, 0.6, 0.0, 0.0),
+        ])
+    }
+
+    #[test]
+    fn check_parses_code_or_name() {
+        assert_eq!(Check::parse("g006"), Some(Check::CloseContact));
+        assert_eq!(Check::parse("Close-Contact"), Some(Check::CloseContact));
+        assert_eq!(Check::parse("close_contact"), None);
+    }
+
+    #[test]
+    fn policy_disables_checks() {
+        let ctx = tera::Context::new();
+        let mol = short_contact();
+        let default = validate(Some(&mol), &ctx, &meta(None, &[]), &Policy::default());
+        assert_eq!(default[0].check, Check::CloseContact);
+        let disabled = policy("disable = [\"close-contact\"]").unwrap();
+        assert!(validate(Some(&mol), &ctx, &meta(None, &[]), &disabled).is_empty());
+    }
+
+    #[test]
+    fn policy_overrides_severity_then_strict() {
+        let diags = || {
+            vec![
+                Diagnostic::warning(Check::CloseContact, "close"),
+                Diagnostic::error(Check::UnknownSolvent, "unknown"),
+                Diagnostic::warning(Check::KeywordSpelling, "spelling"),
+            ]
+        };
+        let demoted = policy("[severity]\nS001 = \"warning\"")
+            .unwrap()
+            .apply(diags());
+        assert_eq!(demoted[1].severity, Severity::Warning);
+
+        let strict = policy("strict = true\n[severity]\nS001 = \"warning\"")
+            .unwrap()
+            .apply(diags());
+        assert!(strict.iter().all(|d| d.severity == Severity::Error));
+    }
+
+    #[test]
+    fn policy_thresholds_reach_geometry_checks() {
+        let ctx = tera::Context::new();
+        let mol = short_contact();
+        let strict_distance = policy("superposed_distance = 0.7").unwrap();
+        let diags = validate(Some(&mol), &ctx, &meta(None, &[]), &strict_distance);
+        assert_eq!(diags[0].check, Check::SuperposedAtoms);
+
+        let loose_radii = policy("close_contact_factor = 0.3").unwrap();
+        assert!(validate(Some(&mol), &ctx, &meta(None, &[]), &loose_radii).is_empty());
+    }
+
+    #[test]
+    fn policy_rejects_unknown_checks_and_bad_thresholds() {
+        assert!(policy("disable = [\"G999\"]").is_err());
+        assert!(policy("[severity]\nnot-a-check = \"error\"").is_err());
+        assert!(policy("superposed_distance = -1.0").is_err());
+    }
+
+    // ── resources ──────────────────────────────────────────────────────────────
+
+    fn node(cores: i64, mem: i64) -> Node {
+        Node {
+            cores: Some(cores),
+            mem: Some(mem),
+            source: crate::resources::NodeSource::Declared,
+        }
+    }
+
+    #[test]
+    fn resources_warn_when_node_is_oversubscribed() {
+        let ctx = ctx_with_ints(&[("nprocs", 8), ("mem_per_core", 4000), ("mem_total", 32000)]);
+        let diags = check_resources(None, &ctx, &meta(None, &[]), &node(4, 16000));
+        let checks: Vec<_> = diags.iter().map(|d| d.check).collect();
+        assert_eq!(
+            checks,
+            [Check::MemoryOversubscribed, Check::CoresOversubscribed]
+        );
+        assert!(diags.iter().all(|d| d.severity == Severity::Warning));
+        assert!(diags[0].message.contains("the [node] profile"));
+    }
+
+    #[test]
+    fn resources_fit_node_or_skip_unknown_limits() {
+        let ctx = ctx_with_ints(&[("nprocs", 4), ("mem_total", 4000)]);
+        assert!(check_resources(None, &ctx, &meta(None, &[]), &node(4, 16000)).is_empty());
+        assert!(check_resources(None, &ctx, &meta(None, &[]), &Node::default()).is_empty());
+    }
+
+    #[test]
+    fn resources_reject_mem_that_is_not_an_amount() {
+        let mut ctx = tera::Context::new();
+        ctx.insert("mem", "4 parsecs");
+        let diags = check_resources(None, &ctx, &meta(None, &[]), &Node::default());
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].check, Check::InvalidParameter);
+        assert_eq!(diags[0].severity, Severity::Error);
+        assert!(
+            diags[0].message.contains("unknown memory unit"),
+            "{}",
+            diags[0].message
+        );
+    }
+
+    #[test]
+    fn resources_need_mem_for_derived_keys() {
+        let ctx = ctx_with_ints(&[("nprocs", 4)]);
+        let mut meta = meta(None, &[]);
+        assert!(check_resources(None, &ctx, &meta, &Node::default()).is_empty());
+
+        meta.reads = vec!["mem_safe".into(), "omp_threads".into()];
+        let diags = check_resources(None, &ctx, &meta, &Node::default());
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].check, Check::MissingVariable);
+        assert!(diags[0].message.contains("`mem_safe`, `omp_threads`"));
+        assert!(diags[0].message.contains("[resources] mem"));
+    }
+
+    #[test]
+    fn resources_warn_on_more_xtb_procs_than_atoms() {
+        let mol = make_molecule(water_at(0.0));
+        let mut ctx = ctx_with_ints(&[("nprocs", 8)]);
+        ctx.insert("method", "gfn2-xtb");
+        let diags = check_resources(Some(&mol), &ctx, &meta(None, &[]), &Node::default());
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].check, Check::MoreProcsThanAtoms);
+
+        // DFT scales with basis functions, not atoms
+        ctx.insert("method", "pbe0");
+        assert!(check_resources(Some(&mol), &ctx, &meta(None, &[]), &Node::default()).is_empty());
+        assert_eq!(
+            check_resources(Some(&mol), &ctx, &meta(Some("xtb"), &[]), &Node::default()).len(),
+            1
+        );
+    }
 }
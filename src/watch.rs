@@ -0,0 +1,93 @@
+use log::info;
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+
+/// How often `gedent gen --watch` looks at the watched files.
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Modification time and size of each watched file; `None` if it is missing.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Snapshot(Vec<(PathBuf, Option<(SystemTime, u64)>)>);
+
+impl Snapshot {
+    pub fn take(files: Vec<PathBuf>) -> Self {
+        Self(
+            files
+                .into_iter()
+                .map(|path| {
+                    let stamp = std::fs::metadata(&path)
+                        .and_then(|m| Ok((m.modified()?, m.len())))
+                        .ok();
+                    (path, stamp)
+                })
+                .collect(),
+        )
+    }
+
+    /// Files that were modified, created or removed since the snapshot.
+    pub fn changed(&self) -> Vec<&Path> {
+        let now = Self::take(self.0.iter().map(|(p, _)| p.clone()).collect());
+        self.0
+            .iter()
+            .zip(now.0)
+            .filter(|((_, before), (_, after))| before != after)
+            .map(|((path, _), _)| path.as_path())
+            .collect()
+    }
+}
+
+/// Call `run`, then again every time one of the files listed by `files`
+/// changes. `files` is asked again after each run, since a run can resolve a
+/// different template or config chain. Never returns; stop it with Ctrl-C.
+pub fn watch(mut files: impl FnMut() -> Vec<PathBuf>, mut run: impl FnMut()) -> ! {
+    loop {
+        run();
+        let mut watched = files();
+        watched.sort();
+        watched.dedup();
+        info!(
+            "Watching {} files for changes (Ctrl-C to stop)",
+            watched.len()
+        );
+        let snapshot = Snapshot::take(watched);
+        loop {
+            sleep(POLL_INTERVAL);
+            let changed = snapshot.changed();
+            if !changed.is_empty() {
+                let names: Vec<String> = changed.iter().map(|p| p.display().to_string()).collect();
+                info!("Changed: {}", names.join(", "));
+                // Let editors that write in several steps finish.
+                sleep(POLL_INTERVAL);
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_sees_edits_creation_and_removal() {
+        let dir = tempfile::tempdir().unwrap();
+        let (kept, edited, created) = (
+            dir.path().join("kept"),
+            dir.path().join("edited"),
+            dir.path().join("created"),
+        );
+        std::fs::write(&kept, "a").unwrap();
+        std::fs::write(&edited, "a").unwrap();
+        let snapshot = Snapshot::take(vec![kept.clone(), edited.clone(), created.clone()]);
+        assert!(snapshot.changed().is_empty());
+
+        std::fs::write(&edited, "ab").unwrap();
+        std::fs::write(&created, "a").unwrap();
+        assert_eq!(snapshot.changed(), [edited.as_path(), created.as_path()]);
+
+        let snapshot = Snapshot::take(vec![kept.clone()]);
+        std::fs::remove_file(&kept).unwrap();
+        assert_eq!(snapshot.changed(), [kept.as_path()]);
+    }
+}
@@ -0,0 +1,320 @@
+use crate::config::{Config, ModelConfig};
+use crate::knowledge;
+use crate::software::Software;
+use crate::WORKFLOWS_DIR;
+use color_eyre::eyre::{bail, Report as Error, Result, WrapErr};
+use serde::Deserialize;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use toml::{map::Map, Value};
+
+/// A chain of templates rendered one after another by `gedent workflow gen`.
+///
+/// ```toml
+/// description = "Conformers, then DFT refinement"
+///
+/// [[step]]
+/// template = "orca/goat"
+///
+/// [[step]]
+/// template = "orca/opt"
+/// [step.model]
+/// method = "r2scan-3c"
+/// ```
+///
+/// The top-level `[model]` and `[parameters]` apply to every step; each
+/// step's own tables win over them. Unknown fields are rejected.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Workflow {
+    pub description: Option<String>,
+    #[serde(default)]
+    pub model: ModelConfig,
+    #[serde(default)]
+    pub parameters: Map<String, Value>,
+    #[serde(rename = "step", default)]
+    pub steps: Vec<Step>,
+}
+
+/// One template of a [`Workflow`].
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    /// Template name, short (`opt`) or qualified (`orca/opt`).
+    pub template: String,
+    /// Directory suffix; defaults to the template name without its software.
+    pub name: Option<String>,
+    #[serde(default)]
+    pub model: ModelConfig,
+    #[serde(default)]
+    pub parameters: Map<String, Value>,
+}
+
+impl Workflow {
+    /// Load a workflow from a file path, or by name from the `workflows`
+    /// directory of the gedent home (`name` or `name.toml`).
+    pub fn load(workflow: &str) -> Result<Self, Error> {
+        let path = Self::find_path(workflow)?;
+        let body =
+            read_to_string(&path).wrap_err(format!("Can't read workflow {}", path.display()))?;
+        Self::parse(&body).wrap_err(format!("Can't parse workflow {}", path.display()))
+    }
+
+    fn find_path(workflow: &str) -> Result<PathBuf, Error> {
+        let path = PathBuf::from(workflow);
+        if path.is_file() {
+            return Ok(path);
+        }
+        let dir = Config::gedent_home()?.join(WORKFLOWS_DIR);
+        [dir.join(workflow), dir.join(format!("{workflow}.toml"))]
+            .into_iter()
+            .find(|p| p.is_file())
+            .map_or_else(
+                || {
+                    bail!(
+                        "No workflow file {workflow} and no workflow named \"{workflow}\" in {}.",
+                        dir.display()
+                    )
+                },
+                Ok,
+            )
+    }
+
+    pub fn parse(body: &str) -> Result<Self, Error> {
+        let workflow: Self = toml::from_str(body)?;
+        if workflow.steps.is_empty() {
+            bail!("Workflow has no [[step]] entries");
+        }
+        Ok(workflow)
+    }
+}
+
+impl Step {
+    /// Directory of the step inside the molecule's directory, e.g. `02_opt`.
+    pub fn dir_name(&self, index: usize) -> String {
+        let name = self.name.clone().unwrap_or_else(|| {
+            Path::new(&self.template).file_name().map_or_else(
+                || self.template.clone(),
+                |n| n.to_string_lossy().into_owned(),
+            )
+        });
+        format!("{:02}_{name}", index + 1)
+    }
+
+    /// The workflow's `[model]` with this step's values on top.
+    pub fn model(&self, base: &ModelConfig) -> ModelConfig {
+        let own = self.model.clone();
+        ModelConfig {
+            method: own.method.or_else(|| base.method.clone()),
+            basis_set: own.basis_set.or_else(|| base.basis_set.clone()),
+            charge: own.charge.or(base.charge),
+            mult: own.mult.or(base.mult),
+            dispersion: own.dispersion.or_else(|| base.dispersion.clone()),
+            solvent: own.solvent.or_else(|| base.solvent.clone()),
+            solvation_model: own.solvation_model.or_else(|| base.solvation_model.clone()),
+        }
+    }
+
+    /// The workflow's `[parameters]` with this step's values on top.
+    pub fn parameters(&self, base: &Map<String, Value>) -> Map<String, Value> {
+        let mut parameters = base.clone();
+        parameters.extend(self.parameters.clone());
+        parameters
+    }
+}
+
+/// Files a finished job leaves in its directory that later steps can read.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Products {
+    /// Final geometry as xyz.
+    pub geometry: Option<String>,
+    /// Hessian (ORCA `.hess`).
+    pub hessian: Option<String>,
+    /// Converged orbitals to start from (ORCA `.gbw`).
+    pub guess: Option<String>,
+}
+
+/// What a job of `jobtype` run with `software` writes for molecule `name`.
+/// Only jobs whose output names are fixed by the program are listed; anything
+/// else produces nothing for later steps.
+pub fn products(
+    software: Option<Software>,
+    jobtype: Option<&str>,
+    method: Option<&str>,
+    name: &str,
+) -> Products {
+    let jobtype = jobtype.unwrap_or_default();
+    match software {
+        Some(Software::Orca) => {
+            let geometry = match jobtype {
+                "opt" | "optfreq" | "optts" | "optconstrained" => Some(format!("{name}.xyz")),
+                "goat" => Some(format!("{name}.globalminimum.xyz")),
+                "neb-ts" => Some(format!("{name}_NEB-TS_converged.xyz")),
+                "solvator" => Some(format!("{name}.solvator.xyz")),
+                _ => None,
+            };
+            let hessian =
+                matches!(jobtype, "freq" | "optfreq" | "hessian").then(|| format!("{name}.hess"));
+            // xtb jobs run through ORCA leave no orbitals worth reading.
+            let semiempirical = matches!(jobtype, "goat" | "hessian" | "solvator")
+                || method.is_some_and(knowledge::is_xtb_method);
+            let guess = (!semiempirical).then(|| format!("{name}.gbw"));
+            Products {
+                geometry,
+                hessian,
+                guess,
+            }
+        }
+        Some(Software::Crest) if jobtype == "conformers" => Products {
+            geometry: Some("crest_best.xyz".to_string()),
+            ..Products::default()
+        },
+        _ => Products::default(),
+    }
+}
+
+/// Context keys [`Wiring::insert_into`] adds.
+pub const WIRED_KEYS: [&str; 3] = ["geometry_file", "hessian_file", "guess_file"];
+
+/// Files from earlier steps, as paths relative to the next step's directory.
+///
+/// Inserted into the context as `geometry_file`, `hessian_file` and
+/// `guess_file`; each holds the most recent step that produced one. Orbitals
+/// are only handed to a step of the same program.
+#[derive(Debug, Default)]
+pub struct Wiring {
+    geometry: Option<String>,
+    hessian: Option<String>,
+    guess: Option<(Software, String)>,
+}
+
+impl Wiring {
+    /// Record what the step in sibling directory `dir` produces.
+    pub fn record(&mut self, dir: &str, software: Option<Software>, products: Products) {
+        let relative = |file: String| format!("../{dir}/{file}");
+        if let Some(file) = products.geometry {
+            self.geometry = Some(relative(file));
+        }
+        if let Some(file) = products.hessian {
+            self.hessian = Some(relative(file));
+        }
+        if let (Some(software), Some(file)) = (software, products.guess) {
+            self.guess = Some((software, relative(file)));
+        }
+    }
+
+    /// Whether an earlier step produced a geometry.
+    pub const fn has_geometry(&self) -> bool {
+        self.geometry.is_some()
+    }
+
+    /// Insert the wired files into `parameters` for a step of `software`.
+    /// Values set explicitly in the workflow win.
+    pub fn insert_into(&self, parameters: &mut Map<String, Value>, software: Option<Software>) {
+        let guess = self
+            .guess
+            .as_ref()
+            .filter(|(from, _)| Some(*from) == software)
+            .map(|(_, file)| file);
+        let files = [self.geometry.as_ref(), self.hessian.as_ref(), guess];
+        for (key, value) in WIRED_KEYS.into_iter().zip(files) {
+            if let Some(value) = value {
+                parameters
+                    .entry(key)
+                    .or_insert_with(|| Value::String(value.clone()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REFINE: &str = r#"
+description = "GOAT, then DFT"
+
+[model]
+method = "r2scan-3c"
+
+[[step]]
+template = "orca/goat"
+
+[[step]]
+template = "orca/opt"
+
+[[step]]
+template = "freq"
+name = "hessian"
+
+[[step]]
+template = "orca/sp"
+[step.model]
+method = "wb97m-v"
+basis_set = "def2-tzvp"
+"#;
+
+    #[test]
+    fn parses_steps_with_overrides() {
+        let workflow = Workflow::parse(REFINE).unwrap();
+        assert_eq!(workflow.steps.len(), 4);
+        let dirs: Vec<String> = workflow
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(i, s)| s.dir_name(i))
+            .collect();
+        assert_eq!(dirs, ["01_goat", "02_opt", "03_hessian", "04_sp"]);
+        let model = workflow.steps[3].model(&workflow.model);
+        assert_eq!(model.method.as_deref(), Some("wb97m-v"));
+        assert_eq!(model.basis_set.as_deref(), Some("def2-tzvp"));
+        let model = workflow.steps[1].model(&workflow.model);
+        assert_eq!(model.method.as_deref(), Some("r2scan-3c"));
+    }
+
+    #[test]
+    fn rejects_empty_or_unknown_fields() {
+        assert!(Workflow::parse("description = \"nothing\"").is_err());
+        assert!(Workflow::parse("[[step]]\ntemplate = \"sp\"\ntypo = 1").is_err());
+    }
+
+    #[test]
+    fn orca_products_depend_on_jobtype() {
+        let opt = products(Some(Software::Orca), Some("opt"), Some("pbe0"), "mol");
+        assert_eq!(opt.geometry.as_deref(), Some("mol.xyz"));
+        assert_eq!(opt.hessian, None);
+        assert_eq!(opt.guess.as_deref(), Some("mol.gbw"));
+
+        let freq = products(Some(Software::Orca), Some("freq"), Some("pbe0"), "mol");
+        assert_eq!(freq.geometry, None);
+        assert_eq!(freq.hessian.as_deref(), Some("mol.hess"));
+
+        let goat = products(Some(Software::Orca), Some("goat"), None, "mol");
+        assert_eq!(goat.geometry.as_deref(), Some("mol.globalminimum.xyz"));
+        assert_eq!(goat.guess, None);
+
+        assert_eq!(
+            products(Some(Software::Gaussian), Some("opt"), None, "mol"),
+            Products::default()
+        );
+    }
+
+    #[test]
+    fn wiring_keeps_latest_products_relative_to_next_step() {
+        let mut wiring = Wiring::default();
+        for (dir, jobtype) in [("01_goat", "goat"), ("02_opt", "opt"), ("03_freq", "freq")] {
+            let made = products(Some(Software::Orca), Some(jobtype), Some("pbe0"), "mol");
+            wiring.record(dir, Some(Software::Orca), made);
+        }
+        let mut params = Map::new();
+        params.insert("guess_file".into(), Value::String("mine.gbw".into()));
+        wiring.insert_into(&mut params, Some(Software::Orca));
+        assert_eq!(params["geometry_file"].as_str(), Some("../02_opt/mol.xyz"));
+        assert_eq!(params["hessian_file"].as_str(), Some("../03_freq/mol.hess"));
+        assert_eq!(params["guess_file"].as_str(), Some("mine.gbw"));
+
+        let mut params = Map::new();
+        wiring.insert_into(&mut params, Some(Software::Gaussian));
+        assert!(!params.contains_key("guess_file"));
+    }
+}
@@ -23,6 +23,10 @@ mult = 1
 [resources]
 nprocs = 4
 mem = 1000
+
+[node]
+cores = 16
+mem = "64GB"
 "#;
 
 /// Minimal ORCA SP template used across most tests.
@@ -128,6 +132,22 @@ fn gen_var_override_wins_over_config() {
         .stdout(predicate::str::contains("\"nprocs\": 16"));
 }
 
+#[test]
+fn gen_mem_accepts_units() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    let xyz = workdir.path().join("water.xyz");
+    fs::write(&xyz, WATER_XYZ).unwrap();
+
+    gedent(home.path())
+        .args(["gen", "sp", "--mem", "4GB", "--print"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("%maxcore 4096"));
+}
+
 // ── gen (file output) ─────────────────────────────────────────────────────────
 
 #[test]
@@ -172,6 +192,508 @@ fn gen_multiple_xyz_files_produce_separate_outputs() {
     assert!(workdir.path().join("mol2.inp").exists());
 }
 
+#[test]
+fn gen_refuses_colliding_and_existing_outputs() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    for dir in ["a", "b"] {
+        fs::create_dir(workdir.path().join(dir)).unwrap();
+        fs::write(workdir.path().join(dir).join("water.xyz"), WATER_XYZ).unwrap();
+    }
+
+    gedent(home.path())
+        .args(["gen", "sp", "a/water.xyz", "b/water.xyz"])
+        .current_dir(workdir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("would both write water.inp"));
+    assert!(!workdir.path().join("water.inp").exists());
+
+    fs::write(workdir.path().join("water.inp"), "old").unwrap();
+    gedent(home.path())
+        .args(["gen", "sp", "a/water.xyz"])
+        .current_dir(workdir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Refusing to overwrite"));
+    assert_eq!(
+        fs::read_to_string(workdir.path().join("water.inp")).unwrap(),
+        "old"
+    );
+
+    gedent(home.path())
+        .args(["gen", "sp", "a/water.xyz", "--backup"])
+        .current_dir(workdir.path())
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(workdir.path().join("water.inp.bak")).unwrap(),
+        "old"
+    );
+
+    gedent(home.path())
+        .args(["gen", "sp", "a/water.xyz", "--force"])
+        .current_dir(workdir.path())
+        .assert()
+        .success();
+    assert!(!workdir.path().join("water.inp.bak.1").exists());
+}
+
+#[test]
+fn gen_outdir_and_filename_pattern_build_job_directories() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    fs::write(workdir.path().join("water.xyz"), WATER_XYZ).unwrap();
+    fs::write(
+        workdir.path().join("gedent.toml"),
+        "[gedent]\nfilename_pattern = \"{name}/{template}_{method}.{ext}\"\n",
+    )
+    .unwrap();
+
+    gedent(home.path())
+        .args(["gen", "sp", "water.xyz", "--outdir", "jobs", "--copy-xyz"])
+        .current_dir(workdir.path())
+        .assert()
+        .success();
+
+    let job = workdir.path().join("jobs/water");
+    assert!(fs::read_to_string(job.join("sp_pbe0.inp"))
+        .unwrap()
+        .contains("! pbe0 def2-svp"));
+    assert_eq!(
+        fs::read_to_string(job.join("water.xyz")).unwrap(),
+        WATER_XYZ
+    );
+
+    gedent(home.path())
+        .args([
+            "gen",
+            "sp",
+            "water.xyz",
+            "--method",
+            "b3lyp",
+            "--outdir",
+            "jobs",
+        ])
+        .current_dir(workdir.path())
+        .assert()
+        .success();
+    assert!(job.join("sp_b3lyp.inp").exists());
+}
+
+#[test]
+fn gen_submit_script_matches_input_resources() {
+    let home = setup_gedent_home();
+    fs::create_dir(home.path().join("schedulers")).unwrap();
+    fs::write(
+        home.path().join("schedulers/slurm"),
+        include_str!("../schedulers/slurm"),
+    )
+    .unwrap();
+    let workdir = tempfile::tempdir().unwrap();
+    fs::write(workdir.path().join("water.xyz"), WATER_XYZ).unwrap();
+
+    gedent(home.path())
+        .args(["gen", "sp", "water.xyz", "--submit-script"])
+        .current_dir(workdir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("[scheduler]"));
+
+    fs::write(
+        workdir.path().join("gedent.toml"),
+        "[scheduler]\ntype = \"slurm\"\nwalltime = \"2-00:00\"\nmodules = [\"orca\"]\n",
+    )
+    .unwrap();
+    gedent(home.path())
+        .args(["gen", "sp", "water.xyz", "--submit-script", "--nprocs", "8"])
+        .current_dir(workdir.path())
+        .assert()
+        .success();
+
+    assert!(workdir.path().join("water.inp").exists());
+    let script = fs::read_to_string(workdir.path().join("water.slurm")).unwrap();
+    assert!(script.contains("#SBATCH --ntasks=8"));
+    assert!(script.contains("#SBATCH --mem=8000M"));
+    assert!(script.contains("#SBATCH --time=48:00:00"));
+    assert!(script.contains("module load orca"));
+    assert!(script.contains("\"orca\" water.inp > water.out"));
+
+    // The job runs in its input's directory wherever it is submitted from.
+    gedent(home.path())
+        .args(["gen", "sp", "water.xyz", "--submit-script", "-o", "jobs"])
+        .current_dir(workdir.path())
+        .assert()
+        .success();
+    let script = fs::read_to_string(workdir.path().join("jobs/water.slurm")).unwrap();
+    let jobs = workdir.path().canonicalize().unwrap().join("jobs");
+    assert!(
+        script.contains(&format!("#SBATCH --chdir={}\n", jobs.display())),
+        "{script}"
+    );
+
+    gedent(home.path())
+        .args(["template", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("slurm").not());
+}
+
+#[test]
+fn gen_sweep_writes_every_combination_and_a_manifest() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    fs::write(workdir.path().join("water.xyz"), WATER_XYZ).unwrap();
+
+    gedent(home.path())
+        .args(["gen", "sp", "water.xyz", "-o", "bench"])
+        .args(["--sweep", "method=pbe0,b3lyp", "--sweep", "charge=0,2"])
+        .current_dir(workdir.path())
+        .assert()
+        .success();
+
+    let bench = workdir.path().join("bench");
+    for (method, charge) in [("pbe0", 0), ("pbe0", 2), ("b3lyp", 0), ("b3lyp", 2)] {
+        let input = fs::read_to_string(bench.join(format!("water_{method}_{charge}.inp"))).unwrap();
+        assert!(input.contains(&format!("! {method} def2-svp")), "{input}");
+        assert!(input.contains(&format!("*xyz {charge} 1")), "{input}");
+    }
+    let manifest = fs::read_to_string(bench.join("sp.sweep.json")).unwrap();
+    assert!(manifest.contains("\"file\": \"bench/water_b3lyp_2.inp\""));
+    assert!(manifest.contains("\"method\": \"b3lyp\""));
+
+    gedent(home.path())
+        .args(["gen", "sp", "water.xyz", "--sweep", "charge=zero"])
+        .current_dir(workdir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("`charge` must be an integer"));
+}
+
+#[test]
+fn gen_sweep_over_arrays_names_files_by_their_items() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    fs::write(workdir.path().join("water.xyz"), WATER_XYZ).unwrap();
+    fs::write(
+        home.path().join("templates/orca/scan"),
+        include_str!("../templates/orca/scan"),
+    )
+    .unwrap();
+
+    gedent(home.path())
+        .args(["gen", "orca/scan", "water.xyz"])
+        .args(["--var", "scan_end=2.0", "--var", "scan_steps=5"])
+        .args(["--sweep", "scan_atoms=[1,2],[1,3]"])
+        .current_dir(workdir.path())
+        .assert()
+        .success();
+
+    for (atoms, indices) in [("1-2", "0 1"), ("1-3", "0 2")] {
+        let input = fs::read_to_string(workdir.path().join(format!("water_{atoms}.inp"))).unwrap();
+        assert!(input.contains(&format!("B {indices} = ")), "{input}");
+    }
+}
+
+#[test]
+fn regen_reproduces_inputs_from_manifest() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    fs::create_dir(workdir.path().join("xyz")).unwrap();
+    fs::write(workdir.path().join("xyz/water.xyz"), WATER_XYZ).unwrap();
+
+    gedent(home.path())
+        .args(["gen", "sp", "xyz/water.xyz", "-o", "jobs", "--manifest"])
+        .args(["--method", "b3lyp"])
+        .current_dir(workdir.path())
+        .assert()
+        .success();
+    let input = workdir.path().join("jobs/water.inp");
+    let original = fs::read_to_string(&input).unwrap();
+    let manifest = fs::read_to_string(workdir.path().join("jobs/sp.manifest.json")).unwrap();
+    assert!(manifest.contains("\"path\": \"../xyz/water.xyz\""));
+    assert!(manifest.contains("\"method\": \"b3lyp\""));
+
+    // The config no longer matters: the manifest holds the resolved context.
+    fs::write(home.path().join("gedent.toml"), "").unwrap();
+    fs::remove_file(&input).unwrap();
+    gedent(home.path())
+        .args(["regen", "jobs/sp.manifest.json"])
+        .current_dir(workdir.path())
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&input).unwrap(), original);
+
+    fs::write(
+        home.path().join("templates/orca/sp"),
+        format!("{SP_TEMPLATE}\n%scf maxiter 200 end\n"),
+    )
+    .unwrap();
+    fs::remove_file(&input).unwrap();
+    gedent(home.path())
+        .args(["regen", "jobs/sp.manifest.json"])
+        .current_dir(workdir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("changed since"));
+}
+
+#[test]
+fn gen_incremental_writes_only_changed_inputs() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    for name in ["water", "ice"] {
+        fs::write(workdir.path().join(format!("{name}.xyz")), WATER_XYZ).unwrap();
+    }
+    let gen = |extra: &[&str]| {
+        gedent(home.path())
+            .args(["gen", "sp", "water.xyz", "ice.xyz", "--incremental"])
+            .args(extra)
+            .current_dir(workdir.path())
+            .assert()
+    };
+    let run = |extra: &[&str]| gen(extra).success();
+
+    run(&[]).stdout(predicate::str::contains(
+        "2 created, 0 updated, 0 unchanged",
+    ));
+    let water = workdir.path().join("water.inp");
+    let modified = fs::metadata(&water).unwrap().modified().unwrap();
+
+    fs::write(workdir.path().join("ice.inp"), "edited by hand").unwrap();
+    gen(&[])
+        .failure()
+        .stdout(predicate::str::contains("created").not())
+        .stderr(predicate::str::contains("Refusing to overwrite"));
+    assert_eq!(
+        fs::read_to_string(workdir.path().join("ice.inp")).unwrap(),
+        "edited by hand"
+    );
+    run(&["--backup"]).stdout(predicate::str::contains(
+        "0 created, 1 updated, 1 unchanged",
+    ));
+    assert!(workdir.path().join("ice.inp.bak").exists());
+    assert_eq!(fs::metadata(&water).unwrap().modified().unwrap(), modified);
+    assert!(fs::read_to_string(workdir.path().join("ice.inp"))
+        .unwrap()
+        .contains("! pbe0"));
+
+    run(&["--nprocs", "2", "--dry-run"]).stdout(predicate::str::contains(
+        "dry-run: 0 created, 2 updated, 0 unchanged",
+    ));
+    assert_eq!(fs::metadata(&water).unwrap().modified().unwrap(), modified);
+
+    // Inputs gedent generated itself are replaced without --force.
+    run(&["--nprocs", "2"]).stdout(predicate::str::contains(
+        "0 created, 2 updated, 0 unchanged",
+    ));
+    run(&[]).stdout(predicate::str::contains(
+        "0 created, 2 updated, 0 unchanged",
+    ));
+}
+
+#[test]
+fn gen_watch_renders_again_when_the_template_changes() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    fs::write(workdir.path().join("water.xyz"), WATER_XYZ).unwrap();
+    let template = home.path().join("templates/orca/sp");
+    fs::write(&template, "first {{ method }}\n").unwrap();
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("gedent"))
+        .args(["gen", "sp", "water.xyz", "--watch", "--print"])
+        .env("GEDENT_HOME", home.path())
+        .current_dir(workdir.path())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(2));
+    fs::write(&template, "second {{ method }}\n").unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(2));
+    child.kill().unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("first pbe0"), "{stdout}");
+    assert!(stdout.contains("second pbe0"), "{stdout}");
+}
+
+#[test]
+fn gen_watch_rewrites_its_own_files_but_not_edited_ones() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    fs::write(workdir.path().join("water.xyz"), WATER_XYZ).unwrap();
+    fs::write(workdir.path().join("ice.xyz"), WATER_XYZ).unwrap();
+    fs::write(workdir.path().join("ice.inp"), "edited by hand").unwrap();
+    let template = home.path().join("templates/orca/sp");
+    fs::write(&template, "first {{ method }}\n").unwrap();
+
+    let watch = |xyz: &str| {
+        std::process::Command::new(assert_cmd::cargo::cargo_bin("gedent"))
+            .args(["gen", "sp", xyz, "--watch"])
+            .env("GEDENT_HOME", home.path())
+            .current_dir(workdir.path())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .unwrap()
+    };
+    let (mut water, mut ice) = (watch("water.xyz"), watch("ice.xyz"));
+    std::thread::sleep(std::time::Duration::from_secs(2));
+    fs::write(&template, "second {{ method }}\n").unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(2));
+    water.kill().unwrap();
+    ice.kill().unwrap();
+
+    let read = |name: &str| fs::read_to_string(workdir.path().join(name)).unwrap();
+    assert_eq!(read("water.inp"), "second pbe0\n");
+    assert_eq!(read("ice.inp"), "edited by hand");
+}
+
+#[test]
+fn restart_uses_last_geometry_and_orbitals_of_failed_job() {
+    let home = setup_gedent_home();
+    fs::write(
+        home.path().join("templates/orca/sp"),
+        include_str!("../templates/orca/sp"),
+    )
+    .unwrap();
+    let workdir = tempfile::tempdir().unwrap();
+    let output = "\
+                                 * O   R   C   A *
+|  1> ! PBE0 def2-SVP
+---------------------------------
+CARTESIAN COORDINATES (ANGSTROEM)
+---------------------------------
+  O      0.000000    0.000000    0.120000
+  H      0.000000    0.760000   -0.480000
+  H      0.000000   -0.760000   -0.480000
+
+ Total Charge           Charge          ....    0
+ Multiplicity           Mult            ....    1
+ SCF NOT CONVERGED AFTER 125 CYCLES
+";
+    fs::write(workdir.path().join("water.out"), output).unwrap();
+    fs::write(workdir.path().join("water.gbw"), "").unwrap();
+
+    gedent(home.path())
+        .args(["restart", "water.out"])
+        .current_dir(workdir.path())
+        .assert()
+        .success();
+    let input = fs::read_to_string(workdir.path().join("water_restart.inp")).unwrap();
+    assert!(input.contains("%moinp \"water.gbw\""), "{input}");
+    assert!(input.contains("! SlowConv"), "{input}");
+    assert!(input.contains("H       0.00000000    0.76000000   -0.48000000"));
+    assert!(workdir.path().join("water_restart.xyz").exists());
+
+    fs::write(
+        workdir.path().join("done.out"),
+        output.replace(
+            " SCF NOT CONVERGED AFTER 125 CYCLES",
+            "****ORCA TERMINATED NORMALLY****",
+        ),
+    )
+    .unwrap();
+    gedent(home.path())
+        .args(["restart", "done.out"])
+        .current_dir(workdir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("nothing to restart"));
+}
+
+// ── status ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn status_classifies_jobs_by_their_outputs() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    let orca = "                                 * O   R   C   A *\n|  1> ! PBE0 def2-SVP\n";
+    for (name, output) in [
+        (
+            "done",
+            Some(format!("{orca}****ORCA TERMINATED NORMALLY****\n")),
+        ),
+        (
+            "scf",
+            Some(format!("{orca} SCF NOT CONVERGED AFTER 125 CYCLES\n")),
+        ),
+        ("busy", Some(orca.to_string())),
+        ("queued", None),
+    ] {
+        fs::write(workdir.path().join(format!("{name}.inp")), "").unwrap();
+        if let Some(output) = output {
+            fs::write(workdir.path().join(format!("{name}.out")), output).unwrap();
+        }
+    }
+
+    gedent(home.path())
+        .arg("status")
+        .current_dir(workdir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"done\.inp\s+done\.out\s+finished").unwrap())
+        .stdout(
+            predicate::str::is_match(r"scf\.inp\s+scf\.out\s+failed\s+SCF did not converge")
+                .unwrap(),
+        )
+        .stdout(predicate::str::is_match(r"busy\.inp\s+busy\.out\s+running").unwrap())
+        .stdout(predicate::str::is_match(r"queued\.inp\s+pending").unwrap())
+        .stdout(predicate::str::contains(
+            "1 finished, 1 failed, 1 running, 1 pending",
+        ));
+}
+
+// ── results ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn results_reports_relative_energies_as_csv_and_json() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    let orca = |energy: &str| {
+        format!(
+            "                                 * O   R   C   A *\n\
+             FINAL SINGLE POINT ENERGY      {energy}\n\
+             ****ORCA TERMINATED NORMALLY****\n\
+             TOTAL RUN TIME: 0 days 0 hours 0 minutes 42 seconds 0 msec\n"
+        )
+    };
+    fs::create_dir(workdir.path().join("jobs")).unwrap();
+    fs::write(workdir.path().join("jobs/a.out"), orca("-100.0")).unwrap();
+    fs::write(workdir.path().join("jobs/b.out"), orca("-100.01")).unwrap();
+    fs::write(workdir.path().join("jobs/notes.log"), "not an output").unwrap();
+
+    gedent(home.path())
+        .args(["results", "jobs", "--format", "csv"])
+        .current_dir(workdir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "a,jobs/a.out,orca,-100,,,,,42,6.27509",
+        ))
+        .stdout(predicate::str::contains(
+            "b,jobs/b.out,orca,-100.01,,,,,42,0\n",
+        ))
+        .stdout(predicate::str::contains("notes").not());
+
+    let assert = gedent(home.path())
+        .args(["results", "jobs", "--format", "json", "--reference", "a"])
+        .current_dir(workdir.path())
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+    assert!(json["b"]["relative_kcal_mol"].as_f64().unwrap() < -6.27);
+
+    gedent(home.path())
+        .args(["results", "jobs", "--energy", "gibbs", "--reference", "a"])
+        .current_dir(workdir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("a has no gibbs energy"));
+}
+
 // ── --dry-run ─────────────────────────────────────────────────────────────────
 
 #[test]
@@ -215,6 +737,24 @@ fn gen_show_context_outputs_json_with_model_vars() {
         .stdout(predicate::str::contains("\"nprocs\": 4"));
 }
 
+#[test]
+fn gen_show_context_includes_resolved_resources() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    let xyz = workdir.path().join("water.xyz");
+    fs::write(&xyz, WATER_XYZ).unwrap();
+
+    // TEST_CONFIG: nprocs = 4, mem = 1000 MB per core, ORCA template
+    gedent(home.path())
+        .args(["gen", "sp", "--show-context", "--dry-run"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"mem_total\": 4000"))
+        .stdout(predicate::str::contains("\"mem_safe\": 750"));
+}
+
 // ── validation ────────────────────────────────────────────────────────────────
 
 #[test]
@@ -286,6 +826,314 @@ fn validation_warns_on_superposed_atoms() {
         .stderr(predicate::str::contains("superposed"));
 }
 
+#[test]
+fn validation_policy_from_local_config_and_strict_flag() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    // Two C atoms 0.6 Å apart — a close contact warning by default
+    let xyz = workdir.path().join("dimer.xyz");
+    fs::write(&xyz, "2\nshort\nC  0.0  0.0  0.0\nC  0.6  0.0  0.0\n").unwrap();
+    fs::write(
+        home.path().join("templates/orca/coords"),
+        "{#\nrequires = [\"Molecule\"]\n#}\n{{ print_coords(molecule = Molecule) }}",
+    )
+    .unwrap();
+
+    gedent(home.path())
+        .args(["gen", "coords", "--dry-run", "--strict"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("[G006]"));
+
+    fs::write(
+        workdir.path().join("gedent.toml"),
+        "[validation]\nstrict = true\ndisable = [\"close-contact\"]\n",
+    )
+    .unwrap();
+    gedent(home.path())
+        .args(["gen", "coords", "--dry-run"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("[G006]").not());
+}
+
+#[test]
+fn validation_warns_when_resources_oversubscribe_node() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    let xyz = workdir.path().join("water.xyz");
+    fs::write(
+        &xyz,
+        "3\nwater\nO  0.0  0.0  0.0\nH  0.0  0.757  0.587\nH  0.0 -0.757  0.587\n",
+    )
+    .unwrap();
+    fs::write(
+        workdir.path().join("gedent.toml"),
+        "[node]\ncores = 2\nmem = \"2GB\"\n",
+    )
+    .unwrap();
+
+    gedent(home.path())
+        .args(["gen", "sp", "--dry-run"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("[R001]"))
+        .stderr(predicate::str::contains("[R002]"))
+        .stderr(predicate::str::contains("the [node] profile"));
+}
+
+#[test]
+fn validation_warns_on_drifted_fragment() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    let xyz = workdir.path().join("pair.xyz");
+    fs::write(
+        &xyz,
+        "6\ntwo waters\n\
+         O  0.000  0.000  0.0\nH  0.757  0.586  0.0\nH -0.757  0.586  0.0\n\
+         O 20.000  0.000  0.0\nH 20.757  0.586  0.0\nH 19.243  0.586  0.0\n",
+    )
+    .unwrap();
+
+    gedent(home.path())
+        .args(["check", "sp"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("warning[G007] too-many-fragments"))
+        .stdout(predicate::str::contains("(atoms 4, 5, 6)"));
+
+    fs::write(
+        workdir.path().join("gedent.toml"),
+        "[validation]\nmax_fragments = 2\n",
+    )
+    .unwrap();
+    gedent(home.path())
+        .args(["check", "sp"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("G007").not());
+}
+
+#[test]
+fn validation_hints_at_transition_metal_spin_state() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    let xyz = workdir.path().join("fecl2.xyz");
+    let fecl2 =
+        |comment: &str| format!("3\n{comment}\nFe 0.0 0.0 0.0\nCl 2.2 0.0 0.0\nCl -2.2 0.0 0.0\n");
+
+    fs::write(&xyz, fecl2("iron chloride")).unwrap();
+    gedent(home.path())
+        .args(["check", "sp"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "warning[G010] closed-shell-transition-metal",
+        ))
+        .stdout(predicate::str::contains("try multiplicity 1, 3, 5 or 7"));
+
+    fs::write(&xyz, fecl2("iron chloride, low-spin")).unwrap();
+    gedent(home.path())
+        .args(["check", "sp"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("G010").not());
+}
+
+#[test]
+fn validation_suggests_solvent_on_typo() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    let xyz = workdir.path().join("water.xyz");
+    fs::write(&xyz, WATER_XYZ).unwrap();
+
+    gedent(home.path())
+        .args(["gen", "sp", "--dry-run", "--solvent", "acetonitrle"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("did you mean `acetonitrile`"));
+
+    // Solvents outside the table are only a warning.
+    gedent(home.path())
+        .args(["gen", "sp", "--dry-run", "--solvent", "nitrobenzene"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("S001"));
+}
+
+#[test]
+fn gen_diagnostics_json_on_stdout() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    let xyz = workdir.path().join("water.xyz");
+    fs::write(&xyz, WATER_XYZ).unwrap();
+
+    let output = gedent(home.path())
+        .args(["gen", "sp", "--mult", "2", "--diagnostics-format", "json"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let diags: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(diags[0]["code"], "G001");
+    assert_eq!(diags[0]["name"], "charge-mult-parity");
+    assert!(diags[0]["path"].as_str().unwrap().ends_with("water.xyz"));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Validation failed"));
+
+    // With --print the inputs own stdout, so the findings need a file.
+    gedent(home.path())
+        .args(["gen", "sp", "--print", "--diagnostics-format", "json"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--diagnostics-output"));
+    gedent(home.path())
+        .args(["gen", "sp", "--print", "--diagnostics-format", "json"])
+        .args(["--diagnostics-output", "findings.json"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("*xyz 0 1"));
+    let findings = fs::read_to_string(workdir.path().join("findings.json")).unwrap();
+    let diags: serde_json::Value = serde_json::from_str(&findings).unwrap();
+    assert!(diags.is_array());
+}
+
+#[test]
+fn gen_rejects_rendered_input_with_syntax_errors() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    let xyz = workdir.path().join("water.xyz");
+    fs::write(&xyz, WATER_XYZ).unwrap();
+    // The %pal block lost its `end`.
+    fs::write(
+        home.path().join("templates/orca/sp"),
+        SP_TEMPLATE.replace("nprocs {{ nprocs }}\nend", "nprocs {{ nprocs }}"),
+    )
+    .unwrap();
+
+    gedent(home.path())
+        .args(["gen", "sp"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "[I001] `%pal` block opened on line 4 is not closed by `end`",
+        ));
+    assert!(!workdir.path().join("water.inp").exists());
+
+    gedent(home.path())
+        .args(["check", "sp", "--diagnostics-format", "json"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("\"code\": \"I001\""))
+        .stdout(predicate::str::contains("\"path\": \"water.inp\""));
+}
+
+#[test]
+fn check_reports_on_stdout_without_writing() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    let xyz = workdir.path().join("water.xyz");
+    fs::write(&xyz, WATER_XYZ).unwrap();
+
+    gedent(home.path())
+        .args(["check", "sp", "--var", "mult=2"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("error[G001] charge-mult-parity"));
+
+    gedent(home.path())
+        .args(["check", "sp", "--diagnostics-format", "sarif"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"version\": \"2.1.0\""));
+    assert!(!workdir.path().join("water.inp").exists());
+}
+
+// ── workflow ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn workflow_gen_wires_steps_together() {
+    let home = setup_gedent_home();
+    for name in ["opt", "freq"] {
+        let body = match name {
+            "opt" => include_str!("../templates/orca/opt"),
+            _ => include_str!("../templates/orca/freq"),
+        };
+        fs::write(home.path().join("templates/orca").join(name), body).unwrap();
+    }
+    let workdir = tempfile::tempdir().unwrap();
+    fs::write(workdir.path().join("water.xyz"), WATER_XYZ).unwrap();
+    fs::write(
+        workdir.path().join("opt-freq.toml"),
+        r#"
+[[step]]
+template = "orca/opt"
+
+[[step]]
+template = "orca/freq"
+[step.model]
+basis_set = "def2-tzvp"
+"#,
+    )
+    .unwrap();
+
+    gedent(home.path())
+        .args([
+            "workflow",
+            "gen",
+            "opt-freq.toml",
+            "water.xyz",
+            "--outdir",
+            "jobs",
+        ])
+        .current_dir(workdir.path())
+        .assert()
+        .success();
+
+    let mol = workdir.path().join("jobs/water");
+    let opt = fs::read_to_string(mol.join("01_opt/water.inp")).unwrap();
+    assert!(opt.contains("*xyz 0 1"));
+    assert!(mol.join("01_opt/water.xyz").exists());
+
+    let freq = fs::read_to_string(mol.join("02_freq/water.inp")).unwrap();
+    assert!(freq.contains("def2-TZVP"));
+    assert!(freq.contains("*xyzfile 0 1 ../01_opt/water.xyz"));
+    assert!(freq.contains("%moinp \"../01_opt/water.gbw\""));
+    assert!(!mol.join("02_freq/water.xyz").exists());
+}
+
 // ── config subcommand ─────────────────────────────────────────────────────────
 
 #[test]
@@ -331,6 +1179,79 @@ fn template_list_shows_installed_templates() {
         .stdout(predicate::str::contains("sp"));
 }
 
+#[test]
+fn template_lint_reports_typos_and_unknown_functions() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+
+    gedent(home.path())
+        .args(["template", "lint", "sp"])
+        .current_dir(workdir.path())
+        .assert()
+        .success();
+
+    fs::write(
+        home.path().join("templates/orca/broken"),
+        "{#\nrequires = [\"method\", \"charge\"]\n#}\n\
+         ! {{ methd }} {{ charge }}\n{{ print_coord(molecule=Molecule) }}",
+    )
+    .unwrap();
+    gedent(home.path())
+        .args(["template", "lint"])
+        .current_dir(workdir.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("[T002]"))
+        .stdout(predicate::str::contains("did you mean `method`"))
+        .stdout(predicate::str::contains("[T003]"))
+        .stdout(predicate::str::contains(
+            "unknown function `print_coord` (did you mean `print_coords`?)",
+        ));
+}
+
+#[test]
+fn template_params_inject_defaults_and_reject_bad_values() {
+    let home = setup_gedent_home();
+    let workdir = tempfile::tempdir().unwrap();
+    let xyz = workdir.path().join("water.xyz");
+    fs::write(&xyz, WATER_XYZ).unwrap();
+    fs::write(
+        home.path().join("templates/orca/scan"),
+        "{#\nrequires = [\"Molecule\"]\n\
+         [params.scan_atoms]\ntype = \"array\"\nitems = \"int\"\nlength = [2, 4]\n\
+         [params.scan_steps]\ntype = \"int\"\ndefault = 10\ndescription = \"Points along the scan\"\n\
+         #}\n\
+         {% for i in scan_atoms %}{{ i }} {% endfor %}steps {{ scan_steps }}",
+    )
+    .unwrap();
+
+    gedent(home.path())
+        .args(["gen", "scan", "--print", "--var", "scan_atoms=[1, 2]"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 2 steps 10"));
+
+    gedent(home.path())
+        .args(["check", "scan", "--var", "scan_atoms=[1, 2, 3, 4, 5]"])
+        .arg(&xyz)
+        .current_dir(workdir.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "parameter `scan_atoms` must have between 2 and 4 items, got 5",
+        ));
+
+    gedent(home.path())
+        .args(["template", "print", "scan", "--info"])
+        .current_dir(workdir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("array of int"))
+        .stdout(predicate::str::contains("Points along the scan"));
+}
+
 #[test]
 fn template_print_shows_template_source() {
     let home = setup_gedent_home();